@@ -0,0 +1,309 @@
+//! `minidebug` executes a MiniRust program stored in a file step by step, reading commands from
+//! stdin, so that one can watch how the program reaches Undefined Behavior.
+//!
+//! Usage: `minidebug [--tree-borrows | --stacked-borrows] [--seed=N] FILE`
+//!
+//! The file is loaded like by `minirun`. Type `help` for the list of commands.
+//! Threads are picked with a seeded pseudo-random generator (seed 0 unless `--seed` is given),
+//! so that a session can be repeated with the same commands.
+
+use miniutil::debug::{Breakpoint, Debugger, Stop};
+use miniutil::fmt::fmt_ill_formed;
+use miniutil::json::program_from_json;
+use miniutil::parse::parse_program;
+use miniutil::schedule::Schedule;
+use miniutil::*;
+
+use std::io::{BufRead, IsTerminal, Write};
+
+/// The session ended normally.
+const EXIT_SUCCESS: i32 = 0;
+/// The command-line arguments were invalid, or the program could not be loaded.
+const EXIT_USAGE: i32 = 2;
+/// The program is not well-formed.
+const EXIT_ILL_FORMED: i32 = 3;
+/// Setting up the machine for the program failed in one of the ways below.
+/// These are the same exit codes that `minirun` uses.
+const EXIT_UB: i32 = 4;
+const EXIT_DEADLOCK: i32 = 5;
+const EXIT_MEMORY_LEAK: i32 = 6;
+const EXIT_ABORT: i32 = 101;
+
+const USAGE: &str = "Usage: minidebug [--tree-borrows | --stacked-borrows] [--seed=N] FILE";
+
+const HELP: &str = "\
+Commands:
+  step [N], s [N]        execute N steps (default 1)
+  next, n                execute until the current thread is done with its statement or terminator
+  continue, c            execute until a breakpoint is hit or the program terminates
+  break FN [BB], b       stop when a thread reaches function FN or its block BB, e.g. `break f1 bb2`
+  breakpoints            list the breakpoints
+  delete N               remove breakpoint N
+  where, w               show the stack of the current thread
+  locals [FRAME], l      show the locals of a frame of the current thread (default 0, the innermost)
+  memory, m              show the live allocations
+  threads                list the threads
+  thread N               inspect thread N, and let it execute all steps while it is enabled
+  help, h                show this message
+  quit, q                end the session";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MemoryModel {
+    Basic,
+    TreeBorrows,
+    StackedBorrows,
+}
+
+struct Options {
+    file: String,
+    memory_model: MemoryModel,
+    seed: u64,
+}
+
+fn show_error(msg: &impl std::fmt::Display, code: i32) -> ! {
+    eprintln!("fatal error: {msg}");
+    std::process::exit(code)
+}
+
+macro_rules! show_error {
+    ($code:expr, $($tt:tt)*) => { crate::show_error(&format_args!($($tt)*), $code) };
+}
+
+fn main() {
+    let opts = parse_args(std::env::args().skip(1));
+    let prog = load_program(&opts.file);
+
+    match opts.memory_model {
+        MemoryModel::Basic => debug::<BasicMem>(prog, opts.seed),
+        MemoryModel::TreeBorrows => debug::<TreeBorrowMem>(prog, opts.seed),
+        MemoryModel::StackedBorrows => debug::<StackedBorrowMem>(prog, opts.seed),
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Options {
+    let mut file = None;
+    let mut memory_model = MemoryModel::Basic;
+    let mut seed = 0;
+
+    for arg in args {
+        if let Some(n) = arg.strip_prefix("--seed=") {
+            seed = n.parse().unwrap_or_else(|_| show_error!(EXIT_USAGE, "invalid seed `{n}`"));
+            continue;
+        }
+        match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                std::process::exit(EXIT_SUCCESS);
+            }
+            "--tree-borrows" | "--stacked-borrows" if memory_model != MemoryModel::Basic =>
+                show_error!(EXIT_USAGE, "only one memory model can be selected\n{USAGE}"),
+            "--tree-borrows" => memory_model = MemoryModel::TreeBorrows,
+            "--stacked-borrows" => memory_model = MemoryModel::StackedBorrows,
+            _ if arg.starts_with('-') => show_error!(EXIT_USAGE, "unknown option `{arg}`\n{USAGE}"),
+            _ if file.is_some() => show_error!(EXIT_USAGE, "more than one input file\n{USAGE}"),
+            _ => file = Some(arg),
+        }
+    }
+
+    let Some(file) = file else {
+        show_error!(EXIT_USAGE, "no input file\n{USAGE}");
+    };
+    Options { file, memory_model, seed }
+}
+
+/// Reads and parses the program stored in `file`.
+fn load_program(file: &str) -> Program {
+    let src = std::fs::read_to_string(file)
+        .unwrap_or_else(|err| show_error!(EXIT_USAGE, "cannot read `{file}`: {err}"));
+    let prog = if file.ends_with(".json") {
+        program_from_json(&src).map_err(|err| err.to_string())
+    } else {
+        parse_program(&src).map_err(|err| err.to_string())
+    };
+    prog.unwrap_or_else(|err| show_error!(EXIT_USAGE, "{file}: {err}"))
+}
+
+fn debug<M: Memory>(prog: Program, seed: u64) {
+    let out = std::io::stdout();
+    let err = std::io::stderr();
+    // Setting up the machine checks that the program is well-formed, and allocates the globals,
+    // functions and vtables and the thread-local globals of the start thread, which can fail.
+    let dbg = Debugger::<M>::new(prog, Schedule::Seeded(seed), out, err);
+    let mut dbg = dbg.unwrap_or_else(|info| {
+        match info {
+            TerminationInfo::IllFormed(err) =>
+                show_error!(
+                    EXIT_ILL_FORMED,
+                    "program not well-formed:\n    {}",
+                    fmt_ill_formed(prog, err)
+                ),
+            TerminationInfo::Ub(ub) => show_error!(EXIT_UB, "UB: {ub}"),
+            TerminationInfo::Abort(err) => show_error!(EXIT_ABORT, "Panic: {}", err.get_internal()),
+            TerminationInfo::Deadlock => show_error!(EXIT_DEADLOCK, "program dead-locked"),
+            TerminationInfo::MemoryLeak => show_error!(EXIT_MEMORY_LEAK, "program leaked memory"),
+            TerminationInfo::MachineStop => std::process::exit(EXIT_SUCCESS),
+        }
+    });
+    show_location(&mut dbg);
+
+    let interactive = std::io::stdin().is_terminal();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("(minidebug) ");
+            std::io::stdout().flush().unwrap();
+        }
+        let Some(line) = lines.next() else { break };
+        let line = line.unwrap_or_else(|err| show_error!(EXIT_USAGE, "cannot read stdin: {err}"));
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&cmd, args)) = words.split_first() else { continue };
+        if matches!(cmd, "quit" | "q") {
+            break;
+        }
+        if let Err(err) = run_command(&mut dbg, cmd, args) {
+            println!("error: {err}");
+        }
+    }
+}
+
+fn run_command<M: Memory>(dbg: &mut Debugger<M>, cmd: &str, args: &[&str]) -> Result<(), String> {
+    match (cmd, args) {
+        ("step" | "s", [] | [_]) => {
+            let count: usize = match args {
+                [n] => parse_number(n)?,
+                _ => 1,
+            };
+            check_running(dbg)?;
+            for _ in 0..count {
+                if let Some(stop) = dbg.step() {
+                    show_stop(dbg, stop);
+                    return Ok(());
+                }
+            }
+            show_location(dbg);
+        }
+        ("next" | "n", []) => {
+            check_running(dbg)?;
+            match dbg.next() {
+                Some(stop) => show_stop(dbg, stop),
+                None => show_location(dbg),
+            }
+        }
+        ("continue" | "c", []) => {
+            check_running(dbg)?;
+            let stop = dbg.cont();
+            show_stop(dbg, stop);
+        }
+        ("break" | "b", [func] | [func, _]) => {
+            let func = FnName(Name::from_internal(parse_name(func, "f")?));
+            let block = match args {
+                [_, block] => Some(BbName(Name::from_internal(parse_name(block, "bb")?))),
+                _ => None,
+            };
+            dbg.add_breakpoint(Breakpoint { func, block })?;
+            println!("breakpoint {}: {}", dbg.breakpoints().len() - 1, fmt_breakpoint(func, block));
+        }
+        ("breakpoints", []) =>
+            for (idx, bp) in dbg.breakpoints().iter().enumerate() {
+                println!("breakpoint {idx}: {}", fmt_breakpoint(bp.func, bp.block));
+            },
+        ("delete", [idx]) => {
+            dbg.remove_breakpoint(parse_number(idx)?)?;
+        }
+        ("where" | "w", []) => {
+            let thread = dbg.current_thread();
+            println!("thread {thread}:");
+            for (idx, frame) in dbg.frames(thread).into_iter().enumerate() {
+                println!("  {idx}: {}", dbg.fmt_frame(frame));
+            }
+        }
+        ("locals" | "l", [] | [_]) => {
+            let frame = match args {
+                [n] => parse_number(n)?,
+                _ => 0,
+            };
+            for line in dbg.fmt_locals(dbg.current_thread(), frame)? {
+                println!("{line}");
+            }
+        }
+        ("memory" | "m", []) =>
+            for line in dbg.fmt_allocations() {
+                println!("{line}");
+            },
+        ("threads", []) =>
+            for thread in 0..dbg.thread_count() {
+                let current = if thread == dbg.current_thread() { "* " } else { "  " };
+                let state = match dbg.frames(thread).first() {
+                    None => "terminated".to_string(),
+                    Some(&frame) if dbg.enabled(thread) => dbg.fmt_frame(frame),
+                    Some(&frame) => format!("blocked at {}", dbg.fmt_frame(frame)),
+                };
+                println!("{current}thread {thread}: {state}");
+            },
+        ("thread", [thread]) => {
+            dbg.select_thread(parse_number(thread)?)?;
+            show_location(dbg);
+        }
+        ("help" | "h", []) => println!("{HELP}"),
+        _ => return Err(format!("invalid use of `{cmd}`, see `help`")),
+    }
+    Ok(())
+}
+
+fn check_running<M: Memory>(dbg: &Debugger<M>) -> Result<(), String> {
+    if dbg.terminated() {
+        return Err("the program has terminated".to_string());
+    }
+    Ok(())
+}
+
+/// Show the statement or terminator that the current thread executes next.
+fn show_location<M: Memory>(dbg: &mut Debugger<M>) {
+    let thread = dbg.current_thread();
+    if let Some(&frame) = dbg.frames(thread).first() {
+        println!("thread {thread}: {}", dbg.fmt_frame(frame));
+    }
+}
+
+fn show_stop<M: Memory>(dbg: &mut Debugger<M>, stop: Stop) {
+    match stop {
+        Stop::Breakpoint(thread, bp) => {
+            println!("thread {thread} hit breakpoint {}", fmt_breakpoint(bp.func, bp.block));
+            show_location(dbg);
+        }
+        Stop::Terminated(info) => {
+            let end = match info {
+                TerminationInfo::MachineStop => "the program stopped".to_string(),
+                TerminationInfo::Ub(ub) => format!("UB: {ub}"),
+                TerminationInfo::Abort(msg) => format!("Panic: {}", msg.get_internal()),
+                TerminationInfo::IllFormed(err) =>
+                    format!("ill-formed: {}", err.msg.get_internal()),
+                TerminationInfo::Deadlock => "the program dead-locked".to_string(),
+                TerminationInfo::MemoryLeak => "the program leaked memory".to_string(),
+            };
+            println!("{end}");
+            // Show where the error happened.
+            show_location(dbg);
+        }
+        Stop::Diverged(diverged) => println!("{diverged}"),
+    }
+}
+
+fn fmt_breakpoint(func: FnName, block: Option<BbName>) -> String {
+    let func = func.0.get_internal();
+    match block {
+        Some(block) => format!("f{func} bb{}", block.0.get_internal()),
+        None => format!("f{func}"),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("expected a number, found `{s}`"))
+}
+
+/// Parse a function or block name like `f1` or `bb2`.
+fn parse_name(s: &str, prefix: &str) -> Result<u32, String> {
+    s.strip_prefix(prefix)
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| format!("expected a name like `{prefix}0`, found `{s}`"))
+}
@@ -0,0 +1,49 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs `minidebug` on `file`, feeding it the given commands.
+fn minidebug(file: &str, commands: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_minidebug"))
+        .arg(file)
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/programs"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(commands.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(out: &Output) -> Vec<String> {
+    String::from_utf8(out.stdout.clone()).unwrap().lines().map(str::to_string).collect()
+}
+
+#[test]
+fn step_to_ub() {
+    let out = minidebug("div_by_zero.minirust", "locals\nstep\nlocals\ncontinue\nstep\n");
+    assert_eq!(out.status.code(), Some(0));
+    let lines = stdout(&out);
+    assert_eq!(lines[0], "thread 0: f0 bb0 stmt 0: storage_live(_1);");
+    assert!(lines.contains(&"_0: T0 = ()".to_string()));
+    assert!(lines.contains(&"thread 0: f0 bb0 stmt 1: _1 = 1_u32 / 0_u32;".to_string()));
+    // `_1` is live now, but uninitialized.
+    assert!(lines.contains(&"_1: u32 = <invalid>".to_string()));
+    assert!(lines.contains(&"UB: division by zero".to_string()));
+    assert_eq!(lines.last().unwrap(), "error: the program has terminated");
+}
+
+#[test]
+fn invalid_commands() {
+    let out = minidebug("div_by_zero.minirust", "frobnicate\nbreak f7\nlocals 3\nquit\nstep\n");
+    assert_eq!(out.status.code(), Some(0));
+    let lines = stdout(&out);
+    assert_eq!(
+        lines[1..],
+        [
+            "error: invalid use of `frobnicate`, see `help`",
+            "error: there is no function f7",
+            "error: thread 0 has no frame 3",
+        ]
+    );
+}
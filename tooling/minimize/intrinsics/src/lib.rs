@@ -5,6 +5,7 @@
 
 use std::fmt::Display;
 use std::alloc::{System, Layout, Allocator};
+use std::panic::{self, AssertUnwindSafe};
 use std::ptr::NonNull;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicU32, Ordering};
@@ -22,6 +23,15 @@ pub fn exit() {
     std::process::exit(0);
 }
 
+pub fn abort() -> ! {
+    std::process::abort();
+}
+
+/// Returns whether `try_fn` panicked.
+pub fn catch_unwind(try_fn: fn(*mut u8), data_ptr: *mut u8) -> bool {
+    panic::catch_unwind(AssertUnwindSafe(|| try_fn(data_ptr))).is_err()
+}
+
 pub unsafe fn allocate(size: usize, align: usize) -> *mut u8 {
     let layout = Layout::from_size_align(size, align).unwrap();
     System.allocate(layout).unwrap().as_ptr() as *mut u8
@@ -33,6 +43,18 @@ pub unsafe fn deallocate(ptr: *mut u8, size: usize, align: usize) {
     unsafe { System.deallocate(ptr, layout); }
 }
 
+pub unsafe fn reallocate(ptr: *mut u8, old_size: usize, align: usize, new_size: usize) -> *mut u8 {
+    let ptr = NonNull::new(ptr).unwrap();
+    let old_layout = Layout::from_size_align(old_size, align).unwrap();
+    let new_layout = Layout::from_size_align(new_size, align).unwrap();
+    let new_ptr = if new_size >= old_size {
+        unsafe { System.grow(ptr, old_layout, new_layout) }
+    } else {
+        unsafe { System.shrink(ptr, old_layout, new_layout) }
+    };
+    new_ptr.unwrap().as_ptr() as *mut u8
+}
+
 // This global keeps track of any join handles produced. It is needed
 // because the minirust intrinsic for spawn only returns an integer and
 // the join only takes an integer, so we have to map these integers to `JoinHandles`
@@ -154,3 +176,45 @@ pub unsafe fn atomic_fetch_sub(ptr: *mut u32, delta: u32) -> u32 {
     let atomic = AtomicU32::from_ptr(ptr);
     atomic.fetch_sub(delta, Ordering::SeqCst)
 }
+
+// The `_explicit` variants take the memory ordering(s) of the operation.
+// The orderings must be constants so that they can become part of the MiniRust intrinsic.
+
+pub unsafe fn atomic_store_explicit(ptr: *mut u32, value: u32, order: Ordering) {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.store(value, order);
+}
+
+pub unsafe fn atomic_load_explicit(ptr: *mut u32, order: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.load(order)
+}
+
+pub unsafe fn compare_exchange_explicit(
+    ptr: *mut u32,
+    current: u32,
+    new: u32,
+    success: Ordering,
+    failure: Ordering,
+) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    let res = atomic.compare_exchange(current, new, success, failure);
+    match res {
+        Ok(ret) => ret,
+        Err(ret) => ret,
+    }
+}
+
+pub unsafe fn atomic_fetch_add_explicit(ptr: *mut u32, delta: u32, order: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.fetch_add(delta, order)
+}
+
+pub unsafe fn atomic_fetch_sub_explicit(ptr: *mut u32, delta: u32, order: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.fetch_sub(delta, order)
+}
+
+pub fn fence(order: Ordering) {
+    std::sync::atomic::fence(order);
+}
@@ -3,11 +3,12 @@ use crate::*;
 // Some Rust features are not supported, and are ignored by `minimize`.
 // Those can be found by grepping "IGNORED".
 
-/// A MIR statement becomes either a MiniRust statement or an intrinsic with some arguments, which
-/// then starts a new basic block.
+/// A MIR statement becomes either a MiniRust statement, or an intrinsic or call with some
+/// arguments, which then starts a new basic block.
 enum StatementResult {
     Statement(Statement),
     Intrinsic { intrinsic: IntrinsicOp, destination: PlaceExpr, arguments: List<ValueExpr> },
+    Call { callee: FnName, arguments: List<ArgumentExpr> },
 }
 
 /// A MIR terminator becomes a MiniRust terminator, possibly preceded by a list
@@ -23,37 +24,68 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
     pub fn translate_bb(&mut self, name: BbName, bb: &rs::BasicBlockData<'tcx>) {
         let mut cur_block_name = name;
         let mut cur_block_statements = List::new();
+        let mut cur_block_spans = Vec::new();
         for stmt in bb.statements.iter() {
+            let span = stmt.source_info.span;
             match self.translate_stmt(stmt) {
                 StatementResult::Statement(stmt) => {
                     cur_block_statements.push(stmt);
+                    cur_block_spans.push(span);
                 }
-                StatementResult::Intrinsic { intrinsic, destination, arguments } => {
+                result => {
                     // Generate a fresh bb name.
                     let next_bb = self.fresh_bb_name();
                     // End the current block by jumping to the next one.
-                    let terminator = Terminator::Intrinsic {
-                        intrinsic,
-                        arguments,
-                        ret: destination,
-                        next_block: Some(next_bb),
+                    let terminator = match result {
+                        StatementResult::Intrinsic { intrinsic, destination, arguments } =>
+                            Terminator::Intrinsic {
+                                intrinsic,
+                                arguments,
+                                ret: destination,
+                                next_block: Some(next_bb),
+                                unwind_block: None,
+                            },
+                        StatementResult::Call { callee, arguments } =>
+                            Terminator::Call {
+                                callee: build::fn_ptr(callee),
+                                calling_convention: CallingConvention::Rust,
+                                arguments,
+                                ret: unit_place(),
+                                next_block: Some(next_bb),
+                                unwind_block: None,
+                            },
+                        StatementResult::Statement(_) => unreachable!(),
                     };
                     let cur_block = BasicBlock { statements: cur_block_statements, terminator };
                     let old = self.blocks.insert(cur_block_name, cur_block);
                     assert!(old.is_none()); // make sure we do not overwrite a bb
+                    let spans = BlockSpans { statements: cur_block_spans, terminator: span };
+                    self.block_spans.insert(cur_block_name, spans);
                     // Go on building the next block.
                     cur_block_name = next_bb;
                     cur_block_statements = List::new();
+                    cur_block_spans = Vec::new();
                 }
             }
         }
+        let span = bb.terminator().source_info.span;
         let TerminatorResult { stmts, terminator } = self.translate_terminator(bb.terminator());
         for stmt in stmts.iter() {
             cur_block_statements.push(stmt);
+            cur_block_spans.push(span);
         }
         let cur_block = BasicBlock { statements: cur_block_statements, terminator };
         let old = self.blocks.insert(cur_block_name, cur_block);
         assert!(old.is_none()); // make sure we do not overwrite a bb
+        let spans = BlockSpans { statements: cur_block_spans, terminator: span };
+        self.block_spans.insert(cur_block_name, spans);
+
+        // The remaining blocks without spans were generated for the terminator, e.g. to panic.
+        for (bb_name, block) in self.blocks.iter() {
+            if !self.block_spans.contains_key(&bb_name) {
+                self.block_spans.insert(bb_name, BlockSpans::uniform(block, span));
+            }
+        }
     }
 
     fn translate_stmt(&mut self, stmt: &rs::Statement<'tcx>) -> StatementResult {
@@ -120,8 +152,19 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                             arguments: list![op],
                         };
                     }
-                    rs::NonDivergingIntrinsic::CopyNonOverlapping(_) =>
-                        rs::span_bug!(span, "NonDivergingIntrinsic not supported: {intrinsic:?}"),
+                    rs::NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                        let elem_ty =
+                            copy.src.ty(&self.body, self.tcx).builtin_deref(true).unwrap();
+                        let shim = HeapShim::CopyNonOverlapping(elem_ty);
+                        let arguments = [&copy.src, &copy.dst, &copy.count]
+                            .into_iter()
+                            .map(|op| ArgumentExpr::ByValue(self.translate_operand(op, span)))
+                            .collect();
+                        return StatementResult::Call {
+                            callee: self.cx.get_heap_shim_name(shim, span),
+                            arguments,
+                        };
+                    }
                 }
             }
             rs::StatementKind::PlaceMention(place) => {
@@ -144,8 +187,8 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         let terminator = match &terminator.kind {
             rs::TerminatorKind::Return => Terminator::Return,
             rs::TerminatorKind::Goto { target } => Terminator::Goto(self.bb_name_map[&target]),
-            rs::TerminatorKind::Call { func, target, destination, args, .. } =>
-                return self.translate_call(func, args, destination, target, span),
+            rs::TerminatorKind::Call { func, target, destination, args, unwind, .. } =>
+                return self.translate_call(func, args, destination, target, unwind, span),
             rs::TerminatorKind::SwitchInt { discr, targets } => {
                 let ty = discr.ty(&self.body, self.tcx);
                 let ty = self.translate_ty(ty, span);
@@ -163,12 +206,16 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                             u8_inttype,
                         )
                     }
+                    Type::Char => {
+                        // Same for `char`, which has the representation of a `u32`.
+                        let Type::Int(u32_inttype) = <u32>::get_type() else { unreachable!() };
+                        (build::transmute(discr_op, Type::Int(u32_inttype)), u32_inttype)
+                    }
                     Type::Int(ity) => (discr_op, ity),
-                    // FIXME: add support for switching on `char`
                     _ =>
                         rs::span_bug!(
                             span,
-                            "SwitchInt terminator currently only supports int and bool."
+                            "SwitchInt terminator currently only supports int, bool and char."
                         ),
                 };
 
@@ -185,7 +232,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 Terminator::Switch { value, cases, fallback }
             }
             rs::TerminatorKind::Unreachable => Terminator::Unreachable,
-            rs::TerminatorKind::Assert { cond, expected, target, .. } => {
+            rs::TerminatorKind::Assert { cond, expected, target, unwind, .. } => {
                 let mut condition = self.translate_operand(cond, span);
                 // Check equality of `condition` and `expected`.
                 // We do this by inverting `condition` if `expected` is false
@@ -195,8 +242,18 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 }
 
                 // Create panic block in case of `expected != condition`
+                let unwind_block = self.translate_unwind(unwind);
                 let panic_bb = self.fresh_bb_name();
-                let panic_block = BasicBlock { statements: list![], terminator: build::panic() };
+                let panic_block = BasicBlock {
+                    statements: list![],
+                    terminator: Terminator::Intrinsic {
+                        intrinsic: IntrinsicOp::Panic,
+                        arguments: list![],
+                        ret: unit_place(),
+                        next_block: None,
+                        unwind_block,
+                    },
+                };
                 self.blocks.try_insert(panic_bb, panic_block).unwrap();
 
                 let next_block = self.bb_name_map[target];
@@ -206,24 +263,35 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                     fallback: panic_bb,
                 }
             }
-            rs::TerminatorKind::Drop { place, target, .. } => {
+            rs::TerminatorKind::Drop { place, target, unwind, .. } => {
                 let ty = place.ty(&self.body, self.tcx).ty;
-                let drop_in_place_fn = rs::Instance::resolve_drop_in_place(self.tcx, ty);
                 let place = self.translate_place(place, span);
-                let ptr_to_drop = build::addr_of(place, build::raw_void_ptr_ty());
+
+                let (callee, ptr_to_drop) = if ty.is_trait() {
+                    // Trait objects are dropped through the drop glue in their vtable.
+                    let ptr_ty = rs::Ty::new_mut_ptr(self.tcx, ty);
+                    let ptr_to_drop = build::addr_of(place, self.translate_ty(ptr_ty, span));
+                    let callee = build::vtable_drop_lookup(build::get_metadata(ptr_to_drop));
+                    (callee, build::get_thin_pointer(ptr_to_drop))
+                } else {
+                    let drop_in_place_fn = rs::Instance::resolve_drop_in_place(self.tcx, ty);
+                    let ptr_to_drop = build::addr_of(place, build::raw_void_ptr_ty());
+                    (build::fn_ptr(self.cx.get_fn_name(drop_in_place_fn)), ptr_to_drop)
+                };
 
                 Terminator::Call {
-                    callee: build::fn_ptr(self.cx.get_fn_name(drop_in_place_fn)),
+                    callee,
                     calling_convention: CallingConvention::Rust,
                     arguments: list![ArgumentExpr::ByValue(ptr_to_drop)],
                     ret: unit_place(),
                     next_block: Some(self.bb_name_map[&target]),
+                    unwind_block: self.translate_unwind(unwind),
                 }
             }
+            rs::TerminatorKind::UnwindResume => Terminator::ResumeUnwind,
+            rs::TerminatorKind::UnwindTerminate(_) => build::abort(),
 
-            rs::TerminatorKind::UnwindResume
-            | rs::TerminatorKind::UnwindTerminate(_)
-            | rs::TerminatorKind::TailCall { .. }
+            rs::TerminatorKind::TailCall { .. }
             | rs::TerminatorKind::Yield { .. }
             | rs::TerminatorKind::CoroutineDrop
             | rs::TerminatorKind::FalseEdge { .. }
@@ -236,12 +304,52 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         TerminatorResult { terminator, stmts: List::new() }
     }
 
+    /// Determines where a MiniRust terminator should unwind to, given the unwind action of the MIR terminator.
+    fn translate_unwind(&mut self, unwind: &rs::UnwindAction) -> Option<BbName> {
+        let terminator = match unwind {
+            rs::UnwindAction::Continue => return None,
+            rs::UnwindAction::Cleanup(bb) => return Some(self.bb_name_map[bb]),
+            rs::UnwindAction::Unreachable => Terminator::Unreachable,
+            rs::UnwindAction::Terminate(_) => build::abort(),
+        };
+        // Unwinding must not go on, create a block that ensures this.
+        let bb = self.fresh_bb_name();
+        let block = BasicBlock { statements: list![], terminator };
+        self.blocks.try_insert(bb, block).unwrap();
+        Some(bb)
+    }
+
+    /// Looks up the method with vtable index `idx` through the trait object pointer `receiver` of type `receiver_ty`.
+    /// Returns the function pointer to call and the thin pointer to pass as the receiver.
+    fn translate_virtual_callee(
+        &mut self,
+        receiver: ValueExpr,
+        receiver_ty: rs::Ty<'tcx>,
+        idx: usize,
+        span: rs::Span,
+    ) -> (ValueExpr, ValueExpr) {
+        let Some(dyn_ty) = receiver_ty.builtin_deref(true).filter(|ty| ty.is_trait()) else {
+            rs::span_bug!(span, "virtual call with unsupported receiver type {receiver_ty}");
+        };
+        let trait_name = self.get_trait_name(dyn_ty);
+        let method = self.get_trait_method_name(trait_name, idx);
+
+        // Smart pointer receivers like `Box<dyn Trait>` are turned into raw pointers first.
+        let receiver = build::transmute(
+            receiver,
+            build::raw_ptr_ty(PointerMetaKind::VTablePointer(trait_name)),
+        );
+        let callee = build::vtable_method_lookup(build::get_metadata(receiver), method);
+        (callee, build::get_thin_pointer(receiver))
+    }
+
     fn translate_rs_intrinsic(
         &mut self,
         intrinsic: rs::Instance<'tcx>,
         args: &[rs::Spanned<rs::Operand<'tcx>>],
         destination: &rs::Place<'tcx>,
         target: &Option<rs::BasicBlock>,
+        unwind: &rs::UnwindAction,
         span: rs::Span,
     ) -> TerminatorResult {
         let param_env = rs::ParamEnv::reveal_all();
@@ -262,6 +370,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         arguments: list![],
                         ret: unit_place(),
                         next_block: None,
+                        unwind_block: self.translate_unwind(unwind),
                     }
                 } else {
                     Terminator::Goto(self.bb_name_map[&target.unwrap()])
@@ -279,6 +388,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                             .collect(),
                         ret: self.translate_place(&destination, span),
                         next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                        unwind_block: self.translate_unwind(unwind),
                     },
                 },
             rs::sym::arith_offset => {
@@ -341,6 +451,30 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let terminator = Terminator::Goto(self.bb_name_map[&target.unwrap()]);
                 return TerminatorResult { stmts: list!(stmt), terminator };
             }
+            rs::sym::size_of_val | rs::sym::min_align_of_val => {
+                let pointee = intrinsic.args.type_at(0);
+                let destination = self.translate_place(&destination, span);
+                let ptr = self.translate_operand(&args[0].node, span);
+
+                let layout = self.rs_layout_of(pointee);
+                let val = if layout.is_sized() {
+                    let val = match intrinsic_name {
+                        rs::sym::size_of_val => layout.size().bytes(),
+                        _ => layout.align().abi.bytes(),
+                    };
+                    build::const_int_typed::<usize>(Int::from(val))
+                } else {
+                    let ty = self.translate_ty(pointee, span);
+                    match intrinsic_name {
+                        rs::sym::size_of_val => build::compute_size(ty, build::get_metadata(ptr)),
+                        _ => build::compute_align(ty, build::get_metadata(ptr)),
+                    }
+                };
+
+                let stmt = Statement::Assign { destination, source: val };
+                let terminator = Terminator::Goto(self.bb_name_map[&target.unwrap()]);
+                return TerminatorResult { stmts: list!(stmt), terminator };
+            }
             rs::sym::unlikely | rs::sym::likely => {
                 // FIXME: use the "fallback body" provided in the standard library.
                 let destination = self.translate_place(&destination, span);
@@ -361,39 +495,94 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         args: &[rs::Spanned<rs::Operand<'tcx>>],
         destination: &rs::Place<'tcx>,
         target: &Option<rs::BasicBlock>,
+        unwind: &rs::UnwindAction,
         span: rs::Span,
     ) -> TerminatorResult {
-        // For now we only support calling specific functions, not function pointers.
-        let rs::Operand::Constant(box f1) = func else { panic!() };
-        let rs::mir::Const::Val(_, f2) = f1.const_ else { panic!() };
-        let &rs::TyKind::FnDef(f, substs_ref) = f2.kind() else { panic!() };
         let param_env = rs::ParamEnv::reveal_all();
+        let func_ty = func.ty(&self.body, self.tcx);
+        let (f, substs_ref) = match *func_ty.kind() {
+            rs::TyKind::FnDef(f, substs_ref) => (f, substs_ref),
+            rs::TyKind::FnPtr(sig) => {
+                // A call through a function pointer.
+                let abi =
+                    self.tcx.fn_abi_of_fn_ptr(param_env.and((sig, rs::List::empty()))).unwrap();
+                let rust_call = sig.abi() == rs::Abi::RustCall;
+                let terminator = Terminator::Call {
+                    callee: self.translate_operand(func, span),
+                    calling_convention: translate_calling_convention(abi.conv),
+                    arguments: self.translate_call_args(args, None, rust_call),
+                    ret: self.translate_place(&destination, span),
+                    next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                    unwind_block: self.translate_unwind(unwind),
+                };
+                return TerminatorResult { terminator, stmts: List::new() };
+            }
+            _ => rs::span_bug!(span, "call to value of non-function type {func_ty}"),
+        };
         let instance = rs::Instance::expect_resolve(self.tcx, param_env, f, substs_ref, span);
 
-        if matches!(instance.def, rs::InstanceKind::Intrinsic(_)) {
+        if matches!(instance.def, rs::InstanceKind::Intrinsic(_))
+            && self.heap_shim(instance).is_none()
+        {
             // A Rust intrinsic.
-            return self.translate_rs_intrinsic(instance, args, destination, target, span);
+            return self.translate_rs_intrinsic(instance, args, destination, target, unwind, span);
         }
 
         let terminator = if self.tcx.crate_name(f.krate).as_str() == "intrinsics" {
             // Direct call to a MiniRust intrinsic.
-            let intrinsic = match self.tcx.item_name(f).as_str() {
+            let name = self.tcx.item_name(f);
+            // Atomic operations with explicit memory orderings take them as trailing arguments,
+            // which become part of the intrinsic.
+            let num_orderings = match name.as_str() {
+                "compare_exchange_explicit" => 2,
+                "atomic_store_explicit"
+                | "atomic_load_explicit"
+                | "atomic_fetch_add_explicit"
+                | "atomic_fetch_sub_explicit"
+                | "fence" => 1,
+                _ => 0,
+            };
+            let (args, ordering_args) = args.split_at(args.len() - num_orderings);
+            let orderings: Vec<MemoryOrdering> =
+                ordering_args.iter().map(|x| self.translate_ordering(&x.node, x.span)).collect();
+            let intrinsic = match name.as_str() {
                 "print" => IntrinsicOp::PrintStdout,
                 "eprint" => IntrinsicOp::PrintStderr,
                 "exit" => IntrinsicOp::Exit,
                 "panic" => IntrinsicOp::Panic,
+                "abort" => IntrinsicOp::Abort,
+                "catch_unwind" => IntrinsicOp::CatchUnwind,
                 "allocate" => IntrinsicOp::Allocate,
                 "deallocate" => IntrinsicOp::Deallocate,
+                "reallocate" => IntrinsicOp::Reallocate,
                 "spawn" => IntrinsicOp::Spawn,
                 "join" => IntrinsicOp::Join,
                 "create_lock" => IntrinsicOp::Lock(IntrinsicLockOp::Create),
                 "acquire" => IntrinsicOp::Lock(IntrinsicLockOp::Acquire),
                 "release" => IntrinsicOp::Lock(IntrinsicLockOp::Release),
-                "atomic_store" => IntrinsicOp::AtomicStore,
-                "atomic_load" => IntrinsicOp::AtomicLoad,
-                "compare_exchange" => IntrinsicOp::AtomicCompareExchange,
-                "atomic_fetch_add" => IntrinsicOp::AtomicFetchAndOp(IntBinOp::Add),
-                "atomic_fetch_sub" => IntrinsicOp::AtomicFetchAndOp(IntBinOp::Sub),
+                "atomic_store" => IntrinsicOp::AtomicStore(MemoryOrdering::SeqCst),
+                "atomic_load" => IntrinsicOp::AtomicLoad(MemoryOrdering::SeqCst),
+                "compare_exchange" =>
+                    IntrinsicOp::AtomicCompareExchange {
+                        success: MemoryOrdering::SeqCst,
+                        failure: MemoryOrdering::SeqCst,
+                    },
+                "atomic_fetch_add" =>
+                    IntrinsicOp::AtomicFetchAndOp(IntBinOp::Add, MemoryOrdering::SeqCst),
+                "atomic_fetch_sub" =>
+                    IntrinsicOp::AtomicFetchAndOp(IntBinOp::Sub, MemoryOrdering::SeqCst),
+                "atomic_store_explicit" => IntrinsicOp::AtomicStore(orderings[0]),
+                "atomic_load_explicit" => IntrinsicOp::AtomicLoad(orderings[0]),
+                "compare_exchange_explicit" =>
+                    IntrinsicOp::AtomicCompareExchange {
+                        success: orderings[0],
+                        failure: orderings[1],
+                    },
+                "atomic_fetch_add_explicit" =>
+                    IntrinsicOp::AtomicFetchAndOp(IntBinOp::Add, orderings[0]),
+                "atomic_fetch_sub_explicit" =>
+                    IntrinsicOp::AtomicFetchAndOp(IntBinOp::Sub, orderings[0]),
+                "fence" => IntrinsicOp::AtomicFence(orderings[0]),
                 name => panic!("unsupported MiniRust intrinsic `{}`", name),
             };
             Terminator::Intrinsic {
@@ -401,14 +590,27 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 arguments: args.iter().map(|x| self.translate_operand(&x.node, x.span)).collect(),
                 ret: self.translate_place(&destination, span),
                 next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                unwind_block: self.translate_unwind(unwind),
             }
         } else if is_panic_fn(&instance.to_string()) {
-            // We can't translate this call, it takes a string. As a hack we just ignore the argument.
+            // These functions take the panic message as a `&str`, just like our intrinsic.
             Terminator::Intrinsic {
                 intrinsic: IntrinsicOp::Panic,
-                arguments: list![],
+                arguments: args.iter().map(|x| self.translate_operand(&x.node, x.span)).collect(),
                 ret: unit_place(),
                 next_block: None,
+                unwind_block: self.translate_unwind(unwind),
+            }
+        } else if let Some(shim) = self.heap_shim(instance) {
+            // Calls into the allocator, and to some intrinsics without a MiniRust counterpart,
+            // go to the functions implementing them instead.
+            Terminator::Call {
+                callee: build::fn_ptr(self.cx.get_heap_shim_name(shim, span)),
+                calling_convention: CallingConvention::Rust,
+                arguments: self.translate_call_args(args, None, false),
+                ret: self.translate_place(&destination, span),
+                next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                unwind_block: self.translate_unwind(unwind),
             }
         } else {
             let abi = self
@@ -418,27 +620,125 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 .unwrap();
             let conv = translate_calling_convention(abi.conv);
 
-            let args: List<_> = args
-                .iter()
-                .map(|x| {
-                    match &x.node {
-                        rs::Operand::Move(place) =>
-                            ArgumentExpr::InPlace(self.translate_place(place, x.span)),
-                        op => ArgumentExpr::ByValue(self.translate_operand(op, x.span)),
-                    }
-                })
-                .collect();
+            // For dynamically dispatched calls, we look up the method in the vtable of the receiver,
+            // and pass the receiver as a thin pointer.
+            let (callee, receiver) = if let rs::InstanceKind::Virtual(_, idx) = instance.def {
+                let receiver_ty = args[0].node.ty(&self.body, self.tcx);
+                let receiver = self.translate_operand(&args[0].node, span);
+                let (callee, receiver) =
+                    self.translate_virtual_callee(receiver, receiver_ty, idx, span);
+                (callee, Some(ArgumentExpr::ByValue(receiver)))
+            } else {
+                (build::fn_ptr(self.cx.get_fn_name(instance)), None)
+            };
+
+            let rust_call = self.tcx.fn_sig(f).skip_binder().abi() == rs::Abi::RustCall;
+            let arguments = self.translate_call_args(args, receiver, rust_call);
 
             Terminator::Call {
-                callee: build::fn_ptr(self.cx.get_fn_name(instance)),
+                callee,
                 calling_convention: conv,
-                arguments: args,
+                arguments,
                 ret: self.translate_place(&destination, span),
                 next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                unwind_block: self.translate_unwind(unwind),
             }
         };
         TerminatorResult { terminator, stmts: List::new() }
     }
+
+    /// Translates the arguments of a call. If `receiver` is given, it replaces the first argument.
+    fn translate_call_args(
+        &mut self,
+        args: &[rs::Spanned<rs::Operand<'tcx>>],
+        receiver: Option<ArgumentExpr>,
+        rust_call: bool,
+    ) -> List<ArgumentExpr> {
+        let mut arguments: List<ArgumentExpr> = receiver.into_iter().collect();
+        for (i, x) in args.iter().enumerate().skip(arguments.len().try_to_usize().unwrap()) {
+            if rust_call && i == args.len() - 1 {
+                // The "rust-call" ABI passes the fields of the last argument as separate arguments.
+                let ty = x.node.ty(&self.body, self.tcx);
+                let rs::TyKind::Tuple(fields) = ty.kind() else {
+                    rs::span_bug!(x.span, "\"rust-call\" argument is not a tuple: {ty}");
+                };
+                let Some(place) = x.node.place() else {
+                    rs::span_bug!(x.span, "constant \"rust-call\" arguments are not supported");
+                };
+                let place = self.translate_place(&place, x.span);
+                for field in 0..fields.len() {
+                    arguments.push(ArgumentExpr::ByValue(build::load(build::field(place, field))));
+                }
+                continue;
+            }
+            arguments.push(match &x.node {
+                rs::Operand::Move(place) =>
+                    ArgumentExpr::InPlace(self.translate_place(place, x.span)),
+                op => ArgumentExpr::ByValue(self.translate_operand(op, x.span)),
+            });
+        }
+        arguments
+    }
+
+    /// Translates a `std::sync::atomic::Ordering` argument of a MiniRust intrinsic.
+    /// The ordering must be a constant, but without MIR optimizations it is usually first
+    /// assigned to a temporary, so we also look through locals that are assigned exactly once.
+    fn translate_ordering(&self, op: &rs::Operand<'tcx>, span: rs::Span) -> MemoryOrdering {
+        let name = match op {
+            rs::Operand::Constant(c) => Some(self.const_variant_name(c, span)),
+            rs::Operand::Move(place) | rs::Operand::Copy(place) =>
+                place.as_local().and_then(|local| self.const_variant_assigned_to(local, span)),
+        };
+        let Some(name) = name else {
+            rs::span_bug!(span, "memory orderings of MiniRust intrinsics must be constants");
+        };
+        match name.as_str() {
+            "Relaxed" => MemoryOrdering::Relaxed,
+            "Acquire" => MemoryOrdering::Acquire,
+            "Release" => MemoryOrdering::Release,
+            "AcqRel" => MemoryOrdering::AcqRel,
+            "SeqCst" => MemoryOrdering::SeqCst,
+            name => rs::span_bug!(span, "unknown memory ordering `{name}`"),
+        }
+    }
+
+    /// Returns the name of the variant of the enum constant that is assigned to `local`,
+    /// if that is the only assignment to `local`.
+    fn const_variant_assigned_to(&self, local: rs::Local, span: rs::Span) -> Option<rs::Symbol> {
+        let statements = self.body.basic_blocks.iter().flat_map(|bb| &bb.statements);
+        let mut rvalues = statements.filter_map(|stmt| {
+            match &stmt.kind {
+                rs::StatementKind::Assign(box (lhs, rvalue)) if lhs.as_local() == Some(local) =>
+                    Some(rvalue),
+                _ => None,
+            }
+        });
+        let (Some(rvalue), None) = (rvalues.next(), rvalues.next()) else {
+            return None;
+        };
+        match rvalue {
+            rs::Rvalue::Use(rs::Operand::Constant(c)) => Some(self.const_variant_name(c, span)),
+            rs::Rvalue::Aggregate(box rs::AggregateKind::Adt(def_id, variant_idx, ..), _) =>
+                Some(self.tcx.adt_def(def_id).variant(*variant_idx).name),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the variant of the enum constant `c`.
+    fn const_variant_name(&self, c: &rs::ConstOperand<'tcx>, span: rs::Span) -> rs::Symbol {
+        let ty = c.const_.ty();
+        let rs::TyKind::Adt(adt_def, _) = ty.kind() else {
+            rs::span_bug!(span, "memory ordering of type {ty} is not an enum");
+        };
+        let Ok(val) = c.const_.eval(self.tcx, rs::ParamEnv::reveal_all(), span) else {
+            rs::span_bug!(span, "const-eval failed");
+        };
+        let (ecx, v) =
+            rs::mk_eval_cx_for_const_val(self.tcx.at(span), rs::ParamEnv::reveal_all(), val, ty)
+                .unwrap();
+        let variant = ecx.read_discriminant(&v).unwrap();
+        adt_def.variant(variant).name
+    }
 }
 
 // HACK to skip translating some functions we can't handle yet.
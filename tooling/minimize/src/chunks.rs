@@ -45,6 +45,8 @@ fn mark_used_bytes(ty: Type, markers: &mut [bool]) {
     match ty {
         Type::Int(int_ty) => mark_size(int_ty.size, markers),
         Type::Bool => mark_size(Size::from_bytes_const(1), markers),
+        Type::Char => mark_size(Size::from_bytes_const(4), markers),
+        Type::Float(float_ty) => mark_size(float_ty.size(), markers),
         Type::Ptr(_) => mark_size(DefaultTarget::PTR_SIZE, markers),
         Type::Tuple { fields, .. } =>
             for (offset, ty) in fields {
@@ -75,7 +77,8 @@ fn mark_used_bytes(ty: Type, markers: &mut [bool]) {
             }
             mark_discriminator(discriminator, markers);
         }
-        Type::Slice { .. } => panic!("unsized types cannot be part of unions"),
+        Type::Slice { .. } | Type::TraitObject(..) =>
+            panic!("unsized types cannot be part of unions"),
     }
 }
 
@@ -0,0 +1,100 @@
+use crate::*;
+
+impl<'tcx> Ctxt<'tcx> {
+    /// Returns the function that a non-capturing closure of type `closure_ty` turns into when coerced
+    /// to a function pointer.
+    ///
+    /// rustc uses a shim that takes the (zero-sized) closure as its first argument, relying on the ABI
+    /// to ignore that argument. MiniRust does not ignore any arguments, so instead we generate a
+    /// function that creates the closure itself and then calls the closure body.
+    pub fn get_closure_fn_ptr_name(&mut self, closure_ty: rs::Ty<'tcx>, span: rs::Span) -> FnName {
+        if let Some(fn_name) = self.closure_fn_ptr_map.get(&closure_ty) {
+            return *fn_name;
+        }
+        let fn_name = self.fresh_fn_name();
+        self.closure_fn_ptr_map.insert(closure_ty, fn_name);
+
+        let f = self.translate_closure_fn_ptr(closure_ty, span);
+        self.functions.insert(fn_name, f);
+        fn_name
+    }
+
+    /// Returns the function that a function pointer to `instance` in a constant calls.
+    /// When a closure is coerced to a function pointer during const-eval, this points to the
+    /// `FnOnce::call_once` shim of the closure, which we replace like the coercion at runtime.
+    pub fn get_fn_ptr_name(&mut self, instance: rs::Instance<'tcx>) -> FnName {
+        let rs::InstanceKind::ClosureOnceShim { .. } = instance.def else {
+            return self.get_fn_name(instance);
+        };
+        let closure_ty = instance.args.type_at(0);
+        let rs::TyKind::Closure(def_id, _) = *closure_ty.kind() else {
+            return self.get_fn_name(instance);
+        };
+        self.get_closure_fn_ptr_name(closure_ty, self.tcx.def_span(def_id))
+    }
+
+    fn translate_closure_fn_ptr(&mut self, closure_ty: rs::Ty<'tcx>, span: rs::Span) -> Function {
+        let rs::TyKind::Closure(def_id, args) = *closure_ty.kind() else {
+            rs::span_bug!(span, "closure to fn pointer coercion of non-closure type {closure_ty}");
+        };
+        let closure = args.as_closure();
+        if !closure.upvar_tys().is_empty() {
+            rs::span_bug!(span, "only non-capturing closures can be coerced to fn pointers");
+        }
+        let body = rs::Instance::resolve_closure(self.tcx, def_id, args, closure.kind());
+
+        // The closure signature takes all arguments as one tuple.
+        let sig = self
+            .tcx
+            .normalize_erasing_late_bound_regions(rs::ParamEnv::reveal_all(), closure.sig());
+        let &[args_ty] = sig.inputs() else { unreachable!() };
+        let rs::TyKind::Tuple(arg_tys) = args_ty.kind() else { unreachable!() };
+
+        // The closure body takes the closure in the way its closure kind demands.
+        let re_erased = self.tcx.lifetimes.re_erased;
+        let self_ty = match closure.kind() {
+            rs::ClosureKind::Fn => rs::Ty::new_imm_ref(self.tcx, re_erased, closure_ty),
+            rs::ClosureKind::FnMut => rs::Ty::new_mut_ref(self.tcx, re_erased, closure_ty),
+            rs::ClosureKind::FnOnce => closure_ty,
+        };
+        let closure_mini_ty = self.translate_ty(closure_ty, span);
+
+        // Local `0` is the return local, followed by the arguments and the closure itself.
+        let mut locals = vec![self.translate_ty(sig.output(), span)];
+        for ty in arg_tys.iter() {
+            locals.push(self.translate_ty(ty, span));
+        }
+        let closure_local = locals.len() as u32;
+        locals.push(closure_mini_ty);
+
+        let receiver = match closure.kind() {
+            rs::ClosureKind::FnOnce => build::load(build::local(closure_local)),
+            _ => build::addr_of(build::local(closure_local), self.translate_ty(self_ty, span)),
+        };
+        let mut arguments = list![ArgumentExpr::ByValue(receiver)];
+        for i in 0..arg_tys.len() {
+            arguments.push(ArgumentExpr::ByValue(build::load(build::local(i as u32 + 1))));
+        }
+
+        let b0 = build::block(
+            &[
+                build::storage_live(closure_local),
+                build::assign(build::local(closure_local), build::tuple(&[], closure_mini_ty)),
+            ],
+            Terminator::Call {
+                callee: build::fn_ptr(self.get_fn_name(body)),
+                calling_convention: CallingConvention::Rust,
+                arguments,
+                ret: build::local(0),
+                next_block: Some(BbName(Name::from_internal(1))),
+                unwind_block: None,
+            },
+        );
+        let b1 = build::block(&[], Terminator::Return);
+
+        Function {
+            calling_convention: CallingConvention::Rust,
+            ..build::function(build::Ret::Yes, arg_tys.len(), &locals, &[b0, b1])
+        }
+    }
+}
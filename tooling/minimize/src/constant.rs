@@ -36,24 +36,50 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let val = ecx.read_scalar(&val).unwrap().to_bool().unwrap();
                 ValueExpr::Constant(Constant::Bool(val), ty)
             }
-            Type::Ptr(ptr_ty) => {
-                if let PtrType::FnPtr = ptr_ty {
-                    rs::span_bug!(span, "Function pointers are currently not supported")
-                }
+            Type::Char => {
+                let val = ecx.read_scalar(&val).unwrap().to_char().unwrap();
+                ValueExpr::Constant(Constant::Int(Int::from(u32::from(val))), ty)
+            }
+            Type::Float(float_ty) => {
+                let scalar = ecx.read_scalar(&val).unwrap();
+                let bits = scalar.to_bits(scalar.size()).unwrap();
+                let val = match float_ty {
+                    FloatType::F32 => Float::F32(bits.try_into().unwrap()),
+                    FloatType::F64 => Float::F64(bits.try_into().unwrap()),
+                };
+                ValueExpr::Constant(Constant::Float(val), ty)
+            }
+            Type::Ptr(PtrType::FnPtr) => {
                 let ptr = ecx.read_pointer(&val).unwrap();
-                let (prov, offset) = ptr.into_parts();
-                let c = match prov {
-                    None => {
-                        let addr: Int = offset.bytes_usize().into();
-                        Constant::PointerWithoutProvenance(addr)
-                    }
-                    Some(prov) => {
-                        let alloc_id = prov.alloc_id();
-                        let rel = self.translate_relocation(alloc_id, offset);
-                        Constant::GlobalPointer(rel)
-                    }
+                let (Some(prov), _offset) = ptr.into_parts() else {
+                    rs::span_bug!(span, "function pointer constant without provenance")
+                };
+                let rs::GlobalAlloc::Function { instance, .. } =
+                    self.tcx.global_alloc(prov.alloc_id())
+                else {
+                    rs::span_bug!(span, "function pointer constant does not point to a function")
                 };
-                ValueExpr::Constant(c, ty)
+                build::fn_ptr(self.cx.get_fn_ptr_name(instance))
+            }
+            Type::Ptr(ptr_ty) if ptr_ty.meta_kind() == PointerMetaKind::ElementCount => {
+                // There are no wide pointer constants, so we transmute a (pointer, length) pair.
+                // This is how `&str` literals get translated.
+                let (ptr, len) = ecx.read_immediate(&val).unwrap().to_scalar_pair();
+                let thin_ptr_ty = Type::Ptr(PtrType::Raw { meta_kind: PointerMetaKind::None });
+                let ptr = self.translate_ptr_const(ptr.to_pointer(ecx).unwrap());
+                let len = Int::from(len.to_target_usize(ecx).unwrap());
+                let pair = build::tuple(
+                    &[
+                        ValueExpr::Constant(ptr, thin_ptr_ty),
+                        ValueExpr::Constant(Constant::Int(len), <usize>::get_type()),
+                    ],
+                    ptr_ty.as_wide_pair::<DefaultTarget>().unwrap(),
+                );
+                build::transmute(pair, ty)
+            }
+            Type::Ptr(_) => {
+                let ptr = ecx.read_pointer(&val).unwrap();
+                ValueExpr::Constant(self.translate_ptr_const(ptr), ty)
             }
             Type::Tuple { fields, .. } => {
                 let mut t: List<ValueExpr> = List::new();
@@ -98,6 +124,22 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
             Type::Union { .. } =>
                 rs::span_bug!(span, "Constant Unions are currently not supported!"),
             Type::Slice { .. } => rs::span_bug!(span, "constant slices do not exist!"),
+            Type::TraitObject(..) => rs::span_bug!(span, "constant trait objects do not exist!"),
+        }
+    }
+
+    fn translate_ptr_const(&mut self, ptr: rs::Pointer<Option<rs::CtfeProvenance>>) -> Constant {
+        let (prov, offset) = ptr.into_parts();
+        match prov {
+            None => {
+                let addr: Int = offset.bytes_usize().into();
+                Constant::PointerWithoutProvenance(addr)
+            }
+            Some(prov) => {
+                let alloc_id = prov.alloc_id();
+                let rel = self.translate_relocation(alloc_id, offset);
+                Constant::GlobalPointer(rel)
+            }
         }
     }
 
@@ -126,6 +168,27 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         name
     }
 
+    // Translates a `#[thread_local]` static to a thread-local global,
+    // and adds it to thread_local_map.
+    // This covers `thread_local!` with a `const { }` initializer and a type without drop glue.
+    // The lazy form and thread-locals that need dropping are not supported: they register their
+    // destructor through the weakly linked `__cxa_thread_atexit_impl`, which we cannot translate.
+    pub fn translate_thread_local(&mut self, def_id: rs::DefId) -> GlobalName {
+        if let Some(x) = self.thread_local_map.get(&def_id) {
+            return *x;
+        }
+
+        let name = self.fresh_global_name();
+        self.cx.thread_local_map.insert(def_id, name);
+
+        // Every thread starts out with the value of the initializer.
+        let alloc = self.tcx.eval_static_initializer(def_id).unwrap();
+        self.translate_const_allocation(alloc, name);
+        let global = Global { thread_local: true, ..self.globals.index_at(name) };
+        self.cx.globals.insert(name, global);
+        name
+    }
+
     // adds a Global representing this ConstAllocation, and returns the corresponding GlobalName.
     fn translate_const_allocation(
         &mut self,
@@ -145,27 +208,34 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 *b = None;
             }
         }
-        let relocations = allocation
-            .provenance()
-            .ptrs()
-            .iter()
-            .map(|&(offset, alloc_id)| {
-                // "Note that the bytes of a pointer represent the offset of the pointer.", see https://doc.rust-lang.org/nightly/nightly-rustc/rustc_middle/mir/interpret/struct.Allocation.html
-                // Hence we have to decode them.
-                let start = offset.bytes_usize();
-                let end = start + DefaultTarget::PTR_SIZE.bytes().try_to_usize().unwrap();
-                // Pointer bytes are always initialized, so we can unwrap.
-                let inner_offset = bytes[start..end].iter().map(|x| x.unwrap()).collect();
-                let inner_offset = DefaultTarget::ENDIANNESS.decode(Unsigned, inner_offset);
-                let inner_offset = rs::Size::from_bytes(inner_offset.try_to_usize().unwrap());
-                let relo = self.translate_relocation(alloc_id.alloc_id(), inner_offset);
-
-                let offset = translate_size(offset);
-                (offset, relo)
-            })
-            .collect();
+        let mut relocations = List::new();
+        let mut fn_pointers = List::new();
+        for &(offset, alloc_id) in allocation.provenance().ptrs().iter() {
+            let alloc_id = alloc_id.alloc_id();
+            let offset = translate_size(offset);
+            if let rs::GlobalAlloc::Function { instance, .. } = self.tcx.global_alloc(alloc_id) {
+                // Function pointers do not have an offset, their bytes are just zero.
+                fn_pointers.push((offset, self.cx.get_fn_ptr_name(instance)));
+                continue;
+            }
+            // "Note that the bytes of a pointer represent the offset of the pointer.", see https://doc.rust-lang.org/nightly/nightly-rustc/rustc_middle/mir/interpret/struct.Allocation.html
+            // Hence we have to decode them.
+            let start = offset.bytes().try_to_usize().unwrap();
+            let end = start + DefaultTarget::PTR_SIZE.bytes().try_to_usize().unwrap();
+            // Pointer bytes are always initialized, so we can unwrap.
+            let inner_offset = bytes[start..end].iter().map(|x| x.unwrap()).collect();
+            let inner_offset = DefaultTarget::ENDIANNESS.decode(Unsigned, inner_offset);
+            let inner_offset = rs::Size::from_bytes(inner_offset.try_to_usize().unwrap());
+            relocations.push((offset, self.translate_relocation(alloc_id, inner_offset)));
+        }
         let align = translate_align(allocation.align);
-        let global = Global { bytes: bytes.into_iter().collect(), relocations, align };
+        let global = Global {
+            bytes: bytes.into_iter().collect(),
+            relocations,
+            fn_pointers,
+            align,
+            thread_local: false,
+        };
 
         self.cx.globals.insert(name, global);
     }
@@ -176,7 +246,9 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         let default_global = Global {
             bytes: Default::default(),
             relocations: Default::default(),
+            fn_pointers: Default::default(),
             align: Align::ONE,
+            thread_local: false,
         };
         self.cx.globals.insert(name, default_global);
         name
@@ -27,11 +27,14 @@ impl<'tcx> Ctxt<'tcx> {
                     sref,
                     span,
                 );
+                // The only variant need not be the first one, e.g. in `Result<Infallible, E>`.
+                let discr = adt_def.discriminant_for_variant(self.tcx, *index);
+                let discr_int = int_from_bits(discr.val, discriminant_ty);
                 let variants = [(
-                    Int::ZERO,
+                    discr_int,
                     Variant { ty: Type::Tuple { fields, size, align }, tagger: Map::new() },
                 )];
-                let discriminator = Discriminator::Known(Int::ZERO);
+                let discriminator = Discriminator::Known(discr_int);
                 (variants.into_iter().collect::<Map<Int, Variant>>(), discriminator)
             }
             rs::Variants::Multiple { tag, tag_encoding, tag_field, variants } => {
@@ -24,6 +24,9 @@ pub struct FnCtxt<'cx, 'tcx> {
 
     pub locals: Map<LocalName, Type>,
     pub blocks: Map<BbName, BasicBlock>,
+
+    /// where in the Rust source each of the `blocks` comes from.
+    pub block_spans: HashMap<BbName, BlockSpans>,
 }
 
 impl<'cx, 'tcx> std::ops::Deref for FnCtxt<'cx, 'tcx> {
@@ -64,6 +67,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
             bb_name_map: Default::default(),
             locals: Default::default(),
             blocks: Default::default(),
+            block_spans: Default::default(),
             locals_smir,
             next_bb: 0,
         }
@@ -75,15 +79,11 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         BbName(Name::from_internal(name))
     }
 
-    /// translates a function body.
+    /// translates a function body, and returns the spans of its blocks along with it.
     /// Any fn calls occuring during this translation will be added to the `FnNameMap`.
-    pub fn translate(mut self) -> Function {
+    pub fn translate(mut self) -> (Function, HashMap<BbName, BlockSpans>) {
         // associate names for each mir BB.
         for bb_id in self.body.basic_blocks.indices() {
-            if self.body.basic_blocks[bb_id].is_cleanup {
-                // We don't support unwinding, so we don't translate cleanup blocks.
-                continue;
-            }
             let bb_name = self.fresh_bb_name();
             self.bb_name_map.insert(bb_id, bb_name);
         }
@@ -111,7 +111,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         // this block allocates all "always_storage_live_locals",
         // except for those which are implicitly storage live in Minirust;
         // like the return local and function args.
-        let init_blk = BasicBlock {
+        let mut init_blk = BasicBlock {
             statements: rs::always_storage_live_locals(&self.body)
                 .iter()
                 .map(|loc| self.local_name_map[&loc])
@@ -120,7 +120,33 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 .collect(),
             terminator: Terminator::Goto(self.bb_name_map[&rs::mir::START_BLOCK]),
         };
+
+        // With the "rust-call" ABI, the last argument is passed untupled,
+        // and the function body expects it in its `spread_arg` local.
+        // So we add a local for each field, and assemble the tuple in the init block.
+        let mut spread_args = List::default();
+        if let Some(spread_local) = self.body.spread_arg {
+            let spread_name = self.local_name_map[&spread_local];
+            let spread_ty = self.locals.index_at(spread_name);
+            let Type::Tuple { fields, .. } = spread_ty else {
+                panic!("spread argument is not a tuple")
+            };
+            let mut field_vals = List::default();
+            for (_offset, field_ty) in fields {
+                let field_name = self.locals.len().try_to_usize().unwrap(); // .len() is the next free index
+                let field_name = LocalName(Name::from_internal(field_name as _));
+                self.locals.insert(field_name, field_ty);
+                spread_args.push(field_name);
+                field_vals.push(build::load(build::local_by_name(field_name)));
+            }
+            init_blk.statements.push(Statement::StorageLive(spread_name));
+            init_blk.statements.push(Statement::Assign {
+                destination: build::local_by_name(spread_name),
+                source: ValueExpr::Tuple(field_vals, spread_ty),
+            });
+        }
         self.blocks.insert(init_bb, init_blk);
+        self.block_spans.insert(init_bb, BlockSpans::uniform(init_blk, self.body.span));
 
         // convert MIR BBs to minirust.
         for (id, bb_name) in self.bb_name_map.clone() {
@@ -136,6 +162,13 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         for i in 0..self.body.arg_count {
             let i = i + 1; // this starts counting with 1, as id 0 is the return value of the function.
             let local_name = LocalName(Name::from_internal(i as _));
+            if self.body.spread_arg.is_some_and(|l| self.local_name_map[&l] == local_name) {
+                // The spread argument is replaced by its fields.
+                continue;
+            }
+            args.push(local_name);
+        }
+        for local_name in spread_args {
             args.push(local_name);
         }
 
@@ -148,6 +181,6 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
             calling_convention: translate_calling_convention(self.abi.conv),
         };
 
-        f
+        (f, self.block_spans)
     }
 }
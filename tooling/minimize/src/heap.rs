@@ -0,0 +1,328 @@
+use crate::*;
+
+/// Allocator functions that we do not translate, but implement with the heap intrinsics instead.
+/// Some of them have no body, and the others go through too much library code.
+/// We also implement the Rust intrinsics here that `Vec` and `String` need, but that have no
+/// MiniRust counterpart.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HeapShim<'tcx> {
+    /// `alloc::alloc::exchange_malloc`, which `Box::new` uses.
+    ExchangeMalloc,
+    /// `__rust_alloc`, or `__rust_alloc_zeroed` if `zeroed`.
+    Alloc { zeroed: bool },
+    /// `__rust_dealloc`.
+    Dealloc,
+    /// `__rust_realloc`.
+    Realloc,
+    /// `alloc::alloc::alloc`, or `alloc::alloc::alloc_zeroed` if `zeroed`, where `layout` is the
+    /// type `Layout`. We have to replace these rather than just `__rust_alloc`, since `alloc`
+    /// first reads `__rust_no_alloc_shim_is_unstable`, a foreign static that we cannot translate.
+    LayoutAlloc { zeroed: bool, layout: rs::Ty<'tcx> },
+    /// `alloc::alloc::dealloc`, with the type `Layout`.
+    LayoutDealloc(rs::Ty<'tcx>),
+    /// `alloc::alloc::realloc`, with the type `Layout`.
+    LayoutRealloc(rs::Ty<'tcx>),
+    /// `<Box<T> as Drop>::drop` for the given `T`, which frees the memory of the box.
+    /// By the time it is called, the contents of the box have already been dropped.
+    BoxDrop(rs::Ty<'tcx>),
+    /// `copy_nonoverlapping::<T>` for the given `T`. MIR has it as a statement, not a call.
+    CopyNonOverlapping(rs::Ty<'tcx>),
+    /// The `write_bytes::<T>` intrinsic for the given `T`.
+    WriteBytes(rs::Ty<'tcx>),
+    /// The `ctpop::<T>` intrinsic for the given integer type `T`.
+    Ctpop(rs::Ty<'tcx>),
+}
+
+impl<'tcx> Ctxt<'tcx> {
+    /// Returns which shim replaces calls to `instance`, if any.
+    pub fn heap_shim(&self, instance: rs::Instance<'tcx>) -> Option<HeapShim<'tcx>> {
+        let def_id = instance.def_id();
+        if Some(def_id) == self.tcx.lang_items().exchange_malloc_fn() {
+            return Some(HeapShim::ExchangeMalloc);
+        }
+        if let rs::InstanceKind::Intrinsic(_) = instance.def {
+            return match self.tcx.item_name(def_id) {
+                rs::sym::write_bytes => Some(HeapShim::WriteBytes(instance.args.type_at(0))),
+                rs::sym::ctpop => Some(HeapShim::Ctpop(instance.args.type_at(0))),
+                _ => None,
+            };
+        }
+        if self.tcx.is_foreign_item(def_id) {
+            return match self.tcx.item_name(def_id).as_str() {
+                "__rust_alloc" => Some(HeapShim::Alloc { zeroed: false }),
+                "__rust_alloc_zeroed" => Some(HeapShim::Alloc { zeroed: true }),
+                "__rust_dealloc" => Some(HeapShim::Dealloc),
+                "__rust_realloc" => Some(HeapShim::Realloc),
+                _ => None,
+            };
+        }
+        if let Some(name) = self.alloc_fn_name(def_id) {
+            let sig = self.tcx.fn_sig(def_id).instantiate_identity().skip_binder();
+            return match name.as_str() {
+                "alloc" => Some(HeapShim::LayoutAlloc { zeroed: false, layout: sig.inputs()[0] }),
+                "alloc_zeroed" =>
+                    Some(HeapShim::LayoutAlloc { zeroed: true, layout: sig.inputs()[0] }),
+                "dealloc" => Some(HeapShim::LayoutDealloc(sig.inputs()[1])),
+                "realloc" => Some(HeapShim::LayoutRealloc(sig.inputs()[1])),
+                _ => None,
+            };
+        }
+        let impl_def_id = self.tcx.impl_of_method(def_id)?;
+        if self.tcx.trait_id_of_impl(impl_def_id) == self.tcx.lang_items().drop_trait()
+            && self.tcx.type_of(impl_def_id).instantiate_identity().is_box()
+        {
+            // Only the global allocator is supported, so we ignore the allocator argument.
+            return Some(HeapShim::BoxDrop(instance.args.type_at(0)));
+        }
+        None
+    }
+
+    /// Returns the name of `def_id` if it is a function in the module `alloc::alloc`.
+    fn alloc_fn_name(&self, def_id: rs::DefId) -> Option<rs::Symbol> {
+        if self.tcx.crate_name(def_id.krate).as_str() != "alloc" {
+            return None;
+        }
+        let path = self.tcx.def_path(def_id);
+        let [module, item] = path.data.as_slice() else {
+            return None;
+        };
+        if module.data.get_opt_name()?.as_str() != "alloc" {
+            return None;
+        }
+        item.data.get_opt_name()
+    }
+
+    /// Returns the function implementing `shim`.
+    pub fn get_heap_shim_name(&mut self, shim: HeapShim<'tcx>, span: rs::Span) -> FnName {
+        if let Some(fn_name) = self.heap_shim_map.get(&shim) {
+            return *fn_name;
+        }
+        let fn_name = self.fresh_fn_name();
+        self.heap_shim_map.insert(shim, fn_name);
+
+        let f = self.translate_heap_shim(shim, span);
+        self.functions.insert(fn_name, f);
+        fn_name
+    }
+
+    fn translate_heap_shim(&mut self, shim: HeapShim<'tcx>, span: rs::Span) -> Function {
+        let usize_ty = <usize>::get_type();
+        let ptr_ty = build::raw_void_ptr_ty();
+        let arg = |i: u32| build::load(build::local(i));
+        // A `Layout` is a `size: usize` followed by an `align: Alignment`, which has the
+        // representation of a `usize`.
+        let layout_size_align = |i: u32| {
+            let align = build::load(build::field(build::local(i), 1));
+            (build::load(build::field(build::local(i), 0)), build::transmute(align, usize_ty))
+        };
+        // Allocates `size` bytes into local 0. Only the zeroed allocations run the loop that
+        // writes zeros, using local `idx` as the index.
+        let allocate_blocks = |size, align, zeroed, idx: u32| {
+            let b0 = build::block(
+                &[],
+                build::allocate(size, align, build::local(0), if zeroed { 1 } else { 4 }),
+            );
+            let b1 = build::block(
+                &[
+                    build::storage_live(idx),
+                    build::assign(build::local(idx), build::const_int(0_usize)),
+                ],
+                build::goto(2),
+            );
+            let b2 = build::block(&[], build::if_(build::lt(arg(idx), size), 3, 4));
+            let byte = build::ptr_offset(arg(0), arg(idx), build::InBounds::Yes);
+            let b3 = build::block(
+                &[
+                    build::assign(build::deref(byte, <u8>::get_type()), build::const_int(0_u8)),
+                    build::assign(
+                        build::local(idx),
+                        build::add(arg(idx), build::const_int(1_usize)),
+                    ),
+                ],
+                build::goto(2),
+            );
+            let b4 = build::block(&[], build::return_());
+            [b0, b1, b2, b3, b4]
+        };
+        let f = match shim {
+            HeapShim::ExchangeMalloc => {
+                // fn(size: usize, align: usize) -> *mut u8
+                // Zero-sized boxes do not allocate, they use a dangling pointer instead.
+                let b0 = build::block(
+                    &[],
+                    build::if_(build::eq(arg(1), build::const_int(0_usize)), 1, 2),
+                );
+                let b1 = build::block(
+                    &[build::assign(build::local(0), build::transmute(arg(2), ptr_ty))],
+                    build::return_(),
+                );
+                let b2 = build::block(&[], build::allocate(arg(1), arg(2), build::local(0), 3));
+                let b3 = build::block(&[], build::return_());
+                build::function(
+                    build::Ret::Yes,
+                    2,
+                    &[ptr_ty, usize_ty, usize_ty],
+                    &[b0, b1, b2, b3],
+                )
+            }
+            HeapShim::Alloc { zeroed } => {
+                // fn(size: usize, align: usize) -> *mut u8
+                let blocks = allocate_blocks(arg(1), arg(2), zeroed, 3);
+                let locals = [ptr_ty, usize_ty, usize_ty, usize_ty];
+                build::function(build::Ret::Yes, 2, &locals, &blocks)
+            }
+            HeapShim::Dealloc => {
+                // fn(ptr: *mut u8, size: usize, align: usize)
+                let b0 = build::block(&[], build::deallocate(arg(1), arg(2), arg(3), 1));
+                let b1 = build::block(&[], build::return_());
+                let locals = [<()>::get_type(), ptr_ty, usize_ty, usize_ty];
+                build::function(build::Ret::Yes, 3, &locals, &[b0, b1])
+            }
+            HeapShim::Realloc => {
+                // fn(ptr: *mut u8, old_size: usize, align: usize, new_size: usize) -> *mut u8
+                let b0 = build::block(
+                    &[],
+                    build::reallocate(arg(1), arg(2), arg(3), arg(4), build::local(0), 1),
+                );
+                let b1 = build::block(&[], build::return_());
+                let locals = [ptr_ty, ptr_ty, usize_ty, usize_ty, usize_ty];
+                build::function(build::Ret::Yes, 4, &locals, &[b0, b1])
+            }
+            HeapShim::LayoutAlloc { zeroed, layout } => {
+                // fn(layout: Layout) -> *mut u8
+                let (size, align) = layout_size_align(1);
+                let blocks = allocate_blocks(size, align, zeroed, 2);
+                let locals = [ptr_ty, self.translate_ty(layout, span), usize_ty];
+                build::function(build::Ret::Yes, 1, &locals, &blocks)
+            }
+            HeapShim::LayoutDealloc(layout) => {
+                // fn(ptr: *mut u8, layout: Layout)
+                let (size, align) = layout_size_align(2);
+                let b0 = build::block(&[], build::deallocate(arg(1), size, align, 1));
+                let b1 = build::block(&[], build::return_());
+                let locals = [<()>::get_type(), ptr_ty, self.translate_ty(layout, span)];
+                build::function(build::Ret::Yes, 2, &locals, &[b0, b1])
+            }
+            HeapShim::LayoutRealloc(layout) => {
+                // fn(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8
+                let (size, align) = layout_size_align(2);
+                let b0 = build::block(
+                    &[],
+                    build::reallocate(arg(1), size, align, arg(3), build::local(0), 1),
+                );
+                let b1 = build::block(&[], build::return_());
+                let locals = [ptr_ty, ptr_ty, self.translate_ty(layout, span), usize_ty];
+                build::function(build::Ret::Yes, 3, &locals, &[b0, b1])
+            }
+            HeapShim::BoxDrop(pointee) => {
+                // fn(this: &mut Box<T>)
+                let box_ty = rs::Ty::new_box(self.tcx, pointee);
+                let ref_ty = rs::Ty::new_mut_ref(self.tcx, self.tcx.lifetimes.re_erased, box_ty);
+                let pointee = self.translate_ty(pointee, span);
+                let this = build::load(build::deref(arg(1), self.translate_ty(box_ty, span)));
+                let size = build::compute_size(pointee, build::get_metadata(this));
+                let align = build::compute_align(pointee, build::get_metadata(this));
+                // Like `Box::new`, zero-sized boxes do not own any memory.
+                let b0 =
+                    build::block(&[], build::if_(build::ne(size, build::const_int(0_usize)), 1, 2));
+                let b1 = build::block(
+                    &[],
+                    build::deallocate(build::get_thin_pointer(this), size, align, 2),
+                );
+                let b2 = build::block(&[], build::return_());
+                let locals = [<()>::get_type(), self.translate_ty(ref_ty, span)];
+                build::function(build::Ret::Yes, 1, &locals, &[b0, b1, b2])
+            }
+            HeapShim::CopyNonOverlapping(elem) => {
+                // fn(src: *const T, dst: *mut T, count: usize)
+                // The elements are copied as unions without fields, which keeps all their bytes,
+                // using local 4 as the index. IGNORED: We do not check that the ranges are disjoint.
+                let layout = self.rs_layout_of(elem);
+                let size = translate_size(layout.size());
+                let elem = build::union_ty(&[], size, translate_align(layout.align().abi));
+                let offset = build::mul(arg(4), build::const_int_typed::<usize>(size.bytes()));
+                let elem_at =
+                    |ptr| build::deref(build::ptr_offset(ptr, offset, build::InBounds::Yes), elem);
+                let b0 = build::block(
+                    &[
+                        build::storage_live(4),
+                        build::assign(build::local(4), build::const_int(0_usize)),
+                    ],
+                    build::goto(1),
+                );
+                let b1 = build::block(&[], build::if_(build::lt(arg(4), arg(3)), 2, 3));
+                let b2 = build::block(
+                    &[
+                        build::assign(elem_at(arg(2)), build::load(elem_at(arg(1)))),
+                        build::assign(
+                            build::local(4),
+                            build::add(arg(4), build::const_int(1_usize)),
+                        ),
+                    ],
+                    build::goto(1),
+                );
+                let b3 = build::block(&[], build::return_());
+                let locals = [<()>::get_type(), ptr_ty, ptr_ty, usize_ty, usize_ty];
+                build::function(build::Ret::Yes, 3, &locals, &[b0, b1, b2, b3])
+            }
+            HeapShim::WriteBytes(elem) => {
+                // fn(dst: *mut T, val: u8, count: usize)
+                // Writes the bytes one by one, using local 4 as the index.
+                let size = self.rs_layout_of(elem).size().bytes();
+                let bytes =
+                    build::mul_unchecked(arg(3), build::const_int_typed::<usize>(size.into()));
+                let b0 = build::block(
+                    &[
+                        build::storage_live(4),
+                        build::assign(build::local(4), build::const_int(0_usize)),
+                    ],
+                    build::goto(1),
+                );
+                let b1 = build::block(&[], build::if_(build::lt(arg(4), bytes), 2, 3));
+                let byte = build::ptr_offset(arg(1), arg(4), build::InBounds::Yes);
+                let b2 = build::block(
+                    &[
+                        build::assign(build::deref(byte, <u8>::get_type()), arg(2)),
+                        build::assign(
+                            build::local(4),
+                            build::add(arg(4), build::const_int(1_usize)),
+                        ),
+                    ],
+                    build::goto(1),
+                );
+                let b3 = build::block(&[], build::return_());
+                let locals = [<()>::get_type(), ptr_ty, <u8>::get_type(), usize_ty, usize_ty];
+                build::function(build::Ret::Yes, 3, &locals, &[b0, b1, b2, b3])
+            }
+            HeapShim::Ctpop(int_ty) => {
+                // fn(x: T) -> u32
+                // Clears the lowest set bit of `x` until it is zero, counting the iterations.
+                let int_ty = self.translate_ty(int_ty, span);
+                let Type::Int(int_ty) = int_ty else {
+                    rs::span_bug!(span, "ctpop on non-integer type {int_ty:?}")
+                };
+                let zero = ValueExpr::Constant(Constant::Int(Int::ZERO), Type::Int(int_ty));
+                let one = ValueExpr::Constant(Constant::Int(Int::ONE), Type::Int(int_ty));
+                let b0 = build::block(
+                    &[build::assign(build::local(0), build::const_int(0_u32))],
+                    build::goto(1),
+                );
+                let b1 = build::block(&[], build::if_(build::ne(arg(1), zero), 2, 3));
+                let b2 = build::block(
+                    &[
+                        build::assign(
+                            build::local(1),
+                            build::bit_and(arg(1), build::sub(arg(1), one)),
+                        ),
+                        build::assign(build::local(0), build::add(arg(0), build::const_int(1_u32))),
+                    ],
+                    build::goto(1),
+                );
+                let b3 = build::block(&[], build::return_());
+                let locals = [<u32>::get_type(), Type::Int(int_ty)];
+                build::function(build::Ret::Yes, 1, &locals, &[b0, b1, b2, b3])
+            }
+        };
+        Function { calling_convention: CallingConvention::Rust, ..f }
+    }
+}
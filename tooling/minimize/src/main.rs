@@ -25,10 +25,12 @@ mod rs {
     pub use rustc_middle::span_bug;
     pub use rustc_middle::ty::*;
     pub use rustc_mir_dataflow::storage::always_storage_live_locals;
+    pub use rustc_span::def_id::DefId;
     pub use rustc_span::source_map::Spanned;
-    pub use rustc_span::{sym, Span, DUMMY_SP};
+    pub use rustc_span::{sym, Span, Symbol, DUMMY_SP};
     pub use rustc_target::abi::{self, call::*, Align, FieldIdx, Layout, Size};
     pub use rustc_target::abi::{FieldsShape, TagEncoding, VariantIdx, Variants};
+    pub use rustc_target::spec::abi::Abi;
 
     pub type CompileTimeInterpCx<'tcx> =
         InterpCx<'tcx, rustc_const_eval::const_eval::CompileTimeMachine<'tcx>>;
@@ -39,7 +41,6 @@ pub use rustc_target::abi::HasDataLayout as _;
 
 mod smir {
     pub use rustc_smir::rustc_internal::*;
-    pub use stable_mir::mir::mono::*;
     pub use stable_mir::mir::*;
     pub use stable_mir::ty::*;
 }
@@ -53,11 +54,16 @@ pub use minirust_rs::mem::*;
 pub use minirust_rs::prelude::NdResult;
 pub use minirust_rs::prelude::*;
 
+pub use miniutil::borrows::{BorrowDumper, BorrowFormat, DumpBorrowsAt};
 pub use miniutil::build::{self, unit_place, TypeConv as _};
-pub use miniutil::fmt::dump_program;
+pub use miniutil::explore::{explore, Exploration, ExploreBounds, Outcome};
+pub use miniutil::fmt::{dump_program, fmt_ill_formed};
+pub use miniutil::json::program_to_json;
 pub use miniutil::run::*;
+pub use miniutil::schedule::{Schedule, ScheduleTrace};
 pub use miniutil::BasicMem;
 pub use miniutil::DefaultTarget;
+pub use miniutil::StackedBorrowMem;
 pub use miniutil::TreeBorrowMem;
 
 // Get back some `std` items
@@ -79,16 +85,28 @@ mod rvalue;
 
 mod constant;
 
+mod vtable;
+
+mod closure;
+
+mod heap;
+use heap::*;
+
 mod chunks;
 use chunks::calc_chunks;
 
 mod enums;
 use enums::int_from_bits;
 
+mod spans;
+use spans::*;
+
 // Imports for `main``
 
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
 use std::env::Args;
+use std::hash::BuildHasher;
 
 pub const DEFAULT_ARGS: &[&str] = &[
     // This is the same as Miri's `MIRI_DEFAULT_ARGS`, ensuring we get a MIR with all the UB still present.
@@ -115,22 +133,43 @@ macro_rules! show_error {
 fn main() {
     let (minimize_args, rustc_args) = split_args(std::env::args());
     let dump = minimize_args.iter().any(|x| x == "--minimize-dump");
+    let emit_json = minimize_args.iter().find_map(|x| x.strip_prefix("--minimize-emit-json="));
+    let explore_all = minimize_args.iter().any(|x| x == "--minimize-explore");
+    let show_backtrace = minimize_args.iter().any(|x| x == "--minimize-backtrace");
+    if minimize_args.iter().any(|x| x == "--minimize-tree-borrows")
+        && minimize_args.iter().any(|x| x == "--minimize-stacked-borrows")
+    {
+        show_error!(
+            "`--minimize-tree-borrows` and `--minimize-stacked-borrows` cannot be used together"
+        );
+    }
 
-    get_mini(rustc_args, |_tcx, prog| {
+    get_mini(rustc_args, |tcx, prog, spans| {
+        if let Some(path) = emit_json {
+            if let Err(err) = std::fs::write(path, program_to_json(prog)) {
+                show_error!("could not write `{path}`: {err}");
+            }
+        }
         if dump {
             dump_program(prog);
+        } else if explore_all {
+            explore_prog(prog, &minimize_args);
         } else {
-            match run_prog(prog, &minimize_args) {
+            let (info, backtraces) = run_prog(prog, &minimize_args);
+            let backtrace =
+                if show_backtrace { spans.fmt_backtraces(tcx, &backtraces) } else { String::new() };
+            match info {
                 // We can't use tcx.dcx().fatal due to <https://github.com/oli-obk/ui_test/issues/226>
                 TerminationInfo::IllFormed(err) =>
                     show_error!(
                         "program not well-formed (this is a bug in minimize):\n    {}",
-                        err.get_internal()
+                        fmt_ill_formed(prog, err)
                     ),
                 TerminationInfo::MachineStop => { /* silent exit. */ }
-                TerminationInfo::Abort(err) => show_error!("Panic: {}", err.get_internal()),
-                TerminationInfo::Ub(err) => show_error!("UB: {}", err.get_internal()),
-                TerminationInfo::Deadlock => show_error!("program dead-locked"),
+                TerminationInfo::Abort(err) =>
+                    show_error!("Panic: {}{backtrace}", err.get_internal()),
+                TerminationInfo::Ub(ub) => show_error!("UB: {ub}{backtrace}"),
+                TerminationInfo::Deadlock => show_error!("program dead-locked{backtrace}"),
                 TerminationInfo::MemoryLeak => show_error!("program leaked memory"),
             }
         }
@@ -151,24 +190,138 @@ fn split_args(args: Args) -> (Vec<String>, Vec<String>) {
     (minimize_args, rustc_args)
 }
 
-fn run_prog(prog: Program, args: &Vec<String>) -> TerminationInfo {
+fn run_prog(prog: Program, args: &Vec<String>) -> (TerminationInfo, Vec<Backtrace>) {
+    if let Some((out, format)) = get_borrows_writer(args) {
+        if !args.iter().any(|x| x == "--minimize-tree-borrows") {
+            show_error!("`--minimize-dump-borrows` needs `--minimize-tree-borrows`");
+        }
+        let at = if args.iter().any(|x| x == "--minimize-dump-borrows-every-step") {
+            DumpBorrowsAt::EveryStep
+        } else {
+            DumpBorrowsAt::Ub
+        };
+        let mut dumper = BorrowDumper::new(at, format, out);
+        return run_prog_with::<TreeBorrowMem>(prog, args, Some(&mut dumper));
+    }
     if args.iter().any(|x| x == "--minimize-tree-borrows") {
-        run_program::<TreeBorrowMem>(prog)
+        run_prog_with::<TreeBorrowMem>(prog, args, None)
+    } else if args.iter().any(|x| x == "--minimize-stacked-borrows") {
+        run_prog_with::<StackedBorrowMem>(prog, args, None)
     } else {
-        run_program::<BasicMem>(prog)
+        run_prog_with::<BasicMem>(prog, args, None)
+    }
+}
+
+fn run_prog_with<M: Memory>(
+    prog: Program,
+    args: &Vec<String>,
+    hook: Option<&mut dyn StepHook<M>>,
+) -> (TerminationInfo, Vec<Backtrace>) {
+    let record = args.iter().find_map(|x| x.strip_prefix("--minimize-record-schedule="));
+    let exec_trace = get_trace_writer(args);
+    // Tracing needs to know which thread executes each step, so it also needs a schedule.
+    let schedule = get_schedule(args, record.is_some() || exec_trace.is_some());
+
+    let (info, trace, backtraces) =
+        run_program_with_backtraces::<M>(prog, schedule, exec_trace, hook)
+            .unwrap_or_else(|err| show_error!("{err}"));
+    if let (Some(path), Some(trace)) = (record, trace) {
+        if let Err(err) = std::fs::write(path, trace.to_string()) {
+            show_error!("could not write `{path}`: {err}");
+        }
+    }
+    (info, backtraces)
+}
+
+/// Explore all thread interleavings of the program and print their distinct outcomes.
+fn explore_prog(prog: Program, args: &Vec<String>) {
+    let mut bounds = ExploreBounds::default();
+    if let Some(n) = args.iter().find_map(|x| x.strip_prefix("--minimize-explore-steps=")) {
+        bounds.steps = n.parse().unwrap_or_else(|_| show_error!("invalid step bound `{n}`"));
+    }
+    if let Some(n) = args.iter().find_map(|x| x.strip_prefix("--minimize-explore-preemptions=")) {
+        let n = n.parse().unwrap_or_else(|_| show_error!("invalid preemption bound `{n}`"));
+        bounds.preemptions = Some(n);
+    }
+
+    let exploration: Exploration = if args.iter().any(|x| x == "--minimize-tree-borrows") {
+        explore::<TreeBorrowMem>(prog, bounds)
+    } else if args.iter().any(|x| x == "--minimize-stacked-borrows") {
+        explore::<StackedBorrowMem>(prog, bounds)
+    } else {
+        explore::<BasicMem>(prog, bounds)
+    };
+
+    for outcome in &exploration.outcomes {
+        println!("{outcome}");
+    }
+    println!(
+        "explored {} executions with {} distinct outcomes",
+        exploration.executions,
+        exploration.outcomes.len()
+    );
+    if !exploration.complete {
+        eprintln!("warning: not all interleavings were explored");
+    }
+    if exploration.outcomes.iter().any(|outcome| !matches!(outcome, Outcome::Stop(_))) {
+        show_error!("some interleavings did not terminate normally");
+    }
+}
+
+/// Determine where `--minimize-trace` (to stderr) or `--minimize-trace=<path>` writes the trace.
+fn get_trace_writer(args: &Vec<String>) -> Option<Box<dyn std::io::Write>> {
+    if args.iter().any(|x| x == "--minimize-trace") {
+        return Some(Box::new(std::io::stderr()));
+    }
+    let path = args.iter().find_map(|x| x.strip_prefix("--minimize-trace="))?;
+    let file = std::fs::File::create(path)
+        .unwrap_or_else(|err| show_error!("could not create `{path}`: {err}"));
+    Some(Box::new(std::io::BufWriter::new(file)))
+}
+
+/// Determine where `--minimize-dump-borrows` (to stderr) or `--minimize-dump-borrows=<path>`
+/// writes the Tree Borrows state. Files ending in `.dot` get DOT, everything else gets text.
+fn get_borrows_writer(args: &Vec<String>) -> Option<(Box<dyn std::io::Write>, BorrowFormat)> {
+    if args.iter().any(|x| x == "--minimize-dump-borrows") {
+        return Some((Box::new(std::io::stderr()), BorrowFormat::Text));
+    }
+    let path = args.iter().find_map(|x| x.strip_prefix("--minimize-dump-borrows="))?;
+    let format = if path.ends_with(".dot") { BorrowFormat::Dot } else { BorrowFormat::Text };
+    let file = std::fs::File::create(path)
+        .unwrap_or_else(|err| show_error!("could not create `{path}`: {err}"));
+    Some((Box::new(std::io::BufWriter::new(file)), format))
+}
+
+/// Determine the schedule from `--minimize-replay-schedule=<path>` and `--minimize-seed=<n>`.
+/// Returns `None` if the machine should pick the threads itself.
+fn get_schedule(args: &Vec<String>, record: bool) -> Option<Schedule> {
+    if let Some(path) = args.iter().find_map(|x| x.strip_prefix("--minimize-replay-schedule=")) {
+        let src = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| show_error!("could not read `{path}`: {err}"));
+        let trace = ScheduleTrace::parse(&src).unwrap_or_else(|err| show_error!("{path}: {err}"));
+        return Some(Schedule::Replay(trace));
+    }
+    if let Some(seed) = args.iter().find_map(|x| x.strip_prefix("--minimize-seed=")) {
+        let seed = seed.parse().unwrap_or_else(|_| show_error!("invalid seed `{seed}`"));
+        return Some(Schedule::Seeded(seed));
     }
+    // Recording needs a schedule, so pick a random seed.
+    record.then(|| Schedule::Seeded(RandomState::new().hash_one(())))
 }
 
-fn get_mini(mut args: Vec<String>, callback: impl FnOnce(rs::TyCtxt<'_>, Program) + Send + Copy) {
+fn get_mini(
+    mut args: Vec<String>,
+    callback: impl FnOnce(rs::TyCtxt<'_>, Program, &SpanMap) + Send + Copy,
+) {
     args.splice(1..1, DEFAULT_ARGS.iter().map(ToString::to_string));
     rustc_driver::RunCompiler::new(&args, &mut Cb { callback }).run().unwrap();
 }
 
-struct Cb<F: FnOnce(rs::TyCtxt<'_>, Program) + Send + Copy> {
+struct Cb<F: FnOnce(rs::TyCtxt<'_>, Program, &SpanMap) + Send + Copy> {
     callback: F,
 }
 
-impl<F: FnOnce(rs::TyCtxt<'_>, Program) + Send + Copy> rustc_driver::Callbacks for Cb<F> {
+impl<F: FnOnce(rs::TyCtxt<'_>, Program, &SpanMap) + Send + Copy> rustc_driver::Callbacks for Cb<F> {
     fn after_analysis<'tcx>(
         &mut self,
         _compiler: &rustc_interface::interface::Compiler,
@@ -177,8 +330,8 @@ impl<F: FnOnce(rs::TyCtxt<'_>, Program) + Send + Copy> rustc_driver::Callbacks f
         queries.global_ctxt().unwrap().enter(|tcx| {
             // StableMIR can only be used inside a `run` call, to guarantee its context is properly
             // initialized. Calls to StableMIR functions will panic if done outside a run.
-            let prog = smir::run(tcx, || Ctxt::new(tcx).translate()).unwrap();
-            (self.callback)(tcx, prog);
+            let (prog, spans) = smir::run(tcx, || Ctxt::new(tcx).translate()).unwrap();
+            (self.callback)(tcx, prog, &spans);
         });
 
         rustc_driver::Compilation::Stop
@@ -6,14 +6,36 @@ pub struct Ctxt<'tcx> {
     /// maps Rust function calls to MiniRust FnNames.
     pub fn_name_map: HashMap<rs::Instance<'tcx>, FnName>,
 
+    /// maps closure types to the MiniRust function used when coercing them to a function pointer.
+    pub closure_fn_ptr_map: HashMap<rs::Ty<'tcx>, FnName>,
+
+    /// maps the allocator functions we replace to the MiniRust functions replacing them.
+    pub heap_shim_map: HashMap<HeapShim<'tcx>, FnName>,
+
     /// Stores which AllocId evaluates to which GlobalName.
     pub alloc_map: HashMap<rs::AllocId, GlobalName>,
 
+    /// Stores which thread-local static is translated to which GlobalName.
+    pub thread_local_map: HashMap<rs::DefId, GlobalName>,
+
     pub globals: Map<GlobalName, Global>,
 
     pub functions: Map<FnName, Function>,
 
+    /// Maps the principal trait of a trait object to its MiniRust trait.
+    pub trait_map: HashMap<Option<rs::PolyExistentialTraitRef<'tcx>>, TraitName>,
+
+    pub traits: Map<TraitName, Set<TraitMethodName>>,
+
+    /// Stores which vtable implements a given principal trait for a given type.
+    pub vtable_map: HashMap<(rs::Ty<'tcx>, Option<rs::PolyExistentialTraitRef<'tcx>>), VTableName>,
+
+    pub vtables: Map<VTableName, VTable>,
+
     pub ty_cache: HashMap<rs::Ty<'tcx>, Type>,
+
+    /// where in the Rust source the statements and terminators of the `functions` come from.
+    pub spans: SpanMap,
 }
 
 impl<'tcx> Ctxt<'tcx> {
@@ -42,18 +64,37 @@ impl<'tcx> Ctxt<'tcx> {
                 "{rs_int_ty:?} seem to have the wrong alignment"
             );
         }
+        for (rs_float_ty, float_ty) in
+            [(rs::abi::Float::F32, FloatType::F32), (rs::abi::Float::F64, FloatType::F64)]
+        {
+            assert_eq!(translate_size(rs_float_ty.size()), float_ty.size());
+            assert_eq!(
+                translate_align(rs_float_ty.align(dl).abi),
+                float_ty.align::<DefaultTarget>(),
+                "{rs_float_ty:?} seem to have the wrong alignment"
+            );
+        }
 
         Ctxt {
             tcx,
             fn_name_map: Default::default(),
+            closure_fn_ptr_map: Default::default(),
+            heap_shim_map: Default::default(),
             alloc_map: Default::default(),
+            thread_local_map: Default::default(),
             globals: Default::default(),
             functions: Default::default(),
+            trait_map: Default::default(),
+            traits: Default::default(),
+            vtable_map: Default::default(),
+            vtables: Default::default(),
             ty_cache: Default::default(),
+            spans: Default::default(),
         }
     }
 
-    pub fn translate(mut self) -> Program {
+    /// Translates the program, and returns the spans of its functions along with it.
+    pub fn translate(mut self) -> (Program, SpanMap) {
         let (entry, _ty) = self.tcx.entry_fn(()).unwrap();
         let entry_instance = rs::Instance::mono(self.tcx, entry);
         let entry_name = FnName(Name::from_internal(0));
@@ -66,31 +107,80 @@ impl<'tcx> Ctxt<'tcx> {
             self.fn_name_map.values().find(|k| !self.functions.contains_key(**k)).copied()
         {
             let instance =
-                self.fn_name_map.iter().find(|(_, f)| **f == fn_name).map(|(r, _)| r).unwrap();
+                *self.fn_name_map.iter().find(|(_, f)| **f == fn_name).map(|(r, _)| r).unwrap();
 
-            let f = FnCtxt::new(*instance, &mut self).translate();
+            let (f, blocks) = if self.is_mir_available(instance) {
+                FnCtxt::new(instance, &mut self).translate()
+            } else {
+                self.translate_unavailable_fn(instance)
+            };
             self.functions.insert(fn_name, f);
+            let name = self.tcx.def_path_str(instance.def_id());
+            self.spans.insert(fn_name, FnSpans { name, blocks });
         }
 
-        let number_of_fns = self.fn_name_map.len();
-
         // add a `start` function, which calls `entry`.
-        let start = FnName(Name::from_internal(number_of_fns as _));
+        let start = self.fresh_fn_name();
         self.functions.insert(start, mk_start_fn(0));
 
-        Program { start, functions: self.functions, globals: self.globals }
+        let prog = Program {
+            start,
+            functions: self.functions,
+            globals: self.globals,
+            traits: self.traits,
+            vtables: self.vtables,
+        };
+        (prog, self.spans)
     }
 
     // Returns FnName associated with some key. If it does not exist it creates a new one.
     pub fn get_fn_name(&mut self, key: rs::Instance<'tcx>) -> FnName {
         // Used as the fn name if it is not named yet.
-        let len = self.fn_name_map.len();
+        let fresh = self.fresh_fn_name();
+
+        *self.fn_name_map.entry(key).or_insert(fresh)
+    }
 
-        *self.fn_name_map.entry(key).or_insert_with(|| FnName(Name::from_internal(len as _)))
+    // Returns a FnName that is not used by any function yet.
+    pub fn fresh_fn_name(&self) -> FnName {
+        let len = self.fn_name_map.len() + self.closure_fn_ptr_map.len() + self.heap_shim_map.len();
+        FnName(Name::from_internal(len as _))
     }
 
-    pub fn get_fn_name_smir(&mut self, key: smir::Instance) -> FnName {
-        self.get_fn_name(smir::internal(self.tcx, key))
+    /// Whether we can translate the body of `instance`. Non-generic library functions that are
+    /// not inlined, like `core::option::unwrap_failed`, do not have their MIR available.
+    fn is_mir_available(&self, instance: rs::Instance<'tcx>) -> bool {
+        match instance.def {
+            rs::InstanceKind::Item(def_id) => self.tcx.is_mir_available(def_id),
+            _ => true,
+        }
+    }
+
+    /// Translates a function whose MIR is not available to one that stops the program.
+    /// Such functions that never return are panic helpers, so they panic without a message.
+    /// IGNORED: The other ones abort, as we cannot run them.
+    fn translate_unavailable_fn(
+        &mut self,
+        instance: rs::Instance<'tcx>,
+    ) -> (Function, HashMap<BbName, BlockSpans>) {
+        let span = self.tcx.def_span(instance.def_id());
+        let sig = self.tcx.normalize_erasing_late_bound_regions(
+            rs::ParamEnv::reveal_all(),
+            self.tcx.fn_sig(instance.def_id()).instantiate(self.tcx, instance.args),
+        );
+        let locals: Vec<Type> = std::iter::once(sig.output())
+            .chain(sig.inputs().iter().copied())
+            .map(|ty| self.translate_ty(ty, span))
+            .collect();
+        let terminator = if sig.output().is_never() { build::panic() } else { build::abort() };
+        let block = build::block(&[], terminator);
+        let f = build::function(build::Ret::Yes, sig.inputs().len(), &locals, &[block]);
+        let abi = self
+            .tcx
+            .fn_abi_of_instance(rs::ParamEnv::reveal_all().and((instance, rs::List::empty())))
+            .unwrap();
+        let blocks = [(f.start, BlockSpans::uniform(block, span))].into_iter().collect();
+        (Function { calling_convention: translate_calling_convention(abi.conv), ..f }, blocks)
     }
 
     pub fn rs_layout_of(&self, ty: rs::Ty<'tcx>) -> rs::Layout<'tcx> {
@@ -114,6 +204,7 @@ fn mk_start_fn(entry: u32) -> Function {
             arguments: List::new(),
             ret: build::unit_place(),
             next_block: Some(b1_name),
+            unwind_block: None,
         },
     };
 
@@ -124,6 +215,7 @@ fn mk_start_fn(entry: u32) -> Function {
             arguments: List::new(),
             ret: build::unit_place(),
             next_block: None,
+            unwind_block: None,
         },
     };
 
@@ -50,6 +50,12 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                     (ShlUnchecked, Type::Int(_)) => build::shl_unchecked(l, r),
                     (ShrUnchecked, Type::Int(_)) => build::shr_unchecked(l, r),
 
+                    (Add, Type::Float(_)) => build::float_add(l, r),
+                    (Sub, Type::Float(_)) => build::float_sub(l, r),
+                    (Mul, Type::Float(_)) => build::float_mul(l, r),
+                    (Div, Type::Float(_)) => build::float_div(l, r),
+                    (Rem, Type::Float(_)) => build::float_rem(l, r),
+
                     (Lt, _) => build::lt(l, r),
                     (Le, _) => build::le(l, r),
                     (Gt, _) => build::gt(l, r),
@@ -96,6 +102,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                     (Neg, Type::Int(_)) => build::neg(operand),
                     (Not, Type::Int(_)) => build::bit_not(operand),
                     (Not, Type::Bool) => build::not(operand),
+                    (Neg, Type::Float(_)) => build::float_neg(operand),
                     (PtrMetadata, Type::Ptr(_)) => build::get_metadata(operand),
                     (op, _) =>
                         rs::span_bug!(span, "UnOp {op:?} called with unsupported type {ty_smir}."),
@@ -180,6 +187,18 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         ));
                         ValueExpr::Variant { discriminant, data, enum_ty: ty }
                     }
+                    Type::Ptr(ptr_ty) => {
+                        // A raw pointer built from a thin data pointer and the metadata,
+                        // like `ptr::from_raw_parts` does.
+                        assert_eq!(operands.len(), 2);
+                        let thin = self.translate_operand_smir(&operands[0], span);
+                        let Some(pair_ty) = ptr_ty.as_wide_pair::<DefaultTarget>() else {
+                            // Thin pointers have `()` as their metadata.
+                            return build::transmute(thin, ty);
+                        };
+                        let meta = self.translate_operand_smir(&operands[1], span);
+                        build::transmute(build::tuple(&[thin, meta], pair_ty), ty)
+                    }
                     x => rs::span_bug!(span, "Invalid aggregate type: {x:?}"),
                 }
             }
@@ -225,22 +244,64 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         let operand_ty = operand.ty(&self.locals_smir).unwrap();
                         let operand_ty = self.translate_ty_smir(operand_ty, span);
                         let operand = self.translate_operand_smir(operand, span);
-                        let Type::Int(int_ty) = self.translate_ty_smir(*cast_ty, span) else {
-                            rs::span_bug!(span, "Attempting to IntToInt-Cast to non-int type!");
-                        };
 
                         let operand = match operand_ty {
                             Type::Int(_) => operand,
                             // bool2int casts first go to u8, and then to the final type.
                             Type::Bool => build::transmute(operand, u8::get_type()),
+                            // char2int casts first go to u32, which has the same representation.
+                            Type::Char => build::transmute(operand, u32::get_type()),
                             _ =>
                                 rs::span_bug!(
                                     span,
-                                    "Attempting to cast non-int and non-boolean type to int!"
+                                    "Attempting to cast non-int, non-boolean and non-char type to int!"
                                 ),
                         };
+                        match self.translate_ty_smir(*cast_ty, span) {
+                            Type::Int(int_ty) =>
+                                ValueExpr::UnOp {
+                                    operator: UnOp::Cast(CastOp::IntToInt(int_ty)),
+                                    operand: GcCow::new(operand),
+                                },
+                            // Only `u8` can be cast to `char`, and every `u8` is a valid `char`,
+                            // so we can go through `u32` and transmute.
+                            Type::Char =>
+                                build::transmute(build::int_cast::<u32>(operand), build::char_ty()),
+                            _ =>
+                                rs::span_bug!(span, "Attempting to IntToInt-Cast to non-int type!"),
+                        }
+                    }
+
+                    smir::CastKind::FloatToInt => {
+                        let operand = self.translate_operand_smir(operand, span);
+                        let Type::Int(int_ty) = self.translate_ty_smir(*cast_ty, span) else {
+                            rs::span_bug!(span, "Attempting to FloatToInt-Cast to non-int type!");
+                        };
                         ValueExpr::UnOp {
-                            operator: UnOp::Cast(CastOp::IntToInt(int_ty)),
+                            operator: UnOp::Cast(CastOp::FloatToInt(int_ty)),
+                            operand: GcCow::new(operand),
+                        }
+                    }
+                    smir::CastKind::IntToFloat => {
+                        let operand = self.translate_operand_smir(operand, span);
+                        let Type::Float(float_ty) = self.translate_ty_smir(*cast_ty, span) else {
+                            rs::span_bug!(span, "Attempting to IntToFloat-Cast to non-float type!");
+                        };
+                        ValueExpr::UnOp {
+                            operator: UnOp::Cast(CastOp::IntToFloat(float_ty)),
+                            operand: GcCow::new(operand),
+                        }
+                    }
+                    smir::CastKind::FloatToFloat => {
+                        let operand = self.translate_operand_smir(operand, span);
+                        let Type::Float(float_ty) = self.translate_ty_smir(*cast_ty, span) else {
+                            rs::span_bug!(
+                                span,
+                                "Attempting to FloatToFloat-Cast to non-float type!"
+                            );
+                        };
+                        ValueExpr::UnOp {
+                            operator: UnOp::Cast(CastOp::FloatToFloat(float_ty)),
                             operand: GcCow::new(operand),
                         }
                     }
@@ -253,11 +314,16 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         let Type::Ptr(new_ptr_ty) = self.translate_ty_smir(*cast_ty, span) else {
                             rs::span_bug!(span, "ptr to ptr cast to non-pointer");
                         };
+                        let operand = self.translate_operand_smir(operand, span);
                         if old_ptr_ty.meta_kind() == new_ptr_ty.meta_kind() {
-                            let operand = self.translate_operand_smir(operand, span);
                             build::transmute(operand, Type::Ptr(new_ptr_ty))
+                        } else if new_ptr_ty.meta_kind() == PointerMetaKind::None {
+                            // Wide to thin pointer casts drop the metadata.
+                            build::transmute(
+                                build::get_thin_pointer(operand),
+                                Type::Ptr(new_ptr_ty),
+                            )
                         } else {
-                            // TODO(UnsizedTypes): Implement this for wide to thin pointer casts
                             rs::span_bug!(
                                 span,
                                 "Unimplemented: casting pointers with different metadata"
@@ -272,15 +338,28 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         build::transmute(operand, ty)
                     }
                     smir::CastKind::PointerCoercion(smir::PointerCoercion::ReifyFnPointer) => {
-                        let smir::Operand::Constant(f1) = operand else { panic!() };
-                        let smir::TyKind::RigidTy(smir::RigidTy::FnDef(f, substs_ref)) =
-                            f1.ty().kind()
+                        let operand_ty = operand.ty(&self.locals_smir).unwrap();
+                        let rs::TyKind::FnDef(f, substs_ref) =
+                            *smir::internal(self.tcx, operand_ty).kind()
                         else {
-                            panic!()
+                            rs::span_bug!(span, "ReifyFnPointer cast of non-FnDef type")
                         };
-                        let instance = smir::Instance::resolve(f, &substs_ref).unwrap();
+                        // Like rustc, use a shim where required, e.g. for `#[track_caller]` functions.
+                        let instance = rs::Instance::resolve_for_fn_ptr(
+                            self.tcx,
+                            rs::ParamEnv::reveal_all(),
+                            f,
+                            substs_ref,
+                        )
+                        .unwrap();
 
-                        build::fn_ptr_internal(self.cx.get_fn_name_smir(instance).0.get_internal())
+                        build::fn_ptr(self.cx.get_fn_name(instance))
+                    }
+                    smir::CastKind::PointerCoercion(smir::PointerCoercion::ClosureFnPointer(_)) => {
+                        let operand_ty = operand.ty(&self.locals_smir).unwrap();
+                        let closure_ty = smir::internal(self.tcx, operand_ty);
+
+                        build::fn_ptr(self.cx.get_closure_fn_ptr_name(closure_ty, span))
                     }
 
                     smir::CastKind::PointerExposeAddress =>
@@ -296,23 +375,92 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         | smir::PointerCoercion::ArrayToPointer,
                     ) => unreachable!("{cast_kind:?} casts should not occur in runtime MIR"),
 
-                    smir::CastKind::FloatToFloat
-                    | smir::CastKind::FloatToInt
-                    | smir::CastKind::IntToFloat
-                    | smir::CastKind::DynStar
-                    | smir::CastKind::PointerCoercion(smir::PointerCoercion::ClosureFnPointer(
-                        ..,
-                    ))
-                    | smir::CastKind::PointerCoercion(smir::PointerCoercion::Unsize) =>
+                    smir::CastKind::PointerCoercion(smir::PointerCoercion::Unsize) => {
+                        let operand_ty = operand.ty(&self.locals_smir).unwrap();
+                        let operand = self.translate_operand_smir(operand, span);
+                        self.translate_unsize(
+                            operand,
+                            smir::internal(self.tcx, operand_ty),
+                            smir::internal(self.tcx, *cast_ty),
+                            span,
+                        )
+                    }
+
+                    smir::CastKind::DynStar =>
                         rs::span_bug!(span, "cast not supported: {cast_kind:?}"),
                 }
             }
 
-            smir::Rvalue::ShallowInitBox(..) | smir::Rvalue::ThreadLocalRef(..) =>
-                rs::span_bug!(span, "rvalue not supported: {rv:?}"),
+            smir::Rvalue::ShallowInitBox(operand, _pointee) => {
+                // This turns the `*mut u8` returned by `exchange_malloc` into a `Box`.
+                let ty = rv.ty(&self.locals_smir).unwrap();
+                let ty = self.translate_ty_smir(ty, span);
+                let operand = self.translate_operand_smir(operand, span);
+                build::transmute(operand, ty)
+            }
+            smir::Rvalue::ThreadLocalRef(item) => {
+                // A pointer to the instance of the static that belongs to the current thread.
+                let def_id = smir::internal(self.tcx, item);
+                let name = self.translate_thread_local(def_id);
+                // Stable MIR gives the type of the static here, not the type of the pointer.
+                let ty = self.tcx.thread_local_ptr_ty(def_id);
+                let ty = self.translate_ty(ty, span);
+                let relocation = Relocation { name, offset: Offset::ZERO };
+                ValueExpr::Constant(Constant::GlobalPointer(relocation), ty)
+            }
         }
     }
 
+    /// Translates an unsizing coercion from the thin pointer `operand` of type `from_ty` to the wide pointer type `to_ty`.
+    /// This also handles smart pointers like `Box`, as long as they are represented by a single pointer.
+    fn translate_unsize(
+        &mut self,
+        operand: ValueExpr,
+        from_ty: rs::Ty<'tcx>,
+        to_ty: rs::Ty<'tcx>,
+        span: rs::Span,
+    ) -> ValueExpr {
+        let (Some(from_pointee), Some(to_pointee)) =
+            (from_ty.builtin_deref(true), to_ty.builtin_deref(true))
+        else {
+            rs::span_bug!(span, "unsizing coercion not supported: {from_ty} -> {to_ty}");
+        };
+        let (from_tail, to_tail) = self.tcx.struct_lockstep_tails_erasing_lifetimes(
+            from_pointee,
+            to_pointee,
+            rs::ParamEnv::reveal_all(),
+        );
+        let target = self.translate_ty(to_ty, span);
+
+        let (meta_kind, meta) = match (from_tail.kind(), to_tail.kind()) {
+            (rs::TyKind::Array(_, len), rs::TyKind::Slice(_)) => {
+                let len = len.eval_target_usize(self.tcx, rs::ParamEnv::reveal_all());
+                (PointerMetaKind::ElementCount, build::const_int_typed::<usize>(Int::from(len)))
+            }
+            (rs::TyKind::Dynamic(from_preds, ..), rs::TyKind::Dynamic(to_preds, ..)) => {
+                // Only dropping auto traits is supported, which keeps the vtable.
+                if from_preds.principal_def_id() != to_preds.principal_def_id() {
+                    rs::span_bug!(span, "trait upcasting is not supported: {from_ty} -> {to_ty}");
+                }
+                return build::transmute(operand, target);
+            }
+            (_, rs::TyKind::Dynamic(..)) => {
+                let vtable_name = self.get_vtable(from_tail, to_tail);
+                let trait_name = self.get_trait_name(to_tail);
+                (
+                    PointerMetaKind::VTablePointer(trait_name),
+                    build::vtable_ptr(vtable_name, trait_name),
+                )
+            }
+            _ => rs::span_bug!(span, "unsizing coercion not supported: {from_ty} -> {to_ty}"),
+        };
+
+        // Build the wide pointer from its two halves.
+        let thin_ptr = build::transmute(operand, build::raw_void_ptr_ty());
+        let pair_ty = PtrType::Raw { meta_kind }.as_wide_pair::<DefaultTarget>().unwrap();
+        build::transmute(build::tuple(&[thin_ptr, meta], pair_ty), target)
+    }
+
     pub fn translate_operand(&mut self, operand: &rs::Operand<'tcx>, span: rs::Span) -> ValueExpr {
         self.translate_operand_smir(&smir::stable(operand), span)
     }
@@ -340,6 +488,18 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
             place.projection.iter().fold((expr, place_ty), |(expr, place_ty), proj| {
                 let this_ty = proj.ty(place_ty).unwrap();
                 let this_expr = match proj {
+                    smir::ProjectionElem::Field(f, _ty)
+                        if smir::internal(self.tcx, place_ty).is_box() =>
+                    {
+                        // MiniRust treats `Box` as a pointer, not as a struct. The field holding
+                        // the pointer is at offset 0, so we reinterpret the `Box` as that field.
+                        if *f != 0 {
+                            rs::span_bug!(span, "only the pointer field of `Box` can be accessed");
+                        }
+                        let ty = self.translate_ty_smir(this_ty, span);
+                        let ptr = build::addr_of(expr, build::raw_void_ptr_ty());
+                        PlaceExpr::Deref { operand: GcCow::new(ptr), ty }
+                    }
                     smir::ProjectionElem::Field(f, _ty) => {
                         let indirected = GcCow::new(expr);
                         PlaceExpr::Field { root: indirected, field: (*f).into() }
@@ -367,9 +527,26 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                             self.discriminant_for_variant_smir(this_ty, *variant_idx, span);
                         PlaceExpr::Downcast { root, discriminant }
                     }
+                    smir::ProjectionElem::ConstantIndex { offset, min_length: _, from_end } => {
+                        let offset = build::const_int_typed::<usize>(Int::from(*offset));
+                        let index = if *from_end {
+                            let len = match self.translate_ty_smir(place_ty, span) {
+                                Type::Array { elem: _, count } =>
+                                    build::const_int_typed::<usize>(count),
+                                _ =>
+                                    build::get_metadata(build::addr_of(
+                                        expr,
+                                        build::raw_ptr_ty(PointerMetaKind::ElementCount),
+                                    )),
+                            };
+                            build::sub(len, offset)
+                        } else {
+                            offset
+                        };
+                        PlaceExpr::Index { root: GcCow::new(expr), index: GcCow::new(index) }
+                    }
 
-                    stable_mir::mir::ProjectionElem::ConstantIndex { .. }
-                    | stable_mir::mir::ProjectionElem::Subslice { .. }
+                    stable_mir::mir::ProjectionElem::Subslice { .. }
                     | stable_mir::mir::ProjectionElem::OpaqueCast(_)
                     | stable_mir::mir::ProjectionElem::Subtype(_) => {
                         rs::span_bug!(span, "Place Projection not supported: {:?}", proj);
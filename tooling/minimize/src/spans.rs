@@ -0,0 +1,63 @@
+use crate::*;
+
+/// The Rust source locations of the statements and the terminator of a MiniRust basic block.
+pub struct BlockSpans {
+    pub statements: Vec<rs::Span>,
+    pub terminator: rs::Span,
+}
+
+impl BlockSpans {
+    /// Spans for a block that was generated as a whole for the Rust code at `span`.
+    pub fn uniform(block: BasicBlock, span: rs::Span) -> Self {
+        let statements = block.statements.iter().map(|_| span).collect();
+        BlockSpans { statements, terminator: span }
+    }
+}
+
+/// The Rust source locations of a MiniRust function.
+pub struct FnSpans {
+    /// The path of the Rust function.
+    pub name: String,
+    pub blocks: HashMap<BbName, BlockSpans>,
+}
+
+/// Maps the statements and terminators of the MiniRust program back to the Rust source,
+/// so that errors can be reported with a backtrace.
+/// Functions that `minimize` generates itself, like the `start` function, have no spans.
+#[derive(Default)]
+pub struct SpanMap {
+    fns: HashMap<FnName, FnSpans>,
+}
+
+impl SpanMap {
+    pub fn insert(&mut self, fn_name: FnName, spans: FnSpans) {
+        let old = self.fns.insert(fn_name, spans);
+        assert!(old.is_none());
+    }
+
+    /// The function name and source location of a frame, if it comes from Rust code.
+    fn lookup(&self, frame: Frame) -> Option<(&str, rs::Span)> {
+        let fn_spans = self.fns.get(&frame.func)?;
+        let block = fn_spans.blocks.get(&frame.block)?;
+        // An index past the statements refers to the terminator.
+        let span = block.statements.get(frame.stmt).copied().unwrap_or(block.terminator);
+        Some((&fn_spans.name, span))
+    }
+
+    /// Format the backtraces like Miri does, with the innermost frame first.
+    pub fn fmt_backtraces(&self, tcx: rs::TyCtxt<'_>, backtraces: &[Backtrace]) -> String {
+        let source_map = tcx.sess.source_map();
+        let mut out = String::new();
+        for backtrace in backtraces {
+            let active = if backtrace.active { " (where the error occurred)" } else { "" };
+            out += &format!("\nthread {}{active}:", backtrace.thread);
+            for &frame in &backtrace.frames {
+                if let Some((name, span)) = self.lookup(frame) {
+                    let loc = source_map.span_to_diagnostic_string(span);
+                    out += &format!("\n    inside `{name}` at {loc}");
+                }
+            }
+        }
+        out
+    }
+}
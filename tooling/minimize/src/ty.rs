@@ -1,7 +1,7 @@
 use crate::*;
 
 impl<'tcx> Ctxt<'tcx> {
-    pub fn pointee_info_of(&self, ty: rs::Ty<'tcx>, span: rs::Span) -> PointeeInfo {
+    pub fn pointee_info_of(&mut self, ty: rs::Ty<'tcx>, span: rs::Span) -> PointeeInfo {
         let layout = self.rs_layout_of(ty);
         let inhabited = !layout.abi().is_uninhabited();
         let param_env = rs::ParamEnv::reveal_all();
@@ -11,23 +11,87 @@ impl<'tcx> Ctxt<'tcx> {
         if layout.is_sized() {
             let size = SizeStrategy::Sized(translate_size(layout.size()));
             let align = translate_align(layout.align().abi);
-            return PointeeInfo { size, align, inhabited, freeze, unpin };
+            let unsafe_cells = self.unsafe_cells_of(ty);
+            let byte_slice = false;
+            return PointeeInfo { size, align, inhabited, freeze, unpin, unsafe_cells, byte_slice };
         }
 
         // Handle Unsized types:
         match ty.kind() {
-            &rs::TyKind::Slice(elem_ty) => {
+            rs::TyKind::Slice(_) | rs::TyKind::Str => {
+                // `str` is `[u8]` to MiniRust.
+                let elem_ty = ty.sequence_element_type(self.tcx);
                 let elem_layout = self.rs_layout_of(elem_ty);
                 let size = SizeStrategy::Slice(translate_size(elem_layout.size()));
                 let align = translate_align(elem_layout.align().abi);
+                let unsafe_cells = self.unsafe_cells_of(elem_ty);
+                let byte_slice = elem_ty == self.tcx.types.u8;
 
-                PointeeInfo { size, align, inhabited, freeze, unpin }
+                PointeeInfo { size, align, inhabited, freeze, unpin, unsafe_cells, byte_slice }
+            }
+            rs::TyKind::Dynamic(_, _, rs::DynKind::Dyn) => {
+                let size = SizeStrategy::VTable(self.get_trait_name(ty));
+                // The actual alignment is stored in the vtable, statically we only know the minimum.
+                let align = Align::ONE;
+                // Where the `UnsafeCell`s are is not known statically either.
+                let unsafe_cells = List::new();
+                let byte_slice = false;
+
+                PointeeInfo { size, align, inhabited, freeze, unpin, unsafe_cells, byte_slice }
             }
             _ => rs::span_bug!(span, "encountered unimplemented unsized type: {ty}"),
         }
     }
 
-    pub fn pointee_info_of_smir(&self, ty: smir::Ty, span: rs::Span) -> PointeeInfo {
+    /// Computes the byte ranges of the sized type `ty` that are inside an `UnsafeCell`.
+    fn unsafe_cells_of(&self, ty: rs::Ty<'tcx>) -> List<(Offset, Size)> {
+        let mut cells = Vec::new();
+        self.collect_unsafe_cells(ty, rs::Size::ZERO, &mut cells);
+        cells.into_iter().collect()
+    }
+
+    fn collect_unsafe_cells(
+        &self,
+        ty: rs::Ty<'tcx>,
+        offset: rs::Size,
+        cells: &mut Vec<(Offset, Size)>,
+    ) {
+        let param_env = rs::ParamEnv::reveal_all();
+        if ty.is_freeze(self.tcx, param_env) {
+            return;
+        }
+        let layout = self.rs_layout_of(ty);
+        let fields = layout.fields();
+        match ty.kind() {
+            rs::TyKind::Adt(adt_def, sref) if adt_def.is_struct() && !adt_def.is_unsafe_cell() =>
+                for (i, field) in adt_def.non_enum_variant().fields.iter_enumerated() {
+                    let field_ty = field.ty(self.tcx, sref);
+                    let field_ty = self.tcx.normalize_erasing_regions(param_env, field_ty);
+                    self.collect_unsafe_cells(field_ty, offset + fields.offset(i.into()), cells);
+                },
+            rs::TyKind::Tuple(ts) =>
+                for (i, t) in ts.iter().enumerate() {
+                    self.collect_unsafe_cells(t, offset + fields.offset(i), cells);
+                },
+            rs::TyKind::Closure(_, args) => {
+                for (i, t) in args.as_closure().upvar_tys().iter().enumerate() {
+                    self.collect_unsafe_cells(t, offset + fields.offset(i), cells);
+                }
+            }
+            &rs::TyKind::Array(elem_ty, _) =>
+                for i in 0..fields.count() {
+                    self.collect_unsafe_cells(elem_ty, offset + fields.offset(i), cells);
+                },
+            // `UnsafeCell` itself, and enums and unions containing one: we do not look inside those,
+            // the entire value is considered interior mutable.
+            _ =>
+                if layout.size() != rs::Size::ZERO {
+                    cells.push((translate_size(offset), translate_size(layout.size())));
+                },
+        }
+    }
+
+    pub fn pointee_info_of_smir(&mut self, ty: smir::Ty, span: rs::Span) -> PointeeInfo {
         self.pointee_info_of(smir::internal(self.tcx, ty), span)
     }
 
@@ -42,6 +106,7 @@ impl<'tcx> Ctxt<'tcx> {
 
         let mini_ty = match ty.kind() {
             rs::TyKind::Bool => Type::Bool,
+            rs::TyKind::Char => Type::Char,
             rs::TyKind::Int(t) => {
                 let sz = rs::abi::Integer::from_int_ty(&self.tcx, *t).size();
                 Type::Int(IntType { size: translate_size(sz), signed: Signedness::Signed })
@@ -50,6 +115,8 @@ impl<'tcx> Ctxt<'tcx> {
                 let sz = rs::abi::Integer::from_uint_ty(&self.tcx, *t).size();
                 Type::Int(IntType { size: translate_size(sz), signed: Signedness::Unsigned })
             }
+            rs::TyKind::Float(rs::FloatTy::F32) => Type::Float(FloatType::F32),
+            rs::TyKind::Float(rs::FloatTy::F64) => Type::Float(FloatType::F64),
             rs::TyKind::Tuple(ts) => {
                 let layout = self.rs_layout_of(ty);
                 let size = translate_size(layout.size());
@@ -106,6 +173,32 @@ impl<'tcx> Ctxt<'tcx> {
                 let elem = GcCow::new(self.translate_ty(*ty, span));
                 Type::Slice { elem }
             }
+            rs::TyKind::Str => build::slice_ty(<u8>::get_type()),
+            rs::TyKind::Dynamic(_, _, rs::DynKind::Dyn) =>
+                Type::TraitObject(self.get_trait_name(ty)),
+            rs::TyKind::Closure(_, args) => {
+                // A closure is represented like a tuple of its captured variables.
+                let layout = self.rs_layout_of(ty);
+                let size = translate_size(layout.size());
+                let align = translate_align(layout.align().abi);
+
+                let fields = args
+                    .as_closure()
+                    .upvar_tys()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| {
+                        let t = self.translate_ty(t, span);
+                        let offset = translate_size(layout.fields().offset(i));
+
+                        (offset, t)
+                    })
+                    .collect();
+
+                Type::Tuple { fields, size, align }
+            }
+            // Function items are zero-sized: calls to them are resolved statically.
+            rs::TyKind::FnDef(..) => build::tuple_ty(&[], build::size(0), build::align(1)),
             x => rs::span_bug!(span, "TyKind not supported: {x:?}"),
         };
         self.ty_cache.insert(ty, mini_ty);
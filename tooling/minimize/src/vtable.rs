@@ -0,0 +1,87 @@
+use crate::*;
+
+impl<'tcx> Ctxt<'tcx> {
+    /// Returns the MiniRust trait for the given trait object type.
+    /// Trait objects with the same principal trait share their MiniRust trait,
+    /// auto traits do not matter.
+    pub fn get_trait_name(&mut self, dyn_ty: rs::Ty<'tcx>) -> TraitName {
+        let rs::TyKind::Dynamic(preds, _, rs::DynKind::Dyn) = dyn_ty.kind() else {
+            panic!("`get_trait_name` called on non-trait-object type {dyn_ty}")
+        };
+        let principal = self.tcx.erase_regions(preds.principal());
+
+        // Used as the trait name if it is not named yet.
+        let len = self.trait_map.len();
+        let trait_name = *self
+            .trait_map
+            .entry(principal)
+            .or_insert_with(|| TraitName(Name::from_internal(len as _)));
+        if !self.traits.contains_key(trait_name) {
+            self.traits.insert(trait_name, Set::new());
+        }
+        trait_name
+    }
+
+    /// Registers `method` as a method of the given trait and returns its MiniRust name.
+    /// Methods are named after their index in the vtable layout of rustc.
+    pub fn get_trait_method_name(&mut self, trait_name: TraitName, idx: usize) -> TraitMethodName {
+        let method = TraitMethodName(Name::from_internal(idx as _));
+        let mut methods = self.traits.index_at(trait_name);
+        methods.insert(method);
+        self.traits.insert(trait_name, methods);
+        method
+    }
+
+    /// Returns the vtable for using `ty` as the trait object type `dyn_ty`.
+    pub fn get_vtable(&mut self, ty: rs::Ty<'tcx>, dyn_ty: rs::Ty<'tcx>) -> VTableName {
+        let rs::TyKind::Dynamic(preds, _, rs::DynKind::Dyn) = dyn_ty.kind() else {
+            panic!("`get_vtable` called on non-trait-object type {dyn_ty}")
+        };
+        let principal = self.tcx.erase_regions(preds.principal());
+        if let Some(vtable_name) = self.vtable_map.get(&(ty, principal)) {
+            return *vtable_name;
+        }
+        let trait_name = self.get_trait_name(dyn_ty);
+
+        // This mirrors how rustc lays out its vtables.
+        let entries = match principal {
+            Some(principal) => {
+                let trait_ref = self.tcx.erase_regions(principal.with_self_ty(self.tcx, ty));
+                self.tcx.vtable_entries(trait_ref)
+            }
+            None => rs::TyCtxt::COMMON_VTABLE_ENTRIES,
+        };
+        let drop = self.get_fn_name(rs::Instance::resolve_drop_in_place(self.tcx, ty));
+        let mut methods = Map::new();
+        for (idx, entry) in entries.iter().enumerate() {
+            let instance = match entry {
+                rs::VtblEntry::Method(instance) => *instance,
+                // Drop glue, size and alignment are stored directly in the vtable.
+                rs::VtblEntry::MetadataDropInPlace
+                | rs::VtblEntry::MetadataSize
+                | rs::VtblEntry::MetadataAlign => continue,
+                // Methods that cannot be called on trait objects.
+                rs::VtblEntry::Vacant => continue,
+                // Trait upcasting is not supported.
+                rs::VtblEntry::TraitVPtr(_) => continue,
+            };
+            let method = self.get_trait_method_name(trait_name, idx);
+            methods.insert(method, self.get_fn_name(instance));
+        }
+
+        let layout = self.rs_layout_of(ty);
+        assert!(layout.is_sized(), "trait objects can only be created from sized types");
+        let vtable = VTable {
+            trait_name,
+            size: translate_size(layout.size()),
+            align: translate_align(layout.align().abi),
+            drop,
+            methods,
+        };
+
+        let vtable_name = VTableName(Name::from_internal(self.vtable_map.len() as _));
+        self.vtable_map.insert((ty, principal), vtable_name);
+        self.vtables.insert(vtable_name, vtable);
+        vtable_name
+    }
+}
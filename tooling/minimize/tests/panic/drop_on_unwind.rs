@@ -0,0 +1,18 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+struct Bomb;
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        print(42);
+    }
+}
+
+#[allow(unconditional_panic)]
+fn main() {
+    let _b = Bomb;
+    let x = [1, 2];
+    // The panic unwinds out of `main`, dropping `_b` on the way.
+    let _y = x[2];
+}
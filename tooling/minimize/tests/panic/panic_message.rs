@@ -0,0 +1,4 @@
+fn main() {
+    // The message of the panic is reported when the program ends.
+    unreachable!();
+}
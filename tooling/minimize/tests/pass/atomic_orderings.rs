@@ -0,0 +1,35 @@
+extern crate intrinsics;
+use intrinsics::*;
+use std::ptr::addr_of_mut;
+use std::sync::atomic::Ordering;
+
+// The writer publishes `DATA` with a release store to `FLAG`.
+// Once the reader has seen the flag with an acquire load, it must also see the data,
+// so the output is deterministic even though relaxed loads may in general read stale values.
+
+static mut DATA: u32 = 0;
+static mut FLAG: u32 = 0;
+
+extern "C" fn writer(data_ptr: *const ()) {
+    unsafe {
+        atomic_store_explicit(data_ptr as *mut u32, 42, Ordering::Relaxed);
+        atomic_store_explicit(addr_of_mut!(FLAG), 1, Ordering::Release);
+    }
+}
+
+fn main() {
+    let fn_ptr = writer as extern "C" fn(*const ());
+    let thread_id = spawn(fn_ptr, addr_of_mut!(DATA) as *const ());
+
+    unsafe {
+        while atomic_load_explicit(addr_of_mut!(FLAG), Ordering::Acquire) == 0 {}
+        print(atomic_load_explicit(addr_of_mut!(DATA), Ordering::Relaxed));
+
+        // Read-modify-write operations always see the latest value.
+        print(atomic_fetch_add_explicit(addr_of_mut!(DATA), 1, Ordering::Relaxed));
+        print(compare_exchange_explicit(addr_of_mut!(DATA), 43, 50, Ordering::AcqRel, Ordering::Acquire));
+        fence(Ordering::SeqCst);
+        print(atomic_load_explicit(addr_of_mut!(DATA), Ordering::SeqCst));
+    }
+    join(thread_id);
+}
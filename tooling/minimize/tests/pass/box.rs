@@ -0,0 +1,64 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+struct PrintOnDrop(u32);
+
+impl Drop for PrintOnDrop {
+    fn drop(&mut self) {
+        print(self.0);
+    }
+}
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+fn make_box(x: u64) -> Box<u64> {
+    Box::new(x)
+}
+
+fn main() {
+    let b = Box::new(42);
+    print(*b);
+
+    let mut pair = Box::new((1u8, 2u64));
+    pair.1 += 5;
+    print(pair.0);
+    print(pair.1);
+
+    print(*make_box(7));
+
+    let nested = Box::new(Box::new(3));
+    print(**nested);
+
+    // Zero-sized boxes do not allocate.
+    let unit = Box::new(());
+    drop(unit);
+
+    // Dropping the box drops its contents and then frees the memory.
+    let d = Box::new(PrintOnDrop(10));
+    drop(d);
+
+    let shape: Box<dyn Shape> = Box::new(Square(4));
+    print(shape.area());
+
+    let on_drop: Box<dyn Shape> = Box::new(PrintOnDropShape(PrintOnDrop(11)));
+    print(on_drop.area());
+}
+
+#[allow(dead_code)] // The field is only there to be dropped.
+struct PrintOnDropShape(PrintOnDrop);
+
+impl Shape for PrintOnDropShape {
+    fn area(&self) -> u32 {
+        0
+    }
+}
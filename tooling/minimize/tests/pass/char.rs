@@ -0,0 +1,34 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn classify(c: char) -> u32 {
+    match c {
+        'a'..='z' => 0,
+        'A'..='Z' => 1,
+        '0'..='9' => 2,
+        ' ' => 3,
+        _ => 4,
+    }
+}
+
+fn main() {
+    let c = 'x';
+    print(c);
+    print(c as u32);
+    print(c as u8);
+    print(c < 'y');
+    print('ß' as u32);
+
+    let b = 65u8;
+    print(b as char);
+
+    let digits = ['M', 'i', 'n', 'i', ' ', '4', '!'];
+    let mut i = 0;
+    while i < digits.len() {
+        print(classify(digits[i]));
+        i += 1;
+    }
+
+    let max = unsafe { std::mem::transmute::<u32, char>(0x10FFFF) };
+    print(max as u32);
+}
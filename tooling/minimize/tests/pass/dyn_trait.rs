@@ -0,0 +1,81 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::mem::{align_of_val, size_of_val, ManuallyDrop};
+
+trait Shape {
+    fn area(&self) -> u32;
+    fn scale(&mut self, factor: u32);
+}
+
+struct Square(u32);
+
+struct Rect {
+    w: u32,
+    h: u64,
+}
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+
+    fn scale(&mut self, factor: u32) {
+        self.0 *= factor;
+    }
+}
+
+impl Shape for Rect {
+    fn area(&self) -> u32 {
+        self.w * self.h as u32
+    }
+
+    fn scale(&mut self, factor: u32) {
+        self.w *= factor;
+        self.h *= factor as u64;
+    }
+}
+
+impl Drop for Rect {
+    fn drop(&mut self) {
+        print(self.w);
+    }
+}
+
+fn total_area(shapes: &[&dyn Shape]) -> u32 {
+    let mut total = 0;
+    let mut i = 0;
+    while i < shapes.len() {
+        total += shapes[i].area();
+        i += 1;
+    }
+    total
+}
+
+fn apply(f: &dyn Fn(u32) -> u32, x: u32) -> u32 {
+    f(x)
+}
+
+fn main() {
+    let square = Square(3);
+    let mut rect = ManuallyDrop::new(Rect { w: 2, h: 5 });
+
+    let shape: &mut dyn Shape = &mut *rect;
+    shape.scale(2);
+    print(shape.area());
+    print(total_area(&[&square, &*rect]));
+
+    let shape: &dyn Shape = &square;
+    print(size_of_val(shape));
+    print(align_of_val(shape));
+    let shape: &dyn Shape = &*rect;
+    print(size_of_val(shape));
+    print(align_of_val(shape));
+
+    let offset = 10;
+    print(apply(&|x| x + offset, 5));
+
+    // Dropping through a trait object uses the drop glue of the vtable.
+    let ptr: *mut dyn Shape = &mut *rect;
+    unsafe { std::ptr::drop_in_place(ptr) };
+}
@@ -0,0 +1,20 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn black_box<T>(t: T) -> T { t }
+
+fn main() {
+    let x = black_box(1.5f32);
+    let y = black_box(0.25f64);
+    print(x + 2.0); // 3.5
+    print(-y * 4.0); // -1
+    print(black_box(7.5f64) % 2.0); // 1.5
+    print(x < 2.0); // true
+    print(black_box(-3.9f64) as i32); // -3
+    print(black_box(300.0f32) as u8); // 255
+    print(black_box(f64::NAN) as u64); // 0
+    print(black_box(42u16) as f64); // 42
+    print(black_box(0.5f32) as f64); // 0.5
+    let nan = black_box(0.0f64) / 0.0;
+    print(nan == nan); // false
+}
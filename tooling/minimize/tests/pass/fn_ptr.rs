@@ -0,0 +1,42 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn add_one(x: u32) -> u32 {
+    x + 1
+}
+
+fn apply_twice(f: fn(u32) -> u32, x: u32) -> u32 {
+    f(f(x))
+}
+
+fn call_generic<F: Fn(u32) -> u32>(f: F, x: u32) -> u32 {
+    f(x)
+}
+
+const TABLE: [fn(u32) -> u32; 2] = [double, add_one];
+
+fn main() {
+    let f: fn(u32) -> u32 = double;
+    print(f(21));
+    print(apply_twice(add_one, 5));
+
+    // Non-capturing closures can be coerced to function pointers.
+    let square = |x: u32| x * x;
+    print(apply_twice(square, 3));
+
+    // Functions and function pointers implement the `Fn` traits.
+    print(call_generic(double, 4));
+    print(call_generic(f, 5));
+
+    // Globals cannot point to functions, so we copy the table into a local instead of borrowing it.
+    let table = TABLE;
+    let mut i = 0;
+    while i < table.len() {
+        print(table[i](10));
+        i += 1;
+    }
+}
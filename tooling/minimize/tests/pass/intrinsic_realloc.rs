@@ -0,0 +1,22 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn main() {
+    unsafe {
+        let ptr = allocate(2, 1);
+        *ptr = 1;
+        *ptr.add(1) = 2;
+
+        // Growing keeps the old contents.
+        let ptr = reallocate(ptr, 2, 1, 3);
+        *ptr.add(2) = 3;
+        print(*ptr);
+        print(*ptr.add(1));
+        print(*ptr.add(2));
+
+        // Shrinking keeps the part that still fits.
+        let ptr = reallocate(ptr, 3, 1, 1);
+        print(*ptr);
+        deallocate(ptr, 1, 1);
+    }
+}
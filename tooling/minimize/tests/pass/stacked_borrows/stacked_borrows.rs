@@ -0,0 +1,75 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+// Check some programs that are allowed by Stacked Borrows.
+
+// Reborrows can be used in a stack-like manner.
+fn nested_reborrows() {
+    let mut x = 0u8;
+    let y = &mut x;
+    let z = &mut *y;
+    *z = 1;
+    *y = 2; // This pops `z`, which is fine as long as `z` is not used again.
+    assert!(x == 2);
+}
+
+// Shared references can be used in any order.
+fn interleaved_shared() {
+    let x = 42;
+    let y = &x;
+    let z = &x;
+    assert!(*y == 42);
+    assert!(*z == 42);
+    assert!(*y == *z);
+}
+
+// Raw pointers derived from the same reference can be interleaved.
+fn interleaved_raw() {
+    let mut x = 0i32;
+    let r = &mut x;
+    let p1 = r as *mut i32;
+    let p2 = p1;
+    unsafe {
+        *p1 = 1;
+        *p2 = 2;
+        *p1 += 1;
+    }
+    assert!(x == 3);
+}
+
+// A protector goes back to normal behavior when the function returns.
+fn end_of_protector() {
+    let data = &mut 0u8;
+    let x = &mut *data;
+    do_nothing(x);
+    let y = &mut *data;
+    *y = 1;
+    assert!(*y == 1);
+}
+
+fn do_nothing(x: &mut u8) {
+    assert!(*x == 0);
+}
+
+// Shared references to interior mutable data may be used for writes in any order,
+// even while they are protected.
+fn interleaved_interior_mut() {
+    use std::cell::Cell;
+
+    let c = Cell::new(0);
+    set_both(&c, &c);
+    assert!(c.get() == 2);
+}
+
+fn set_both(a: &std::cell::Cell<i32>, b: &std::cell::Cell<i32>) {
+    a.set(1);
+    b.set(2);
+    assert!(a.get() == 2);
+}
+
+fn main() {
+    nested_reborrows();
+    interleaved_shared();
+    interleaved_raw();
+    end_of_protector();
+    interleaved_interior_mut();
+}
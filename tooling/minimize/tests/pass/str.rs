@@ -0,0 +1,23 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn pick(first: bool) -> &'static str {
+    if first { "first" } else { "second" }
+}
+
+fn main() {
+    print("hello, MiniRust!");
+    print("ünïcödé ✓");
+    print(pick(true));
+    print(pick(false));
+
+    let words = ["one", "two", "three"];
+    let mut i = 0;
+    while i < words.len() {
+        print(words[i]);
+        i += 1;
+    }
+
+    let empty = "";
+    print(empty);
+}
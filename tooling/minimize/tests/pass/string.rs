@@ -0,0 +1,32 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn main() {
+    let mut s = String::new();
+    s.push_str("hello");
+    s.push(',');
+    s.push(' ');
+    s.push_str("MiniRust");
+    print(s.as_str());
+    print(s.len());
+
+    // Growing the string one character at a time reallocates its buffer.
+    let mut t = String::from("a");
+    let mut i = 0;
+    while i < 20 {
+        t.push('b');
+        i += 1;
+    }
+    print(t.len());
+    print(&t[18..]);
+
+    t.truncate(1);
+    t.push_str("ü");
+    print(t.as_str());
+    drop(t);
+
+    // `String::clone` has no MIR in the standard library, so we copy through `&str` instead.
+    let copy = String::from(s.as_str());
+    drop(s);
+    print(copy.as_str());
+}
@@ -0,0 +1,34 @@
+#![feature(thread_local)]
+
+extern crate intrinsics;
+use intrinsics::*;
+use std::cell::Cell;
+
+#[thread_local]
+static COUNTER: Cell<u32> = Cell::new(0);
+
+thread_local! {
+    static CACHE: Cell<u32> = const { Cell::new(10) };
+}
+
+fn bump() -> u32 {
+    COUNTER.set(COUNTER.get() + 1);
+    COUNTER.get()
+}
+
+extern "C" fn thread(_: *const ()) {
+    // This thread has its own counter, which starts at 0 again.
+    print(bump());
+    CACHE.with(|cache| cache.set(cache.get() + 5));
+    print(CACHE.with(|cache| cache.get()));
+}
+
+fn main() {
+    print(bump());
+    print(bump());
+    let thread_id = spawn(thread as extern "C" fn(*const ()), &() as *const ());
+    join(thread_id);
+    // The other thread did not change our values.
+    print(bump());
+    print(CACHE.with(|cache| cache.get()));
+}
@@ -0,0 +1,25 @@
+//@ compile-flags: --minimize-tree-borrows
+
+// Check that shared references to interior mutable data can be used for writing,
+// while the parts outside of `UnsafeCell` stay frozen.
+
+use std::cell::UnsafeCell;
+
+struct Pair {
+    frozen: i32,
+    cell: UnsafeCell<i32>,
+}
+
+fn write_through_shared(p: &Pair) {
+    unsafe { *p.cell.get() = 42 };
+}
+
+fn main() {
+    let p = Pair { frozen: 1, cell: UnsafeCell::new(0) };
+    let r1 = &p;
+    let r2 = &p;
+    write_through_shared(r1); // The write is a foreign write for `r2`, but `r2` has `Cell` permission there.
+    unsafe { *r2.cell.get() += 1 };
+    assert!(p.frozen == 1);
+    assert!(unsafe { *r1.cell.get() } == 43);
+}
@@ -0,0 +1,25 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+struct PrintOnDrop(i32);
+
+impl Drop for PrintOnDrop {
+    fn drop(&mut self) {
+        print(self.0);
+    }
+}
+
+fn may_panic(data_ptr: *mut u8) {
+    let _guard = PrintOnDrop(1);
+    let arr = [0u8; 2];
+    let idx = unsafe { *data_ptr } as usize;
+    print(arr[idx]); // panics if `idx` is out of bounds
+    print(2);
+}
+
+fn main() {
+    let mut idx = 0u8;
+    print(catch_unwind(may_panic, &mut idx as *mut u8));
+    idx = 5;
+    print(catch_unwind(may_panic, &mut idx as *mut u8));
+}
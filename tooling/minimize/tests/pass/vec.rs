@@ -0,0 +1,42 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+struct PrintOnDrop(u32);
+
+impl Drop for PrintOnDrop {
+    fn drop(&mut self) {
+        print(self.0);
+    }
+}
+
+fn main() {
+    // Pushing reallocates the buffer several times.
+    let mut v = Vec::new();
+    let mut i = 0;
+    while i < 10 {
+        v.push(i * i);
+        i += 1;
+    }
+    print(v.len());
+    print(v[3]);
+    print(v[9]);
+    print(v.pop().unwrap());
+    print(v.len());
+
+    // `vec![0; n]` allocates zeroed memory.
+    let zeros = vec![0u8; 5];
+    print(zeros[4]);
+
+    // Dropping the vector drops its elements in order and then frees the buffer.
+    let mut drops = Vec::with_capacity(1);
+    drops.push(PrintOnDrop(1));
+    drops.push(PrintOnDrop(2));
+    drops.push(PrintOnDrop(3));
+    drop(drops);
+
+    // Shrinking reallocates as well.
+    let mut shrink = Vec::with_capacity(8);
+    shrink.push(7u64);
+    shrink.shrink_to_fit();
+    print(shrink[0]);
+}
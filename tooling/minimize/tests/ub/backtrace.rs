@@ -0,0 +1,15 @@
+//@ compile-flags: --minimize-backtrace
+extern crate intrinsics;
+use intrinsics::*;
+
+fn inner(ptr: *const u32) -> u32 {
+    unsafe { *ptr }
+}
+
+fn outer() -> u32 {
+    inner(std::ptr::null())
+}
+
+fn main() {
+    print(outer());
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let b = Box::new(1);
+    // The leak checker notices that the memory of the box is never freed.
+    std::mem::forget(b);
+}
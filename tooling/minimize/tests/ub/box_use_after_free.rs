@@ -0,0 +1,7 @@
+fn main() {
+    let b = Box::new(1);
+    let ptr = &*b as *const i32;
+    drop(b);
+    // The memory of the box was freed when it was dropped.
+    let _val = unsafe { *ptr };
+}
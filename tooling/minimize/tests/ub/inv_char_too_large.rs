@@ -0,0 +1,5 @@
+use std::mem::transmute;
+
+fn main() { unsafe {
+    let _c = transmute::<u32, char>(0x110000);
+} }
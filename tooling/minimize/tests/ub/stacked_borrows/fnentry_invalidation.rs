@@ -0,0 +1,20 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+// Stacked Borrows version of `fnentry_invalidation` in `pass/tree_borrows/sb_fails.rs`.
+
+fn main() {
+    let mut x = 0i32;
+    let z = &mut x as *mut i32;
+    x.do_bad(); // The mutable reborrow of `x` pops the item of `z`...
+    unsafe {
+        let _oof = *z; // ...so reading through `z` is UB.
+    }
+}
+
+trait Bad {
+    fn do_bad(&mut self) {
+        // who knows
+    }
+}
+
+impl Bad for i32 {}
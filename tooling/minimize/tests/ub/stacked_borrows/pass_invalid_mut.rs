@@ -0,0 +1,13 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+// Stacked Borrows version of `pass_invalid_mut` in `pass/tree_borrows/sb_fails.rs`.
+
+fn foo(_: &mut i32) {}
+
+fn main() {
+    let x = &mut 42;
+    let xraw = x as *mut _;
+    let xref = unsafe { &mut *xraw };
+    let _val = unsafe { *xraw }; // This disables the item of `xref`...
+    foo(xref); // ...which then cannot be reborrowed here.
+}
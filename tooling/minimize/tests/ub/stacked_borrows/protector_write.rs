@@ -0,0 +1,16 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+// Check that writing through a raw pointer while a reborrow of it is protected is UB.
+
+fn foo(x: *mut i32, y: &mut i32) {
+    *y = 57;
+    unsafe { *x = 42 }; // UB! This removes the protected item of `y`.
+}
+
+fn main() {
+    let x = &mut 31;
+    let xraw = x as *mut i32;
+    let y = unsafe { &mut *xraw };
+
+    foo(xraw, y);
+}
@@ -0,0 +1,14 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+// Stacked Borrows version of `return_invalid_mut` in `pass/tree_borrows/sb_fails.rs`.
+
+fn foo(x: &mut (i32, i32)) -> &mut i32 {
+    let xraw = x as *mut (i32, i32);
+    let ret = unsafe { &mut (*xraw).1 };
+    let _val = unsafe { *xraw }; // This disables the item of `ret`...
+    ret // ...which then cannot be reborrowed here.
+}
+
+fn main() {
+    foo(&mut (1, 2));
+}
@@ -0,0 +1,12 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+#![allow(invalid_reference_casting)]
+
+// Check that writing through a pointer derived from a shared reference is UB.
+
+fn main() {
+    let x = 31;
+    let y = &x;
+    let yraw = y as *const i32 as *mut i32;
+    unsafe { *yraw = 42 }; // UB! `SharedReadOnly` does not grant write access.
+}
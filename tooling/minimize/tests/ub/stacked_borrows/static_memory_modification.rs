@@ -0,0 +1,13 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+// Stacked Borrows version of `static_memory_modification` in `pass/tree_borrows/sb_fails.rs`.
+
+static X: usize = 5;
+
+#[allow(mutable_transmutes)]
+fn main() {
+    let x = unsafe {
+        std::mem::transmute::<&usize, &mut usize>(&X) // UB! `SharedReadOnly` does not grant the write access of this mutable reborrow.
+    };
+    assert!(*&*x == 5);
+}
@@ -0,0 +1,22 @@
+#![feature(thread_local)]
+
+extern crate intrinsics;
+use intrinsics::*;
+
+#[thread_local]
+static VALUE: u32 = 42;
+
+static mut PTR: *const u32 = std::ptr::null();
+
+extern "C" fn thread(_: *const ()) {
+    unsafe {
+        PTR = &VALUE as *const u32;
+    }
+}
+
+fn main() {
+    let thread_id = spawn(thread as extern "C" fn(*const ()), &() as *const ());
+    join(thread_id);
+    // The instance of `VALUE` of the other thread was deallocated when it terminated.
+    print(unsafe { *PTR });
+}
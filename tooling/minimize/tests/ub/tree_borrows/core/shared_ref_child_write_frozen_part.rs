@@ -0,0 +1,20 @@
+//@ compile-flags: --minimize-tree-borrows
+
+#![allow(invalid_reference_casting)]
+
+// Check that only the `UnsafeCell` part of a shared reference can be written to.
+
+use std::cell::UnsafeCell;
+
+struct Pair {
+    frozen: i32,
+    cell: UnsafeCell<i32>,
+}
+
+fn main() {
+    let p = Pair { frozen: 1, cell: UnsafeCell::new(0) };
+    let r = &p; // (p, Active) -> (r, Frozen outside the cell, Cell inside)
+    unsafe { *r.cell.get() = 42 }; // Writing to the cell is fine.
+    let frozen = &r.frozen as *const i32 as *mut i32;
+    unsafe { *frozen = 2 }; // UB! Child Write to Frozen.
+}
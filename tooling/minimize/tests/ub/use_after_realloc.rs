@@ -0,0 +1,13 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn main() {
+    unsafe {
+        let ptr = allocate(4, 4) as *mut u32;
+        *ptr = 42;
+        let new_ptr = reallocate(ptr as *mut u8, 4, 4, 8) as *mut u32;
+        // The old pointer is dangling now, even if the new allocation is at the same address.
+        print(*ptr);
+        deallocate(new_ptr as *mut u8, 8, 4);
+    }
+}
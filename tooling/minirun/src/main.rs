@@ -0,0 +1,183 @@
+//! `minirun` executes a MiniRust program stored in a file, without needing rustc.
+//!
+//! Usage: `minirun [--tree-borrows | --stacked-borrows] [--dump] [SCHEDULE OPTIONS] FILE`
+//!
+//! The schedule options are `--seed=N` to make the non-deterministic choices (threads, allocation
+//! addresses, ...) with a seeded pseudo-random generator, `--record-schedule=PATH` to write the
+//! choices that were made to a file, and `--replay-schedule=PATH` to make the choices recorded in
+//! such a file.
+//!
+//! The file contains a program in the textual syntax printed by `miniutil::fmt::fmt_program`,
+//! or, if its name ends in `.json`, in the JSON encoding of `miniutil::json`.
+//! How the program terminated is reported via the exit code, see the `EXIT_*` constants below.
+
+use miniutil::fmt::{dump_program, fmt_ill_formed};
+use miniutil::json::program_from_json;
+use miniutil::parse::parse_program;
+use miniutil::run::{run_program, run_program_with_schedule};
+use miniutil::schedule::{Schedule, ScheduleTrace};
+use miniutil::*;
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+/// The program exited normally.
+const EXIT_SUCCESS: i32 = 0;
+/// The command-line arguments were invalid, or the program could not be loaded.
+const EXIT_USAGE: i32 = 2;
+/// The program is not well-formed.
+const EXIT_ILL_FORMED: i32 = 3;
+/// The program has Undefined Behavior.
+const EXIT_UB: i32 = 4;
+/// The program dead-locked.
+const EXIT_DEADLOCK: i32 = 5;
+/// The program leaked memory.
+const EXIT_MEMORY_LEAK: i32 = 6;
+/// The program aborted. This is the same exit code that `minimize` uses.
+const EXIT_ABORT: i32 = 101;
+
+const USAGE: &str = "Usage: minirun [--tree-borrows | --stacked-borrows] [--dump] \
+    [--seed=N] [--record-schedule=PATH] [--replay-schedule=PATH] FILE";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MemoryModel {
+    Basic,
+    TreeBorrows,
+    StackedBorrows,
+}
+
+struct Options {
+    file: String,
+    memory_model: MemoryModel,
+    /// Print the program instead of running it.
+    dump: bool,
+    seed: Option<u64>,
+    record_schedule: Option<String>,
+    replay_schedule: Option<String>,
+}
+
+fn show_error(msg: &impl std::fmt::Display, code: i32) -> ! {
+    eprintln!("fatal error: {msg}");
+    std::process::exit(code)
+}
+
+macro_rules! show_error {
+    ($code:expr, $($tt:tt)*) => { crate::show_error(&format_args!($($tt)*), $code) };
+}
+
+fn main() {
+    let opts = parse_args(std::env::args().skip(1));
+    let prog = load_program(&opts.file);
+
+    if opts.dump {
+        dump_program(prog);
+        return;
+    }
+
+    match run_prog(prog, &opts) {
+        TerminationInfo::MachineStop => std::process::exit(EXIT_SUCCESS),
+        TerminationInfo::IllFormed(err) =>
+            show_error!(
+                EXIT_ILL_FORMED,
+                "program not well-formed:\n    {}",
+                fmt_ill_formed(prog, err)
+            ),
+        TerminationInfo::Abort(err) => show_error!(EXIT_ABORT, "Panic: {}", err.get_internal()),
+        TerminationInfo::Ub(ub) => show_error!(EXIT_UB, "UB: {ub}"),
+        TerminationInfo::Deadlock => show_error!(EXIT_DEADLOCK, "program dead-locked"),
+        TerminationInfo::MemoryLeak => show_error!(EXIT_MEMORY_LEAK, "program leaked memory"),
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Options {
+    let mut file = None;
+    let mut memory_model = MemoryModel::Basic;
+    let mut dump = false;
+    let mut seed = None;
+    let mut record_schedule = None;
+    let mut replay_schedule = None;
+
+    for arg in args {
+        if let Some(n) = arg.strip_prefix("--seed=") {
+            let n = n.parse().unwrap_or_else(|_| show_error!(EXIT_USAGE, "invalid seed `{n}`"));
+            seed = Some(n);
+            continue;
+        }
+        if let Some(path) = arg.strip_prefix("--record-schedule=") {
+            record_schedule = Some(path.to_string());
+            continue;
+        }
+        if let Some(path) = arg.strip_prefix("--replay-schedule=") {
+            replay_schedule = Some(path.to_string());
+            continue;
+        }
+        match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                std::process::exit(EXIT_SUCCESS);
+            }
+            "--tree-borrows" | "--stacked-borrows" if memory_model != MemoryModel::Basic =>
+                show_error!(EXIT_USAGE, "only one memory model can be selected\n{USAGE}"),
+            "--tree-borrows" => memory_model = MemoryModel::TreeBorrows,
+            "--stacked-borrows" => memory_model = MemoryModel::StackedBorrows,
+            "--dump" => dump = true,
+            _ if arg.starts_with('-') => show_error!(EXIT_USAGE, "unknown option `{arg}`\n{USAGE}"),
+            _ if file.is_some() => show_error!(EXIT_USAGE, "more than one input file\n{USAGE}"),
+            _ => file = Some(arg),
+        }
+    }
+
+    let Some(file) = file else {
+        show_error!(EXIT_USAGE, "no input file\n{USAGE}");
+    };
+    if seed.is_some() && replay_schedule.is_some() {
+        show_error!(EXIT_USAGE, "`--seed` and `--replay-schedule` cannot be combined\n{USAGE}");
+    }
+    Options { file, memory_model, dump, seed, record_schedule, replay_schedule }
+}
+
+/// Reads and parses the program stored in `file`.
+fn load_program(file: &str) -> Program {
+    let src = std::fs::read_to_string(file)
+        .unwrap_or_else(|err| show_error!(EXIT_USAGE, "cannot read `{file}`: {err}"));
+    let prog = if file.ends_with(".json") {
+        program_from_json(&src).map_err(|err| err.to_string())
+    } else {
+        parse_program(&src).map_err(|err| err.to_string())
+    };
+    prog.unwrap_or_else(|err| show_error!(EXIT_USAGE, "{file}: {err}"))
+}
+
+fn run_prog(prog: Program, opts: &Options) -> TerminationInfo {
+    match opts.memory_model {
+        MemoryModel::Basic => run_prog_with::<BasicMem>(prog, opts),
+        MemoryModel::TreeBorrows => run_prog_with::<TreeBorrowMem>(prog, opts),
+        MemoryModel::StackedBorrows => run_prog_with::<StackedBorrowMem>(prog, opts),
+    }
+}
+
+fn run_prog_with<M: Memory>(prog: Program, opts: &Options) -> TerminationInfo {
+    let schedule = if let Some(path) = &opts.replay_schedule {
+        let src = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| show_error!(EXIT_USAGE, "cannot read `{path}`: {err}"));
+        let trace = ScheduleTrace::parse(&src)
+            .unwrap_or_else(|err| show_error!(EXIT_USAGE, "{path}: {err}"));
+        Schedule::Replay(trace)
+    } else if let Some(seed) = opts.seed {
+        Schedule::Seeded(seed)
+    } else if opts.record_schedule.is_some() {
+        // Recording needs a schedule, so pick a random seed.
+        Schedule::Seeded(RandomState::new().hash_one(()))
+    } else {
+        return run_program::<M>(prog);
+    };
+
+    let (info, trace) = run_program_with_schedule::<M>(prog, schedule)
+        .unwrap_or_else(|err| show_error!(EXIT_USAGE, "{err}"));
+    if let Some(path) = &opts.record_schedule {
+        if let Err(err) = std::fs::write(path, trace.to_string()) {
+            show_error!(EXIT_USAGE, "cannot write `{path}`: {err}");
+        }
+    }
+    info
+}
@@ -0,0 +1,84 @@
+use std::process::{Command, Output};
+
+fn minirun(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_minirun"))
+        .args(args)
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/programs"))
+        .output()
+        .unwrap()
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8(out.stdout.clone()).unwrap()
+}
+
+fn stderr(out: &Output) -> String {
+    String::from_utf8(out.stderr.clone()).unwrap()
+}
+
+#[test]
+fn run_print() {
+    for model in [&[][..], &["--tree-borrows"], &["--stacked-borrows"]] {
+        let out = minirun(&[model, &["print.minirust"]].concat());
+        assert_eq!(out.status.code(), Some(0));
+        assert_eq!(stdout(&out), "42\n");
+    }
+}
+
+#[test]
+fn run_json() {
+    let out = minirun(&["print.json"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(stdout(&out), "42\n");
+}
+
+#[test]
+fn run_ub() {
+    let out = minirun(&["div_by_zero.minirust"]);
+    assert_eq!(out.status.code(), Some(4));
+    assert_eq!(stderr(&out), "fatal error: UB: division by zero\n");
+}
+
+#[test]
+fn run_abort() {
+    let out = minirun(&["abort.minirust"]);
+    assert_eq!(out.status.code(), Some(101));
+    assert_eq!(stderr(&out), "fatal error: Panic: the program aborted\n");
+}
+
+#[test]
+fn parse_error() {
+    let out = minirun(&["syntax_error.minirust"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert_eq!(
+        stderr(&out),
+        "fatal error: syntax_error.minirust: parse error at 2:11: expected an integer type\n"
+    );
+}
+
+#[test]
+fn bad_args() {
+    assert_eq!(minirun(&[]).status.code(), Some(2));
+    assert_eq!(minirun(&["--no-such-flag", "print.minirust"]).status.code(), Some(2));
+    assert_eq!(minirun(&["missing.minirust"]).status.code(), Some(2));
+    let out = minirun(&["--tree-borrows", "--stacked-borrows", "print.minirust"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn dump() {
+    let out = minirun(&["--dump", "print.minirust"]);
+    assert_eq!(out.status.code(), Some(0));
+    let dumped = stdout(&out);
+    assert!(dumped.contains("print(42_u32)"));
+}
+
+#[test]
+fn json_error() {
+    let out = minirun(&["syntax_error.json"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert_eq!(
+        stderr(&out),
+        "fatal error: syntax_error.json: JSON error at `program.start`: expected a 32-bit unsigned integer\n"
+    );
+}
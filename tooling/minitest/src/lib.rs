@@ -1,8 +1,12 @@
 #![cfg(test)]
 
 pub use miniutil::build::*;
+pub use miniutil::explore::*;
 pub use miniutil::fmt::*;
+pub use miniutil::json::*;
+pub use miniutil::parse::*;
 pub use miniutil::run::*;
+pub use miniutil::schedule::*;
 pub use miniutil::BasicMem;
 
 pub use minirust_rs::libspecr::hidden::*;
@@ -15,10 +19,63 @@ pub use minirust_rs::prelude::NdResult;
 pub use minirust_rs::prelude::*;
 
 pub use std::format;
+pub use std::result::Result;
 pub use std::string::String;
 
 mod tests;
 
+/// Check that the program is unchanged when it is formatted and parsed back,
+/// and when it is encoded to JSON and decoded back.
+#[track_caller]
+pub fn assert_round_trip(prog: Program) {
+    let text = fmt_program(prog);
+    match parse_program(&text) {
+        Ok(parsed) => assert!(parsed == prog, "program changed when parsing it back:\n{text}"),
+        Err(err) => panic!("{err}\n{text}"),
+    }
+    let json = program_to_json(prog);
+    match program_from_json(&json) {
+        Ok(decoded) =>
+            assert!(decoded == prog, "program changed when decoding it from JSON:\n{json}"),
+        Err(err) => panic!("{err}\n{json}"),
+    }
+}
+
+// These shadow the functions from `miniutil::run`, so that every program run by a test is
+// also checked to round-trip through the textual syntax and JSON.
+
+#[track_caller]
+pub fn run_program<M: Memory>(prog: Program) -> TerminationInfo {
+    assert_round_trip(prog);
+    miniutil::run::run_program::<M>(prog)
+}
+
+#[track_caller]
+pub fn get_stdout<M: Memory>(prog: Program) -> Result<Vec<String>, TerminationInfo> {
+    assert_round_trip(prog);
+    miniutil::run::get_stdout::<M>(prog)
+}
+
+/// Explore all interleavings of the program and return their distinct outcomes.
+/// This fails if the exploration hits one of the default bounds.
+#[track_caller]
+pub fn explore_outcomes<M: Memory>(prog: Program) -> Vec<Outcome> {
+    assert_round_trip(prog);
+    let exploration = explore::<M>(prog, ExploreBounds::default());
+    assert!(exploration.complete, "exploration did not complete: {exploration:?}");
+    exploration.outcomes
+}
+
+/// Check that the outcomes of all interleavings of the program are exactly `expected`, in any order.
+#[track_caller]
+pub fn assert_outcomes<M: Memory>(prog: Program, expected: &[Outcome]) {
+    let mut outcomes = explore_outcomes::<M>(prog);
+    let mut expected = expected.to_vec();
+    outcomes.sort_by_key(|outcome| outcome.to_string());
+    expected.sort_by_key(|outcome| outcome.to_string());
+    assert_eq!(outcomes, expected);
+}
+
 #[track_caller]
 pub fn assert_stop<M: Memory>(prog: Program) {
     assert_eq!(run_program::<M>(prog), TerminationInfo::MachineStop);
@@ -39,19 +96,18 @@ pub fn assert_abort<M: Memory>(prog: Program, msg: &str) {
 
 #[track_caller]
 pub fn assert_ub<M: Memory>(prog: Program, msg: &str) {
-    assert_eq!(
-        run_program::<M>(prog),
-        TerminationInfo::Ub(minirust_rs::prelude::String::from_internal(msg.to_string()))
-    );
+    match run_program::<M>(prog) {
+        TerminationInfo::Ub(ub) => assert_eq!(ub.to_string(), msg),
+        termination_info => panic!("expected UB, got {termination_info:?}"),
+    }
 }
 
 #[track_caller]
 pub fn assert_ub_eventually<M: Memory>(prog: Program, attempts: usize, msg: &str) {
-    let msg = minirust_rs::prelude::String::from_internal(msg.to_string());
     for _ in 0..attempts {
         match run_program::<M>(prog) {
             TerminationInfo::MachineStop => continue,
-            TerminationInfo::Ub(res) if res == msg => {
+            TerminationInfo::Ub(ub) if ub.to_string() == msg => {
                 // Got the expected result.
                 return;
             }
@@ -85,7 +141,11 @@ pub fn assert_ill_formed<M: Memory>(prog: Program, msg: &str) {
     let TerminationInfo::IllFormed(info) = run_program::<M>(prog) else {
         panic!("program is not ill formed!")
     };
-    assert_eq!(info.get_internal(), msg, "program is ill-formed with a different error message");
+    assert_eq!(
+        info.msg.get_internal(),
+        msg,
+        "program is ill-formed with a different error message"
+    );
 }
 
 #[track_caller]
@@ -98,23 +158,17 @@ pub fn assert_memory_leak<M: Memory>(prog: Program) {
     assert_eq!(run_program::<M>(prog), TerminationInfo::MemoryLeak);
 }
 
-/// Run the program multiple times. Checks if we get a data race in some execution
-/// This automatically fails if the program does not terminate correctly if the data race did not occur.
+/// Explore all interleavings of the program and check whether any of them has a data race.
+/// This automatically fails if an interleaving without a data race does not terminate correctly.
 #[track_caller]
 pub fn has_data_race<M: Memory>(prog: Program) -> bool {
-    let data_race_string = minirust_rs::prelude::String::from_internal("Data race".to_string());
-
-    for _ in 0..32 {
-        match run_program::<M>(prog) {
-            TerminationInfo::MachineStop => {}
-            TerminationInfo::Ub(ub) if ub == data_race_string => {
-                return true;
-            }
-            termination_info => {
-                panic!("unexpected outcome in `has_data_race`: {:?}", termination_info);
-            }
+    let mut race = false;
+    for outcome in explore_outcomes::<M>(prog) {
+        match outcome {
+            Outcome::Stop(_) => {}
+            Outcome::Ub(UndefinedBehavior::DataRace(_)) => race = true,
+            outcome => panic!("unexpected outcome in `has_data_race`: {outcome}"),
         }
     }
-
-    false
+    race
 }
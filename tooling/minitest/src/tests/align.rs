@@ -193,6 +193,8 @@ fn addr_of_misaligned_ref() {
         p,
         "taking the address of an invalid (null, misaligned, or uninhabited) place",
     );
+    let TerminationInfo::Ub(ub) = run_program::<BasicMem>(p) else { unreachable!() };
+    assert!(matches!(ub, UndefinedBehavior::Misaligned(_)));
 }
 
 /// Same test as above, but with a raw pointer it's fine.
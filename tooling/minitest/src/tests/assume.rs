@@ -28,6 +28,7 @@ fn assume_wrong_argnum() {
         arguments: list![], // no arguments
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
     let f = function(Ret::No, 0, &locals, &[b0, b1]);
@@ -43,6 +44,7 @@ fn assume_wrong_argty() {
         arguments: list![const_int::<i32>(0)], // should be bool, not int
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
     let f = function(Ret::No, 0, &locals, &[b0, b1]);
@@ -25,10 +25,11 @@ fn atomic_store_success() {
 #[test]
 fn atomic_store_arg_count() {
     let b0 = block!(Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(MemoryOrdering::SeqCst),
         arguments: list!(),
         ret: unit_place(),
-        next_block: Some(BbName(Name::from_internal(1)))
+        next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -40,10 +41,11 @@ fn atomic_store_arg_count() {
 #[test]
 fn atomic_store_arg_type1() {
     let b0 = block!(Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(MemoryOrdering::SeqCst),
         arguments: list!(const_int::<u32>(0), const_int::<u32>(0)),
         ret: unit_place(),
-        next_block: Some(BbName(Name::from_internal(1)))
+        next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -66,10 +68,11 @@ fn atomic_store_arg_type_pow() {
     let b0 = block!(
         storage_live(0),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicStore,
+            intrinsic: IntrinsicOp::AtomicStore(MemoryOrdering::SeqCst),
             arguments: list!(addr_of(local(0), ptr_ty), arr),
             ret: unit_place(),
-            next_block: Some(BbName(Name::from_internal(1)))
+            next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -93,10 +96,11 @@ fn atomic_store_arg_type_size() {
     let b0 = block!(
         storage_live(0),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicStore,
+            intrinsic: IntrinsicOp::AtomicStore(MemoryOrdering::SeqCst),
             arguments: list!(addr_of(local(0), ptr_ty), arr),
             ret: unit_place(),
-            next_block: Some(BbName(Name::from_internal(1)))
+            next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -115,10 +119,11 @@ fn atomic_store_ret_type() {
     let b0 = block!(
         storage_live(0),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicStore,
+            intrinsic: IntrinsicOp::AtomicStore(MemoryOrdering::SeqCst),
             arguments: list!(addr_of(local(0), ptr_ty), const_int::<u64>(0)),
             ret: local(0),
-            next_block: Some(BbName(Name::from_internal(1)))
+            next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -157,10 +162,11 @@ fn atomic_load_arg_count() {
     let b0 = block!(
         storage_live(0),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicLoad,
+            intrinsic: IntrinsicOp::AtomicLoad(MemoryOrdering::SeqCst),
             arguments: list!(),
             ret: local(0),
-            next_block: Some(BbName(Name::from_internal(1)))
+            next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -177,10 +183,11 @@ fn atomic_load_arg_type() {
     let b0 = block!(
         storage_live(0),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicLoad,
+            intrinsic: IntrinsicOp::AtomicLoad(MemoryOrdering::SeqCst),
             arguments: list!(unit()),
             ret: local(0),
-            next_block: Some(BbName(Name::from_internal(1)))
+            next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
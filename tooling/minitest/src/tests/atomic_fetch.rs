@@ -36,10 +36,11 @@ fn atomic_fetch_arg_count() {
     let locals = [];
 
     let b0 = block!(Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicFetchAndOp(IntBinOp::Add),
+        intrinsic: IntrinsicOp::AtomicFetchAndOp(IntBinOp::Add, MemoryOrdering::SeqCst),
         arguments: list!(),
         ret: unit_place(),
-        next_block: Some(BbName(Name::from_internal(1)))
+        next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -149,10 +150,11 @@ fn atomic_fetch_op() {
         storage_live(1),
         assign(local(0), const_int::<u32>(3)),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicFetchAndOp(IntBinOp::Mul),
+            intrinsic: IntrinsicOp::AtomicFetchAndOp(IntBinOp::Mul, MemoryOrdering::SeqCst),
             arguments: list!(addr_of(local(0), ptr_ty), const_int::<u32>(1)),
             ret: local(1),
-            next_block: Some(BbName(Name::from_internal(1)))
+            next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -0,0 +1,99 @@
+use crate::*;
+
+fn funcs(backtrace: &Backtrace) -> Vec<FnName> {
+    backtrace.frames.iter().map(|frame| frame.func).collect()
+}
+
+#[test]
+fn backtrace_of_ub() {
+    let mut p = ProgramBuilder::new();
+
+    let bad = {
+        let mut f = p.declare_function();
+        f.unreachable();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        f.call_ignoreret(bad, &[]);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    let (info, _, backtraces) =
+        run_program_with_backtraces::<BasicMem>(p, None, None, None).unwrap();
+    assert!(matches!(info, TerminationInfo::Ub(_)));
+    assert_eq!(backtraces.len(), 1);
+    assert!(backtraces[0].active);
+    assert_eq!(funcs(&backtraces[0]), [bad, main]);
+
+    // The caller is still at its call terminator.
+    let caller = backtraces[0].frames[1];
+    assert_eq!(
+        Int::from(caller.stmt),
+        p.functions.index_at(main).blocks.index_at(caller.block).statements.len()
+    );
+}
+
+/// Functions are identified by name, not by their body.
+#[test]
+fn backtrace_with_identical_functions() {
+    let mut p = ProgramBuilder::new();
+
+    let [first, second] = [(); 2].map(|()| {
+        let mut f = p.declare_function();
+        f.unreachable();
+        p.finish_function(f)
+    });
+
+    let main = {
+        let mut f = p.declare_function();
+        f.call_ignoreret(second, &[]);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_eq!(p.functions.index_at(first), p.functions.index_at(second));
+    let (_, _, backtraces) = run_program_with_backtraces::<BasicMem>(p, None, None, None).unwrap();
+    assert_eq!(funcs(&backtraces[0]), [second, main]);
+}
+
+#[test]
+fn backtraces_of_deadlock() {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+
+    let worker = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.lock_acquire(load(lock));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.lock_create(lock);
+        f.lock_acquire(load(lock));
+        f.spawn(worker, null(), thread_id);
+        f.join(load(thread_id));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    let schedule = Some(Schedule::Seeded(0));
+    let (info, _, backtraces) =
+        run_program_with_backtraces::<BasicMem>(p, schedule, None, None).unwrap();
+    assert_eq!(info, TerminationInfo::Deadlock);
+    assert_eq!(backtraces.iter().map(|b| b.thread).collect::<Vec<_>>(), [0, 1]);
+    // No thread is to blame for a deadlock.
+    assert!(backtraces.iter().all(|b| !b.active));
+    assert_eq!(funcs(&backtraces[0]), [main]);
+    assert_eq!(funcs(&backtraces[1]), [worker]);
+}
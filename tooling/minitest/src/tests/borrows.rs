@@ -0,0 +1,50 @@
+use crate::*;
+
+use miniutil::borrows::*;
+use miniutil::mock_write::MockWrite;
+use miniutil::TreeBorrowMem;
+
+/// Writes to `x` directly after writing through a mutable reference to it,
+/// and then reads through that reference again.
+fn use_after_foreign_write() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<i32>();
+    let r = f.declare_local::<&mut i32>();
+    f.storage_live(x);
+    f.storage_live(r);
+    f.assign(x, const_int(0_i32));
+    f.assign(r, addr_of(x, <&mut i32>::get_type()));
+    f.validate(r, false);
+    f.assign(deref(load(r), <i32>::get_type()), const_int(1_i32));
+    f.assign(x, const_int(2_i32));
+    f.assign(x, load(deref(load(r), <i32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+fn dump(prog: Program, format: BorrowFormat) -> Vec<String> {
+    let out = MockWrite::new();
+    let mut dumper = BorrowDumper::new(DumpBorrowsAt::Ub, format, out.clone());
+    let (info, _, _) =
+        run_program_with_backtraces::<TreeBorrowMem>(prog, None, None, Some(&mut dumper)).unwrap();
+    assert!(matches!(info, TerminationInfo::Ub(_)));
+    out.into_strings()
+}
+
+#[test]
+fn dump_borrows_before_ub() {
+    let p = use_after_foreign_write();
+
+    let lines = dump(p, BorrowFormat::Text);
+    assert!(lines.iter().any(|l| l.starts_with("alloc") && l.ends_with("(Stack, 4 bytes):")));
+    assert!(lines.iter().any(|l| l.trim_start().starts_with("[]: 0..4: ")));
+    // The write to `x` disabled the reference.
+    assert!(lines.iter().any(|l| l.trim_start() == "[0]: 0..4: Disabled"));
+
+    let lines = dump(p, BorrowFormat::Dot);
+    assert_eq!(lines.first().unwrap(), "digraph borrows {");
+    assert!(lines.iter().any(|l| l.contains(" -> ")));
+    assert_eq!(lines.last().unwrap(), "}");
+}
@@ -19,6 +19,7 @@ fn call_success() {
             arguments: list![by_value(unit())],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -41,6 +42,7 @@ fn call_non_exist() {
             arguments: list![by_value(unit())],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -51,6 +53,44 @@ fn call_non_exist() {
     assert_ill_formed::<BasicMem>(p, "Constant::FnPointer: invalid function name");
 }
 
+/// A global holding a pointer to the given function.
+fn fn_ptr_global(fn_name: u32) -> Global {
+    let fn_pointers = list![(Size::ZERO, FnName(Name::from_internal(fn_name)))];
+    Global { fn_pointers, ..global_ptr::<u8>() }
+}
+
+#[test]
+fn call_fn_ptr_from_global() {
+    let locals = [<()>::get_type()];
+    let relocation = Relocation { name: GlobalName(Name::from_internal(0)), offset: Size::ZERO };
+    let global = ValueExpr::Constant(Constant::GlobalPointer(relocation), <*const u8>::get_type());
+
+    let b0 = block!(
+        storage_live(0),
+        Terminator::Call {
+            callee: load(deref(global, Type::Ptr(PtrType::FnPtr))),
+            calling_convention: CallingConvention::C,
+            arguments: list![by_value(unit())],
+            ret: local(0),
+            next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
+        }
+    );
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program_with_globals(&[f, other_f()], &[fn_ptr_global(1)]);
+    assert_round_trip(p);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn global_fn_ptr_non_exist() {
+    let f = function(Ret::No, 0, &[], &[block!(exit())]);
+    let p = program_with_globals(&[f], &[fn_ptr_global(1)]);
+    assert_ill_formed::<BasicMem>(p, "Program: global pointing to an invalid function");
+}
+
 #[test]
 fn call_arg_count() {
     let locals = [<()>::get_type()];
@@ -63,6 +103,7 @@ fn call_arg_count() {
             arguments: list![],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -85,6 +126,7 @@ fn call_arg_abi() {
             arguments: list![by_value(const_int::<i32>(42))],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -107,6 +149,7 @@ fn call_ret_abi() {
             arguments: list![by_value(unit())],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
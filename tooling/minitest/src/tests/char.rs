@@ -0,0 +1,85 @@
+use crate::*;
+
+fn transmute_to_char(i: u32) -> ValueExpr {
+    transmute(const_int(i), <char>::get_type())
+}
+
+#[test]
+fn char_to_u32_and_back() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let c = f.declare_local::<char>();
+    f.storage_live(c);
+    f.assign(c, const_char('ß'));
+    f.print(load(c));
+    f.print(transmute(load(c), <u32>::get_type()));
+    f.assign(c, transmute_to_char(0x10FFFF));
+    f.print(transmute(load(c), <u32>::get_type()));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["ß", "223", "1114111"]);
+}
+
+#[test]
+fn compare_chars() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    f.print(lt(const_char('a'), const_char('b')));
+    f.print(eq(const_char('a'), const_char('b')));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["true", "false"]);
+}
+
+/// Like `minimize`, we switch on a `char` by transmuting it to `u32`.
+#[test]
+fn switch_on_char() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let c = transmute(const_char('b'), <u32>::get_type());
+    f.switch_int(
+        c,
+        &[
+            ('a' as u32, &|f| f.print(const_int(0_u32))),
+            ('b' as u32, &|f| f.print(const_int(1_u32))),
+        ],
+        |f| f.print(const_int(2_u32)),
+    );
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["1"]);
+}
+
+#[test]
+fn surrogate_is_invalid() {
+    assert_ub_expr::<char, BasicMem>(
+        transmute_to_char(0xD800),
+        "transmuted value is not valid at new type",
+    );
+    assert_ub_expr::<char, BasicMem>(
+        transmute_to_char(0xDFFF),
+        "transmuted value is not valid at new type",
+    );
+}
+
+#[test]
+fn too_large_is_invalid() {
+    assert_ub_expr::<char, BasicMem>(
+        transmute_to_char(0x110000),
+        "transmuted value is not valid at new type",
+    );
+}
+
+#[test]
+fn invalid_char_constant() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    f.print(ValueExpr::Constant(Constant::Int(Int::from(0xD800)), <char>::get_type()));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "Constant::Int: invalid char value");
+}
@@ -60,10 +60,14 @@ fn compare_exchange_arg_count() {
         storage_live(1),
         assign(local(0), const_int::<u32>(10)),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicCompareExchange,
+            intrinsic: IntrinsicOp::AtomicCompareExchange {
+                success: MemoryOrdering::SeqCst,
+                failure: MemoryOrdering::SeqCst,
+            },
             arguments: list!(addr0),
             ret: local(1),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
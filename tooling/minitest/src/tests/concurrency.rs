@@ -1,7 +1,6 @@
 use crate::*;
 
-/// This is a probabilistic test to check that the scheduler allows for multiple orderings.
-/// The probability of failure is: 2*(1/2)^20 which is about 0.0002% with a fair scheduler.
+/// Check that the scheduler allows for multiple orderings.
 #[test]
 fn arbitrary_order() {
     /// A function that writes 1 to the global(1).
@@ -54,23 +53,6 @@ fn arbitrary_order() {
     let p = program_with_globals(&[f, write_1()], &globals);
 
     // We now test, that the program can both finish with global(1) = 1 and = 2.
-    let mut write_1 = false;
-    let mut write_2 = false;
-
-    for _ in 0..20 {
-        let out = match get_stdout::<BasicMem>(p) {
-            Ok(out) => out,
-            Err(err) => panic!("{:?}", err),
-        };
-
-        if out[0] == "1" {
-            write_1 = true;
-        }
-        if out[0] == "2" {
-            write_2 = true;
-        }
-    }
-
-    assert!(write_1);
-    assert!(write_2);
+    let stop = |out: &str| Outcome::Stop(vec![out.to_string()]);
+    assert_outcomes::<BasicMem>(p, &[stop("1"), stop("2")]);
 }
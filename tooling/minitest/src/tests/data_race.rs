@@ -151,3 +151,95 @@ fn non_atomic_store_non_atomic_store() {
 
     assert!(has_data_race::<BasicMem>(p))
 }
+
+/// A second thread writes non-atomically to a global and then sets a flag with the given ordering.
+/// The main thread loads the flag once with the given ordering, and reads the global non-atomically if
+/// it is set. So the race can only happen in some executions.
+fn message_passing(flag_store: MemoryOrdering, flag_load: MemoryOrdering) -> Program {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let flag = p.declare_global_zero_initialized::<u32>();
+
+    let writer = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.assign(data, const_int(42_u32));
+        f.atomic_store_ordered(addr_of(flag, raw_void_ptr_ty()), const_int(1_u32), flag_store);
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        let seen = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.storage_live(seen);
+        f.spawn(writer, null(), thread_id);
+        f.atomic_load_ordered(seen, addr_of(flag, raw_void_ptr_ty()), flag_load);
+        f.if_(eq(load(seen), const_int(1_u32)), |f| f.print(load(data)), |_| {});
+        f.join(load(thread_id));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    p.finish_program(main)
+}
+
+#[test]
+fn release_acquire_synchronizes() {
+    let p = message_passing(MemoryOrdering::Release, MemoryOrdering::Acquire);
+    assert!(!has_data_race::<BasicMem>(p));
+}
+
+#[test]
+fn relaxed_does_not_synchronize() {
+    let p = message_passing(MemoryOrdering::Relaxed, MemoryOrdering::Relaxed);
+    assert!(has_data_race::<BasicMem>(p));
+}
+
+/// The racing accesses are many steps apart, but the race is still detected in every interleaving.
+#[test]
+fn race_across_many_steps() {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+
+    let writer = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.assign(data, const_int(1_u32));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        let counter = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.storage_live(counter);
+        f.assign(counter, const_int(0_u32));
+        f.spawn(writer, null(), thread_id);
+        f.while_(lt(load(counter), const_int(20_u32)), |f| {
+            f.assign(counter, add(load(counter), const_int(1_u32)));
+        });
+        f.print(load(data));
+        f.join(load(thread_id));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    for outcome in explore_outcomes::<BasicMem>(p) {
+        let Outcome::Ub(UndefinedBehavior::DataRace(msg)) = outcome else {
+            panic!("data race was not detected: {outcome}");
+        };
+        let msg = msg.get_internal();
+        // The error mentions both accesses, in the order in which they happened.
+        assert!(
+            msg.starts_with("Data race: non-atomic store by thread 1 at address ")
+                || msg.starts_with("Data race: non-atomic load by thread 0 at address "),
+            "unexpected error: {msg}"
+        );
+    }
+}
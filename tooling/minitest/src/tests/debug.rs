@@ -0,0 +1,127 @@
+use crate::*;
+
+use miniutil::debug::{Breakpoint, Debugger, Stop};
+use miniutil::mock_write::MockWrite;
+
+fn debugger(prog: Program) -> Debugger<BasicMem> {
+    let out = MockWrite::new();
+    Debugger::new(prog, Schedule::Seeded(0), out, std::io::stderr()).unwrap()
+}
+
+#[test]
+fn breakpoint_and_locals() {
+    let mut p = ProgramBuilder::new();
+
+    let callee = {
+        let mut f = p.declare_function();
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u32>();
+        f.storage_live(x);
+        f.assign(x, const_int(42_u32));
+        f.call_ignoreret(callee, &[]);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    let mut dbg = debugger(p);
+    let bp = Breakpoint { func: callee, block: None };
+    dbg.add_breakpoint(bp).unwrap();
+    assert_eq!(dbg.cont(), Stop::Breakpoint(0, bp));
+    let funcs: Vec<FnName> = dbg.frames(0).iter().map(|frame| frame.func).collect();
+    assert_eq!(funcs, [callee, main]);
+
+    // Frame 1 is the caller.
+    let locals = dbg.fmt_locals(0, 1).unwrap();
+    assert!(locals.iter().any(|local| local.ends_with(": u32 = 42")));
+    assert!(dbg.fmt_locals(0, 2).is_err());
+    let allocations = dbg.fmt_allocations();
+    assert!(allocations.iter().any(|a| a.ends_with(": Stack, size 4, align 4: 2a 00 00 00")));
+
+    assert_eq!(dbg.cont(), Stop::Terminated(TerminationInfo::MachineStop));
+    assert!(dbg.terminated());
+}
+
+#[test]
+fn selected_thread_runs() {
+    let mut p = ProgramBuilder::new();
+
+    let worker = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.spawn(worker, null(), thread_id);
+        f.join(load(thread_id));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    let mut dbg = debugger(p);
+    // `storage_live` and `spawn`.
+    assert_eq!(dbg.step(), None);
+    assert_eq!(dbg.step(), None);
+    assert_eq!(dbg.thread_count(), 2);
+    assert!(dbg.select_thread(2).is_err());
+
+    // The selected thread executes all steps until it terminates.
+    dbg.select_thread(1).unwrap();
+    let main_frames = dbg.frames(0);
+    while !dbg.frames(1).is_empty() {
+        assert_eq!(dbg.current_thread(), 1);
+        assert_eq!(dbg.step(), None);
+    }
+    assert_eq!(dbg.frames(0), main_frames);
+    assert!(dbg.select_thread(1).is_err());
+
+    assert_eq!(dbg.cont(), Stop::Terminated(TerminationInfo::MachineStop));
+}
+
+#[test]
+fn zero_sized_locals() {
+    let mut p = ProgramBuilder::new();
+
+    let callee = {
+        let mut f = p.declare_function();
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let units = [(); 3].map(|()| f.declare_local::<()>());
+        let x = f.declare_local::<u32>();
+        for unit in units {
+            f.storage_live(unit);
+        }
+        f.storage_live(x);
+        f.assign(x, const_int(7_u32));
+        f.call_ignoreret(callee, &[]);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    let mut dbg = debugger(p);
+    let bp = Breakpoint { func: callee, block: None };
+    dbg.add_breakpoint(bp).unwrap();
+    assert_eq!(dbg.cont(), Stop::Breakpoint(0, bp));
+
+    // Zero-sized locals may share their address with other allocations, all locals are found.
+    // That includes the return local.
+    let locals = dbg.fmt_locals(0, 1).unwrap();
+    assert_eq!(locals.len(), 5);
+    assert!(locals.iter().any(|local| local.ends_with(": u32 = 7")));
+}
@@ -0,0 +1,169 @@
+use crate::*;
+
+/// Two threads that each print twice.
+fn racy_prints() -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let worker = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.print(const_int(2_u32));
+        f.print(const_int(2_u32));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.spawn(worker, null(), thread_id);
+        f.print(const_int(1_u32));
+        f.print(const_int(1_u32));
+        f.join(load(thread_id));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    p.finish_program(main)
+}
+
+fn stop(out: &[&str]) -> Outcome {
+    Outcome::Stop(out.iter().map(|s| s.to_string()).collect())
+}
+
+#[test]
+fn all_print_orders() {
+    let expected = [
+        stop(&["1", "1", "2", "2"]),
+        stop(&["1", "2", "1", "2"]),
+        stop(&["1", "2", "2", "1"]),
+        stop(&["2", "1", "1", "2"]),
+        stop(&["2", "1", "2", "1"]),
+        stop(&["2", "2", "1", "1"]),
+    ];
+    assert_outcomes::<BasicMem>(racy_prints(), &expected);
+}
+
+#[test]
+fn preemption_bound() {
+    let bounds = ExploreBounds { preemptions: Some(0), ..ExploreBounds::default() };
+    let exploration = explore::<BasicMem>(racy_prints(), bounds);
+    // Without preemptions, the main thread runs until it blocks on the join.
+    assert_eq!(exploration.outcomes, [stop(&["1", "1", "2", "2"])]);
+    assert!(!exploration.complete);
+}
+
+/// The threads acquire the same two locks in opposite order, which deadlocks in some interleavings.
+#[test]
+fn lock_order_inversion() {
+    let mut p = ProgramBuilder::new();
+    let lock_a = p.declare_global_zero_initialized::<u32>();
+    let lock_b = p.declare_global_zero_initialized::<u32>();
+
+    let worker = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.lock_acquire(load(lock_b));
+        f.lock_acquire(load(lock_a));
+        f.lock_release(load(lock_a));
+        f.lock_release(load(lock_b));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.lock_create(lock_a);
+        f.lock_create(lock_b);
+        f.spawn(worker, null(), thread_id);
+        f.lock_acquire(load(lock_a));
+        f.lock_acquire(load(lock_b));
+        f.lock_release(load(lock_b));
+        f.lock_release(load(lock_a));
+        f.join(load(thread_id));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_outcomes::<BasicMem>(p, &[stop(&[]), Outcome::Deadlock]);
+}
+
+/// Threads that only access their own memory do not need to be interleaved at all.
+#[test]
+fn independent_threads() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_zero_initialized::<u32>();
+    let y = p.declare_global_zero_initialized::<u32>();
+
+    let worker = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.while_(lt(load(x), const_int(10_u32)), |f| {
+            f.assign(x, add(load(x), const_int(1_u32)));
+        });
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.spawn(worker, null(), thread_id);
+        f.while_(lt(load(y), const_int(10_u32)), |f| {
+            f.assign(y, add(load(y), const_int(1_u32)));
+        });
+        f.join(load(thread_id));
+        f.print(load(x));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    let exploration = explore::<BasicMem>(p, ExploreBounds::default());
+    assert_eq!(exploration.outcomes, [stop(&["10"])]);
+    assert!(exploration.complete);
+    assert_eq!(exploration.executions, 1);
+}
+
+/// When both workers are blocked on the lock, which of them acquires it on release is explored as
+/// well. If it was left to chance, replaying the executions would diverge.
+#[test]
+fn lock_acquirers() {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+
+    let worker = {
+        let mut f = p.declare_function();
+        let arg = f.declare_arg::<*const ()>();
+        f.lock_acquire(load(lock));
+        f.print(transmute(load(arg), <usize>::get_type()));
+        f.lock_release(load(lock));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let first = f.declare_local::<u32>();
+        let second = f.declare_local::<u32>();
+        f.storage_live(first);
+        f.storage_live(second);
+        f.lock_create(lock);
+        f.lock_acquire(load(lock));
+        f.spawn(worker, transmute(const_int(1_usize), <*const ()>::get_type()), first);
+        f.spawn(worker, transmute(const_int(2_usize), <*const ()>::get_type()), second);
+        f.lock_release(load(lock));
+        f.join(load(first));
+        f.join(load(second));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_outcomes::<BasicMem>(p, &[stop(&["1", "2"]), stop(&["2", "1"])]);
+}
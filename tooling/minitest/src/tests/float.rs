@@ -0,0 +1,124 @@
+use crate::*;
+
+#[test]
+fn float_arith_works() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    f.assume(eq(float_add(const_f32(1.5), const_f32(2.0)), const_f32(3.5)));
+    f.assume(eq(float_sub(const_f64(1.0), const_f64(2.5)), const_f64(-1.5)));
+    f.assume(eq(float_mul(const_f32(3.0), const_f32(-0.5)), const_f32(-1.5)));
+    f.assume(eq(float_div(const_f64(1.0), const_f64(4.0)), const_f64(0.25)));
+    f.assume(eq(float_rem(const_f64(7.5), const_f64(2.0)), const_f64(1.5)));
+    f.assume(eq(float_neg(const_f32(2.0)), const_f32(-2.0)));
+    f.assume(lt(const_f64(-1.0), const_f64(0.0)));
+    // Negative and positive zero compare equal.
+    f.assume(eq(const_f64(-0.0), const_f64(0.0)));
+    // Rounding happens at the precision of the operand type.
+    f.assume(eq(float_add(const_f32(16777216.0), const_f32(1.0)), const_f32(16777216.0)));
+
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn float_nan_not_equal() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    let nan = float_div(const_f64(0.0), const_f64(0.0));
+    f.assume(not(eq(nan, nan)));
+    f.assume(ne(nan, nan));
+    f.assume(not(lt(nan, const_f64(0.0))));
+    f.assume(not(ge(nan, const_f64(0.0))));
+
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_outcomes::<BasicMem>(p, &[Outcome::Stop(vec![])]);
+}
+
+#[test]
+fn float_nan_sign_explored() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    let nan = float_div(const_f64(0.0), const_f64(0.0));
+    f.print(shr(transmute(nan, <u64>::get_type()), const_int(63_u32)));
+
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    let stop = |out: &str| Outcome::Stop(vec![out.to_string()]);
+    assert_outcomes::<BasicMem>(p, &[stop("0"), stop("1")]);
+}
+
+#[test]
+fn float_casts() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    f.assume(eq(float_to_int::<i32>(const_f64(-3.9)), const_int(-3i32)));
+    // Float-to-int casts saturate.
+    f.assume(eq(float_to_int::<u8>(const_f32(300.0)), const_int(255u8)));
+    f.assume(eq(float_to_int::<u8>(const_f32(-1.0)), const_int(0u8)));
+    f.assume(eq(float_to_int::<i8>(const_f64(f64::NEG_INFINITY)), const_int(-128i8)));
+    // NaN turns into 0.
+    let nan = float_div(const_f32(0.0), const_f32(0.0));
+    f.assume(eq(float_to_int::<i64>(nan), const_int(0i64)));
+
+    f.assume(eq(int_to_float::<f64>(const_int(-7i32)), const_f64(-7.0)));
+    f.assume(eq(int_to_float::<f32>(const_int(u128::MAX)), const_f32(u128::MAX as f32)));
+
+    f.assume(eq(float_cast::<f32>(const_f64(0.1)), const_f32(0.1)));
+    f.assume(eq(float_cast::<f64>(const_f32(0.5)), const_f64(0.5)));
+
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn float_mixed_types_ill_formed() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    f.assume(eq(float_add(const_f32(1.0), const_f64(1.0)), const_f64(2.0)));
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "BinOp::Float: invalid right type");
+}
+
+#[test]
+fn float_cmp_ill_formed() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    f.assume(eq(cmp(const_f32(1.0), const_f32(2.0)), const_int(-1i8)));
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "BinOp::Rel: cannot three-way compare floats");
+}
@@ -0,0 +1,71 @@
+use crate::*;
+
+use miniutil::generate::*;
+use miniutil::TreeBorrowMem;
+
+const SEEDS: u64 = 50;
+
+/// Runs `f` on a thread with a large stack.
+/// Generated expressions nest deeply, and evaluating them recursively can exhaust
+/// the default stack of a test thread in debug builds.
+fn with_large_stack(f: impl FnOnce() + Send + 'static) {
+    let thread = std::thread::Builder::new().stack_size(16 << 20).spawn(f).unwrap();
+    if let Err(err) = thread.join() {
+        std::panic::resume_unwind(err);
+    }
+}
+
+#[test]
+fn generated_programs_run() {
+    with_large_stack(|| {
+        let config = GenConfig::default();
+        for seed in 0..SEEDS {
+            let prog = generate_program(seed, &config);
+            // With a schedule, runs do not depend on `pick` finding an enabled thread by chance.
+            let (info, _) =
+                run_program_with_schedule::<BasicMem>(prog, Schedule::Seeded(seed)).unwrap();
+            assert_eq!(info, TerminationInfo::MachineStop, "seed {seed}:\n{}", fmt_program(prog));
+        }
+    });
+}
+
+#[test]
+fn memory_models_agree() {
+    with_large_stack(|| {
+        let config = GenConfig::default();
+        for seed in 0..SEEDS {
+            let prog = generate_program(seed, &config);
+            let schedule = Schedule::Seeded(seed);
+            let (basic, _) = get_stdout_with_schedule::<BasicMem>(prog, schedule.clone()).unwrap();
+            let (tree_borrows, _) =
+                get_stdout_with_schedule::<TreeBorrowMem>(prog, schedule).unwrap();
+            let (basic, tree_borrows) = (basic.unwrap(), tree_borrows.unwrap());
+            assert_eq!(basic, tree_borrows, "seed {seed}:\n{}", fmt_program(prog));
+        }
+    });
+}
+
+#[test]
+fn generation_is_deterministic() {
+    let config = GenConfig::default();
+    let first = generate_program(7, &config);
+    let second = generate_program(7, &config);
+    assert!(first == second);
+    assert!(generate_program(8, &config) != first);
+}
+
+#[test]
+fn generate_without_features() {
+    let config = GenConfig {
+        pointers: false,
+        unions: false,
+        enums: false,
+        threads: false,
+        intrinsics: false,
+        ..GenConfig::default()
+    };
+    for seed in 0..SEEDS {
+        let prog = generate_program(seed, &config);
+        assert_eq!(run_program::<BasicMem>(prog), TerminationInfo::MachineStop);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::*;
 
+use miniutil::{StackedBorrowMem, TreeBorrowMem};
+
 #[test]
 fn dynamic_memory() {
     let locals = [<*const i32>::get_type(), <i32>::get_type()];
@@ -35,6 +37,7 @@ fn alloc_argcount() {
             arguments: list![],
             ret: local(0),
             next_block: None,
+            unwind_block: None,
         },
     );
 
@@ -55,6 +58,7 @@ fn alloc_align_err() {
             arguments: list![const_int::<usize>(4), const_int::<usize>(13)], // 13 is no power of two! hence error!
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         },
     );
     let b1 = block!(exit());
@@ -76,6 +80,7 @@ fn alloc_size_err() {
             arguments: list![const_int::<isize>(-1), const_int::<usize>(4)], // -1 is not a valid size!
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         },
     );
     let b1 = block!(exit());
@@ -98,6 +103,7 @@ fn alloc_wrongarg1() {
             arguments: list![const_bool(true), const_int::<usize>(4)],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         },
     );
     let b1 = block!(exit());
@@ -120,6 +126,7 @@ fn alloc_wrongarg2() {
             arguments: list![const_int::<usize>(4), const_bool(true)],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         },
     );
     let b1 = block!(exit());
@@ -141,6 +148,7 @@ fn alloc_wrongreturn() {
             arguments: list![const_int::<usize>(4), const_int::<usize>(4)],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         },
     );
     let b1 = block!(exit());
@@ -164,6 +172,7 @@ fn dealloc_success() {
         arguments: list![load(local(0)), const_int::<usize>(4), const_int::<usize>(4)],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -182,6 +191,7 @@ fn dealloc_argcount() {
         arguments: list![],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     },);
     let b1 = block!(exit());
 
@@ -204,6 +214,7 @@ fn dealloc_align_err() {
         arguments: list![load(local(0)), const_int::<usize>(4), const_int::<usize>(13)], // 13 is not a power of two!
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -226,6 +237,7 @@ fn dealloc_size_err() {
         arguments: list![load(local(0)), const_int::<isize>(-1), const_int::<usize>(4)], // -1 is not a valid size!
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -248,6 +260,7 @@ fn dealloc_wrongarg1() {
         arguments: list![const_bool(true), const_int::<usize>(4), const_int::<usize>(4)], // bool unexpected here
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -273,6 +286,7 @@ fn dealloc_wrongarg2() {
         arguments: list![load(local(0)), const_bool(true), const_int::<usize>(4)], // bool unexpected here
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -295,6 +309,7 @@ fn dealloc_wrongarg3() {
         arguments: list![load(local(0)), const_int::<usize>(4), const_bool(true)], // bool unexpected here
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -317,6 +332,7 @@ fn dealloc_wrongreturn() {
         arguments: list![load(local(0)), const_int::<usize>(4), const_int::<usize>(4)],
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -339,6 +355,7 @@ fn mem_dealloc_wrong_size() {
         arguments: list![load(local(0)), const_int::<usize>(5), const_int::<usize>(4)],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -361,6 +378,7 @@ fn mem_dealloc_wrong_align() {
         arguments: list![load(local(0)), const_int::<usize>(4), const_int::<usize>(8)],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
     },);
     let b2 = block!(exit());
 
@@ -483,3 +501,187 @@ fn mem_dealloc_stack() {
     let p = program(&[f]);
     assert_ub::<BasicMem>(p, "deallocating Stack memory with Heap deallocation operation");
 }
+
+/// Allocate `old_count` `i32`s, write 1, 2, ... to them, and reallocate them to `new_count` `i32`s.
+/// Then run `rest` with the old and the new pointer.
+fn realloc_program(
+    old_count: u32,
+    new_count: u32,
+    rest: impl Fn(&mut FunctionBuilder, PlaceExpr, PlaceExpr),
+) -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let old = f.declare_local::<*mut i32>();
+    let new = f.declare_local::<*mut i32>();
+    f.storage_live(old);
+    f.storage_live(new);
+    let four = const_int::<usize>(4);
+    let old_size = const_int::<usize>(old_count as usize * 4);
+    let new_size = const_int::<usize>(new_count as usize * 4);
+    f.allocate(old_size, four, old);
+    for i in 0..old_count {
+        f.assign(nth_i32(load(old), i), const_int::<i32>(i as i32 + 1));
+    }
+    f.reallocate(load(old), old_size, four, new_size, new);
+    rest(&mut f, old, new);
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+fn nth_i32(ptr: ValueExpr, n: u32) -> PlaceExpr {
+    let offset = const_int::<usize>(n as usize * 4);
+    deref(ptr_offset(ptr, offset, InBounds::Yes), <i32>::get_type())
+}
+
+#[test]
+fn realloc_grow() {
+    let p = realloc_program(2, 3, |f, _old, new| {
+        f.print(load(nth_i32(load(new), 0)));
+        f.print(load(nth_i32(load(new), 1)));
+        f.assign(nth_i32(load(new), 2), const_int::<i32>(3));
+        f.print(load(nth_i32(load(new), 2)));
+        f.deallocate(load(new), const_int::<usize>(12), const_int::<usize>(4));
+    });
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["1", "2", "3"]);
+    assert_eq!(get_stdout::<TreeBorrowMem>(p).unwrap(), &["1", "2", "3"]);
+    assert_eq!(get_stdout::<StackedBorrowMem>(p).unwrap(), &["1", "2", "3"]);
+}
+
+#[test]
+fn realloc_shrink() {
+    let p = realloc_program(2, 1, |f, _old, new| {
+        f.print(load(nth_i32(load(new), 0)));
+        f.deallocate(load(new), const_int::<usize>(4), const_int::<usize>(4));
+    });
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["1"]);
+}
+
+#[test]
+fn realloc_shrink_out_of_bounds() {
+    let p = realloc_program(2, 1, |f, _old, new| {
+        f.print(load(nth_i32(load(new), 1)));
+    });
+    assert_ub::<BasicMem>(p, "dereferencing pointer outside the bounds of its allocation");
+}
+
+#[test]
+fn realloc_grow_uninit() {
+    let p = realloc_program(1, 2, |f, _old, new| {
+        f.print(load(nth_i32(load(new), 1)));
+    });
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Int(IntType { signed: Signed, size: Size(4 bytes) }) but the data in memory violates the validity invariant",
+    );
+}
+
+#[test]
+fn use_after_realloc() {
+    // Even if the new allocation is at the same address, the old pointer cannot be used any more.
+    let p = realloc_program(1, 1, |f, old, _new| {
+        f.print(load(nth_i32(load(old), 0)));
+    });
+    assert_ub::<BasicMem>(p, "dereferencing pointer to dead allocation");
+    assert_ub::<TreeBorrowMem>(p, "dereferencing pointer to dead allocation");
+}
+
+#[test]
+fn use_ref_after_realloc() {
+    // References derived from the old allocation belong to its borrow tree, which is gone.
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let old = f.declare_local::<*mut i32>();
+    let new = f.declare_local::<*mut i32>();
+    let r = f.declare_local::<&mut i32>();
+    f.storage_live(old);
+    f.storage_live(new);
+    f.storage_live(r);
+    let four = const_int::<usize>(4);
+    f.allocate(four, four, old);
+    f.assign(r, addr_of(nth_i32(load(old), 0), <&mut i32>::get_type()));
+    f.assign(deref(load(r), <i32>::get_type()), const_int::<i32>(1));
+    f.reallocate(load(old), four, four, four, new);
+    f.print(load(deref(load(r), <i32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_ub::<TreeBorrowMem>(p, "dereferencing pointer to dead allocation");
+}
+
+#[test]
+fn realloc_protected() {
+    // Reallocating removes the old allocation, which a strong protector forbids.
+    let mut p = ProgramBuilder::new();
+    let four = const_int::<usize>(4);
+
+    let callee = {
+        let mut f = p.declare_function();
+        let r = f.declare_arg::<&mut i32>();
+        let new = f.declare_local::<*mut i32>();
+        f.storage_live(new);
+        f.validate(r, /* fn_entry */ true);
+        f.reallocate(ptr_to_ptr(load(r), <*mut i32>::get_type()), four, four, four, new);
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let ptr = f.declare_local::<*mut i32>();
+        f.storage_live(ptr);
+        f.allocate(four, four, ptr);
+        f.assign(nth_i32(load(ptr), 0), const_int::<i32>(1));
+        f.call_ignoreret(
+            callee,
+            &[by_value(addr_of(nth_i32(load(ptr), 0), <&mut i32>::get_type()))],
+        );
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_ub::<TreeBorrowMem>(p, "Tree Borrows: deallocating strongly protected allocation");
+}
+
+#[test]
+fn realloc_wrong_size() {
+    let locals = [<*const i32>::get_type()];
+    let n = const_int::<usize>(4);
+    let b0 = block!(storage_live(0), allocate(n, n, local(0), 1));
+    let b1 = block!(reallocate(load(local(0)), const_int::<usize>(8), n, n, local(0), 2));
+    let b2 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(p, "deallocating with incorrect size information");
+}
+
+#[test]
+fn realloc_wrong_align() {
+    let locals = [<*const i32>::get_type()];
+    let n = const_int::<usize>(4);
+    let b0 = block!(storage_live(0), allocate(n, n, local(0), 1));
+    let b1 = block!(reallocate(load(local(0)), n, const_int::<usize>(8), n, local(0), 2));
+    let b2 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(p, "deallocating with incorrect alignment information");
+}
+
+#[test]
+fn realloc_argcount() {
+    let locals = [<*const i32>::get_type()];
+    let n = const_int::<usize>(4);
+    let b0 = block!(storage_live(0), allocate(n, n, local(0), 1));
+    let b1 = block!(Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Reallocate,
+        arguments: list![load(local(0)), n, n],
+        ret: local(0),
+        next_block: Some(BbName(Name::from_internal(2))),
+        unwind_block: None,
+    });
+    let b2 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(p, "invalid number of arguments for `Reallocate` intrinsic");
+}
@@ -36,3 +36,21 @@ fn type_mismatch() {
     let p = small_program(locals, stmts);
     assert_ill_formed::<BasicMem>(p, "Statement::Assign: destination and source type differ");
 }
+
+#[test]
+fn location_of_unknown_local() {
+    let locals = &[<u32>::get_type()];
+    let stmts = &[storage_live(0), assign(local(0), load(local(7)))];
+    let p = small_program(locals, stmts);
+    let TerminationInfo::IllFormed(err) = run_program::<BasicMem>(p) else {
+        panic!("program is not ill formed!")
+    };
+    let loc = err.location;
+    assert_eq!(loc.block.unwrap().1, BlockPosition::Statement(Int::from(1)));
+    // The innermost expression is reported, not the `load` around it.
+    assert_eq!(loc.expr, Some(WfExpr::Place(local(7))));
+    assert_eq!(
+        fmt_ill_formed(p, err),
+        "PlaceExpr::Local: unknown local name\n    in f0 bb0 stmt 1: _0 = load(_7);\n    in expression: _7"
+    );
+}
@@ -0,0 +1,36 @@
+use crate::*;
+
+fn large_int_program() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u128>();
+    f.storage_live(x);
+    f.assign(x, const_int(u128::MAX));
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+#[test]
+fn large_ints_are_strings() {
+    let p = large_int_program();
+    let json = program_to_json(p);
+    assert!(json.contains(&format!("\"{}\"", u128::MAX)));
+    assert!(program_from_json(&json).unwrap() == p);
+}
+
+#[test]
+fn json_version_mismatch() {
+    let json = program_to_json(large_int_program());
+    let json = json.replacen(&format!("\"version\": {JSON_SCHEMA_VERSION}"), "\"version\": 0", 1);
+    let err = program_from_json(&json).unwrap_err();
+    assert_eq!(err.msg, format!("unsupported schema version 0, expected {JSON_SCHEMA_VERSION}"));
+}
+
+#[test]
+fn json_error_path() {
+    let src = r#"{ "version": 1, "program": { "functions": [[0, { "locals": {} }]] } }"#;
+    let err = program_from_json(src).unwrap_err();
+    assert_eq!(err.path, ["program", "functions", "0", "1", "locals"]);
+    assert_eq!(err.msg, "expected a list");
+}
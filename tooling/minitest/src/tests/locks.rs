@@ -129,7 +129,7 @@ fn lock_handover_data_race() {
     }
 
     let p = p.finish_program(main);
-    assert_stop_always::<BasicMem>(p, 10);
+    assert_outcomes::<BasicMem>(p, &[Outcome::Stop(vec![])]);
 }
 
 // UB Tests for Acquire
@@ -141,6 +141,7 @@ fn acquire_arg_count() {
         arguments: list![],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
     let f = function(Ret::No, 0, &[], &[b0, b1]);
@@ -172,6 +173,7 @@ fn acquire_wrongreturn() {
             arguments: list![const_int::<u32>(0)],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -208,6 +210,7 @@ fn release_arg_count() {
         arguments: list![],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
     let f = function(Ret::No, 0, &locals, &[b0, b1]);
@@ -239,6 +242,7 @@ fn release_wrongreturn() {
             arguments: list![const_int::<u32>(0)],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -290,6 +294,7 @@ fn create_arg_count() {
             arguments: list![load(local(0))],
             ret: unit_place(),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
@@ -310,6 +315,7 @@ fn create_wrongreturn() {
             arguments: list![],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         }
     );
     let b1 = block!(exit());
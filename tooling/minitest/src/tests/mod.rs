@@ -2,20 +2,28 @@ mod align;
 mod assume;
 mod atomic;
 mod atomic_fetch;
+mod backtrace;
 mod bool;
+mod borrows;
 mod builder_api;
 mod call;
+mod char;
 mod compare_exchange;
 mod concurrency;
 mod data_race;
+mod debug;
 mod dereferenceable;
 mod enum_discriminant;
 mod enum_downcast;
 mod enum_representation;
+mod explore;
 mod expose;
+mod float;
+mod generate;
 mod heap_intrinsics;
 mod ill_formed;
 mod int;
+mod json;
 mod locals;
 mod locks;
 mod main;
@@ -25,6 +33,7 @@ mod no_preserve_prov;
 mod null;
 mod packed;
 mod panic;
+mod parse;
 mod place_mention;
 mod print;
 mod ptr;
@@ -32,11 +41,17 @@ mod ptr_offset;
 mod ptr_offset_from;
 mod raw_eq;
 mod return_;
+mod schedule;
 mod slice;
 mod spawn_join;
 mod switch;
+mod thread_local;
 mod too_large_alloc;
+mod trace;
+mod ub_kind;
 mod uninit_read;
 mod unreachable;
+mod vtable;
+mod weak_memory;
 mod wide_ptr;
 mod zst;
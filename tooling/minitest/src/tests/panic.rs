@@ -11,3 +11,130 @@ fn panic() {
     let prog = prog.finish_program(start);
     assert_abort::<BasicMem>(prog, "we panicked");
 }
+
+#[test]
+fn panic_with_message() {
+    let mut prog = ProgramBuilder::new();
+
+    let mut start = prog.declare_function();
+    let message = prog.declare_global_str("oh no");
+    start.panic_with_message(message);
+    let start = prog.finish_function(start);
+
+    let prog = prog.finish_program(start);
+    assert_abort::<BasicMem>(prog, "we panicked: oh no");
+}
+
+#[test]
+fn panic_invalid_message() {
+    let mut prog = ProgramBuilder::new();
+
+    let mut start = prog.declare_function();
+    start.panic_with_message(const_int(0_u32));
+    let start = prog.finish_function(start);
+
+    let prog = prog.finish_program(start);
+    assert_ub::<BasicMem>(prog, "invalid argument for `Panic` intrinsic: not a `&str`");
+}
+
+#[test]
+fn abort() {
+    let mut prog = ProgramBuilder::new();
+
+    let mut start = prog.declare_function();
+    start.abort();
+    let start = prog.finish_function(start);
+
+    let prog = prog.finish_program(start);
+    assert_abort::<BasicMem>(prog, "the program aborted");
+}
+
+fn rust_fn(f: Function) -> Function {
+    Function { calling_convention: CallingConvention::Rust, ..f }
+}
+
+/// A function taking a data pointer that panics and then runs the given cleanup block.
+fn panicking_fn(cleanup: BasicBlock) -> Function {
+    let locals = [<()>::get_type(), <*const ()>::get_type()];
+    let b0 = block!(panic_unwind(1));
+
+    rust_fn(function(Ret::Yes, 1, &locals, &[b0, cleanup]))
+}
+
+/// A function taking a data pointer that just returns.
+fn returning_fn() -> Function {
+    let locals = [<()>::get_type(), <*const ()>::get_type()];
+    let b0 = block!(return_());
+
+    rust_fn(function(Ret::Yes, 1, &locals, &[b0]))
+}
+
+fn catch_program(callee: Function, caught: bool) -> Program {
+    let locals = [<bool>::get_type()];
+    let b0 = block!(storage_live(0), catch_unwind(1, null(), local(0), 1));
+    let b1 = block!(assume(eq(load(local(0)), const_bool(caught)), 2));
+    let b2 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    program(&[f, callee])
+}
+
+#[test]
+fn catch_unwind_returns() {
+    let p = catch_program(returning_fn(), false);
+    dump_program(p);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn catch_unwind_catches() {
+    let p = catch_program(panicking_fn(block!(resume_unwind())), true);
+    dump_program(p);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn resume_unwind_not_unwinding() {
+    let locals = [<()>::get_type(), <*const ()>::get_type()];
+    let b0 = block!(resume_unwind());
+    let callee = rust_fn(function(Ret::Yes, 1, &locals, &[b0]));
+
+    let p = catch_program(callee, false);
+    assert_ub::<BasicMem>(p, "resuming unwinding in a function that is not unwinding");
+}
+
+#[test]
+fn return_while_unwinding() {
+    let p = catch_program(panicking_fn(block!(return_())), true);
+    assert_ub::<BasicMem>(p, "return from a function that is unwinding");
+}
+
+#[test]
+fn unwind_out_of_c_function() {
+    let locals = [<()>::get_type()];
+    let b0 = block!(storage_live(0), call(1, &[by_value(null())], local(0), Some(1)));
+    let b1 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+
+    // `call` uses the C calling convention, so the callee must use it as well.
+    let callee = Function {
+        calling_convention: CallingConvention::C,
+        ..panicking_fn(block!(resume_unwind()))
+    };
+
+    let p = program(&[f, callee]);
+    assert_ub::<BasicMem>(
+        p,
+        "unwinding out of a function that does not use the Rust calling convention",
+    );
+}
+
+#[test]
+fn unwind_block_does_not_exist() {
+    let locals = [<()>::get_type()];
+    let b0 = block!(panic_unwind(1));
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert_ill_formed::<BasicMem>(p, "Terminator::Intrinsic: unwind block does not exist");
+}
@@ -0,0 +1,68 @@
+use crate::*;
+
+#[test]
+fn hand_written_program() {
+    let p = parse_program(include_str!("parse/call_and_print.minirust")).unwrap();
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["42", "84"]);
+}
+
+#[test]
+fn round_trip_all_syntax() {
+    let mut p = ProgramBuilder::new();
+    let global = p.declare_global_zero_initialized::<u64>();
+    let trait_name = p.declare_trait(1);
+
+    let callee = {
+        let mut f = p.declare_function();
+        let x = f.declare_arg::<*const u32>();
+        f.print(load(deref(load(x), <u32>::get_type())));
+        f.return_();
+        p.finish_function(f)
+    };
+    let vtable = p.declare_vtable(trait_name, size(4), align(4), callee, &[callee]);
+
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u32>();
+        let y = f.declare_local::<[i8; 2]>();
+        let z = f.declare_local::<f64>();
+        f.storage_live(x);
+        f.storage_live(y);
+        f.storage_live(z);
+        f.assign(x, const_int(0xff_u32));
+        f.assign(x, bit_and(load(x), neg(const_int(5_u32))));
+        f.assign(index(y, const_int(1_usize)), const_int(-3_i8));
+        f.assign(z, float_add(const_f64(1.5), float_neg(const_f64(f64::NAN))));
+        f.assign(global, int_cast::<u64>(load(x)));
+        f.call_ptr(
+            unit_place(),
+            vtable_method_lookup(vtable_ptr(vtable, trait_name), trait_method(0)),
+            &[by_value(addr_of(x, <*const u32>::get_type()))],
+        );
+        f.call_ptr(
+            unit_place(),
+            vtable_drop_lookup(vtable_ptr(vtable, trait_name)),
+            &[by_value(addr_of(x, <*const u32>::get_type()))],
+        );
+        f.atomic_fetch(FetchBinOp::Add, x, addr_of(x, raw_void_ptr_ty()), const_int(1_u32));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    assert_round_trip(p.finish_program(main));
+}
+
+#[test]
+fn parse_error_location() {
+    let src = "start fn f0() -> _0 {\n  let _0: u33;\n}\n";
+    let err = parse_program(src).unwrap_err();
+    assert_eq!((err.line, err.column), (2, 11));
+    assert_eq!(err.msg, "expected an integer type");
+}
+
+#[test]
+fn parse_missing_start() {
+    let src = "fn f0() -> _0 {\n  let _0: u32;\n  start bb0:\n    return;\n}\n";
+    let err = parse_program(src).unwrap_err();
+    assert_eq!(err.msg, "program has no start function");
+}
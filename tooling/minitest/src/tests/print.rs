@@ -41,6 +41,7 @@ fn print_wrongreturn() {
             arguments: list![const_int::<usize>(4)],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         },
     );
     let b1 = block!(exit());
@@ -50,3 +51,73 @@ fn print_wrongreturn() {
     dump_program(p);
     assert_ub::<BasicMem>(p, "invalid return type for `PrintStdout` intrinsic");
 }
+
+#[test]
+fn print_str() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let s = p.declare_global_str("hello, wörld");
+    f.print(s);
+    f.print(transmute(s, <&[u8]>::get_type()));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["hello, wörld", "hello, wörld"]);
+}
+
+/// Slices of other 1-byte types are not strings.
+#[test]
+fn print_non_byte_slice() {
+    for ty in [<&[i8]>::get_type(), <&[bool]>::get_type()] {
+        let mut p = ProgramBuilder::new();
+        let mut f = p.declare_function();
+        let s = p.declare_global_str("01");
+        f.print(transmute(s, ty));
+        f.exit();
+        let f = p.finish_function(f);
+        let p = p.finish_program(f);
+        assert_ub::<BasicMem>(p, "unsupported value for printing");
+    }
+}
+
+/// Build a `&[u8]` pointing to the array in `arr`.
+fn byte_slice(f: &mut FunctionBuilder, arr: PlaceExpr, len: u64) -> ValueExpr {
+    let pair_ty = PtrType::Raw { meta_kind: PointerMetaKind::ElementCount }
+        .as_wide_pair::<miniutil::DefaultTarget>()
+        .unwrap();
+    let pair = f.declare_local_with_ty(pair_ty);
+    f.storage_live(pair);
+    f.assign(field(pair, 0), addr_of(arr, <*const u8>::get_type()));
+    f.assign(field(pair, 1), const_int(len));
+    transmute(load(pair), <&[u8]>::get_type())
+}
+
+/// UTF-8 is only a library invariant, so invalid bytes are printed as replacement characters.
+#[test]
+fn print_invalid_utf8() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let arr = f.declare_local::<[u8; 2]>();
+    f.storage_live(arr);
+    f.assign(arr, array(&[const_int(0xFF_u8), const_int(b'a')], <u8>::get_type()));
+    let slice = byte_slice(&mut f, arr, 2);
+    f.print(slice);
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["\u{FFFD}a"]);
+}
+
+#[test]
+fn print_uninit_bytes() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let arr = f.declare_local::<[u8; 2]>();
+    f.storage_live(arr);
+    let slice = byte_slice(&mut f, arr, 2);
+    f.print(slice);
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "reading uninitialized bytes of a string");
+}
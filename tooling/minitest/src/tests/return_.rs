@@ -50,6 +50,7 @@ fn return_intrinsic_no_next() {
             arguments: list![const_int::<usize>(4), const_int::<usize>(4)],
             ret: local(0),
             next_block: None,
+            unwind_block: None,
         }
     );
 
@@ -0,0 +1,103 @@
+use crate::*;
+
+/// Two threads that each print twice, so the output depends on the scheduling.
+fn racy_prints() -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let worker = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.print(const_int(2_u32));
+        f.print(const_int(2_u32));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.spawn(worker, null(), thread_id);
+        f.print(const_int(1_u32));
+        f.print(const_int(1_u32));
+        f.join(load(thread_id));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    p.finish_program(main)
+}
+
+/// Prints the address of a local, which is picked non-deterministically.
+fn print_address() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.print(ptr_addr(addr_of(x, <*const u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+#[test]
+fn seeded_schedule_is_reproducible() {
+    for p in [racy_prints(), print_address()] {
+        for seed in 0..16 {
+            let first = get_stdout_with_schedule::<BasicMem>(p, Schedule::Seeded(seed));
+            let second = get_stdout_with_schedule::<BasicMem>(p, Schedule::Seeded(seed));
+            assert_eq!(first, second);
+        }
+    }
+}
+
+#[test]
+fn seeds_explore_different_schedules() {
+    let p = racy_prints();
+    let outputs: std::collections::HashSet<Vec<String>> = (0..32)
+        .map(|seed| {
+            get_stdout_with_schedule::<BasicMem>(p, Schedule::Seeded(seed)).unwrap().0.unwrap()
+        })
+        .collect();
+    assert!(outputs.len() > 1);
+}
+
+#[test]
+fn replay_reproduces_run() {
+    for p in [racy_prints(), print_address()] {
+        for seed in 0..16 {
+            let (out, trace) =
+                get_stdout_with_schedule::<BasicMem>(p, Schedule::Seeded(seed)).unwrap();
+            let trace = ScheduleTrace::parse(&trace.to_string()).unwrap();
+            let (replayed_out, replayed_trace) =
+                get_stdout_with_schedule::<BasicMem>(p, Schedule::Replay(trace.clone())).unwrap();
+            assert_eq!(out, replayed_out);
+            assert_eq!(trace, replayed_trace);
+        }
+    }
+}
+
+#[test]
+fn trace_records_addresses() {
+    let (_, trace) =
+        get_stdout_with_schedule::<BasicMem>(print_address(), Schedule::Seeded(0)).unwrap();
+    assert!(trace.choices.iter().any(|c| matches!(c, Choice::Machine(ChoiceKind::Address, _))));
+}
+
+#[test]
+fn replay_diverged() {
+    let trace = ScheduleTrace { choices: vec![Choice::Thread(7)] };
+    let err = get_stdout_with_schedule::<BasicMem>(racy_prints(), Schedule::Replay(trace));
+    let err = err.unwrap_err().to_string();
+    assert!(err.starts_with("replay diverged: the trace has `thread 7`"), "{err}");
+
+    let trace = ScheduleTrace::default();
+    let err = get_stdout_with_schedule::<BasicMem>(print_address(), Schedule::Replay(trace));
+    assert_eq!(err.unwrap_err().to_string(), "replay diverged: the trace ends after 0 choices");
+}
+
+#[test]
+fn trace_parse_error() {
+    let err = ScheduleTrace::parse("# comment\nthread 0\naddress 16\n1\n").unwrap_err();
+    assert_eq!(err, "line 4: expected a choice, found `1`");
+}
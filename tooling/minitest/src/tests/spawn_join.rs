@@ -71,6 +71,7 @@ fn spawn_arg_count() {
         arguments: list![],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
     let f = function(Ret::No, 0, &[], &[b0, b1]);
@@ -184,7 +185,8 @@ fn join_arg_count() {
         intrinsic: IntrinsicOp::Join,
         arguments: list!(),
         ret: unit_place(),
-        next_block: Some(BbName(Name::from_internal(1)))
+        next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -218,6 +220,7 @@ fn join_wrongreturn() {
             arguments: list![const_int::<u32>(1)],
             ret: local(0),
             next_block: Some(BbName(Name::from_internal(1))),
+            unwind_block: None,
         },
     );
     let b1 = block!(exit());
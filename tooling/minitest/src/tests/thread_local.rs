@@ -0,0 +1,65 @@
+use crate::*;
+
+fn thread_local_int<T: TypeConv>() -> Global {
+    Global { thread_local: true, ..global_int::<T>() }
+}
+
+/// Each thread has its own instance of a thread-local global, which starts out with the initial value.
+#[test]
+fn thread_local_per_thread() {
+    // Increments global(0) and prints it.
+    let locals = [<*const ()>::get_type()];
+    let b0 = block!(
+        assign(global::<u32>(0), add(load(global::<u32>(0)), const_int::<u32>(1))),
+        print(load(global::<u32>(0)), 1)
+    );
+    let b1 = block!(return_());
+    let thread = function(Ret::No, 1, &locals, &[b0, b1]);
+
+    let locals = [<u32>::get_type()];
+    let b0 = block!(
+        storage_live(0),
+        assign(global::<u32>(0), const_int::<u32>(5)),
+        spawn(fn_ptr_internal(1), null(), local(0), 1)
+    );
+    let b1 = block!(join(load(local(0)), 2));
+    // The other thread did not change our instance.
+    let b2 = block!(print(load(global::<u32>(0)), 3));
+    let b3 = block!(exit());
+    let main = function(Ret::No, 0, &locals, &[b0, b1, b2, b3]);
+
+    let p = program_with_globals(&[main, thread], &[thread_local_int::<u32>()]);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["1", "5"]);
+}
+
+/// The instance of a thread is deallocated when the thread terminates.
+#[test]
+fn thread_local_dangling() {
+    // Stores a pointer to its instance of global(0) in global(1).
+    let locals = [<*const ()>::get_type()];
+    let b0 = block!(
+        assign(global::<*const u32>(1), addr_of(global::<u32>(0), <*const u32>::get_type())),
+        return_()
+    );
+    let thread = function(Ret::No, 1, &locals, &[b0]);
+
+    let locals = [<u32>::get_type()];
+    let b0 = block!(storage_live(0), spawn(fn_ptr_internal(1), null(), local(0), 1));
+    let b1 = block!(join(load(local(0)), 2));
+    let b2 = block!(print(load(deref(load(global::<*const u32>(1)), <u32>::get_type())), 3));
+    let b3 = block!(exit());
+    let main = function(Ret::No, 0, &locals, &[b0, b1, b2, b3]);
+
+    let globals = [thread_local_int::<u32>(), global_ptr::<u32>()];
+    let p = program_with_globals(&[main, thread], &globals);
+    assert_ub::<BasicMem>(p, "dereferencing pointer to dead allocation");
+}
+
+#[test]
+fn thread_local_relocation() {
+    let relocation = Relocation { name: GlobalName(Name::from_internal(0)), offset: Size::ZERO };
+    let global = Global { relocations: list![(Size::ZERO, relocation)], ..global_ptr::<u32>() };
+    let globals = [thread_local_int::<u32>(), global];
+    let p = program_with_globals(&[function(Ret::No, 0, &[], &[block!(exit())])], &globals);
+    assert_ill_formed::<BasicMem>(p, "Program: global pointing to a thread-local global");
+}
@@ -0,0 +1,62 @@
+use crate::*;
+
+use miniutil::mock_write::MockWrite;
+
+fn trace(prog: Program) -> Vec<String> {
+    trace_seeded(prog, 0)
+}
+
+fn trace_seeded(prog: Program, seed: u64) -> Vec<String> {
+    let out = MockWrite::new();
+    let (info, _) = trace_program::<BasicMem>(prog, Schedule::Seeded(seed), out.clone()).unwrap();
+    assert_eq!(info, TerminationInfo::MachineStop);
+    out.into_strings()
+}
+
+#[test]
+fn trace_steps_and_accesses() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.assign(x, const_int(42_u32));
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let lines = trace(p);
+    assert_eq!(lines.first().unwrap(), "initialization:");
+    let has_line =
+        |start: &str, end: &str| lines.iter().any(|l| l.starts_with(start) && l.ends_with(end));
+    assert!(has_line("step 1: thread 0, f", " = 42_u32;"));
+    assert!(has_line("  store alloc", ": 2a 00 00 00"));
+    assert!(has_line("  load alloc", ": 2a 00 00 00"));
+    assert_eq!(lines.last().unwrap(), "end: stop");
+
+    // Allocation addresses depend on the seed, but do not show up in the trace.
+    assert_eq!(lines, trace_seeded(p, 1));
+}
+
+#[test]
+fn trace_pointers() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let ptr = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(ptr);
+    f.assign(ptr, addr_of(x, <*const u32>::get_type()));
+    f.assign(x, const_int(7_u32));
+    f.print(load(deref(load(ptr), <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let lines = trace(p);
+    // The pointer to `x` is shown relative to the allocation of `x`, not as its address.
+    let store = lines.iter().find(|l| l.contains(": ptr(alloc")).unwrap();
+    assert!(store.starts_with("  store alloc"));
+    assert!(store.ends_with("+0)"));
+    assert_eq!(lines, trace_seeded(p, 1));
+}
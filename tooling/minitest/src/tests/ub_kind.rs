@@ -0,0 +1,98 @@
+use crate::*;
+
+use miniutil::{StackedBorrowMem, TreeBorrowMem};
+
+#[track_caller]
+fn get_ub<M: Memory>(prog: Program) -> UndefinedBehavior {
+    match run_program::<M>(prog) {
+        TerminationInfo::Ub(ub) => ub,
+        termination_info => panic!("expected UB, got {termination_info:?}"),
+    }
+}
+
+/// Writes through a mutable reference to `x`, then writes to `x` directly,
+/// and then reads through the reference again.
+fn use_after_foreign_write() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<i32>();
+    let r = f.declare_local::<&mut i32>();
+    f.storage_live(x);
+    f.storage_live(r);
+    f.assign(x, const_int(0_i32));
+    // This is the first retag of `x`.
+    f.assign(r, addr_of(x, <&mut i32>::get_type()));
+    f.assign(deref(load(r), <i32>::get_type()), const_int(1_i32));
+    f.assign(x, const_int(2_i32));
+    f.assign(x, load(deref(load(r), <i32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+#[test]
+fn stacked_borrows_tag() {
+    let UndefinedBehavior::AliasingViolation(tag, _) =
+        get_ub::<StackedBorrowMem>(use_after_foreign_write())
+    else {
+        panic!("expected an aliasing violation")
+    };
+    // The base tag of an allocation is 0, so the first retag gets tag 1.
+    assert_eq!(tag, Some(BorrowTag::Stacked(Int::from(1))));
+}
+
+#[test]
+fn tree_borrows_tag() {
+    // The state machine of a location does not know the pointer that accessed it.
+    // `access_from_root` adds the path of that pointer.
+    let UndefinedBehavior::AliasingViolation(tag, _) =
+        get_ub::<TreeBorrowMem>(use_after_foreign_write())
+    else {
+        panic!("expected an aliasing violation")
+    };
+    // The reference is the first child of the root.
+    assert_eq!(tag, Some(BorrowTag::Tree(list![Int::ZERO])));
+}
+
+#[test]
+fn data_race_kind() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_zero_initialized::<u32>();
+
+    let worker = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.assign(x, const_int(1_u32));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.spawn(worker, null(), thread_id);
+        // Nothing orders this store and the store of the worker, whichever comes first.
+        f.assign(x, const_int(2_u32));
+        f.join(load(thread_id));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert!(matches!(get_ub::<BasicMem>(p), UndefinedBehavior::DataRace(_)));
+}
+
+#[test]
+fn invalid_deallocation_kind() {
+    let locals = vec![<*const i32>::get_type()];
+    let n = const_int::<usize>(4);
+    let b0 = block!(storage_live(0), allocate(n, n, local(0), 1));
+    let b1 = block!(deallocate(load(local(0)), n, n, 2));
+    let b2 = block!(deallocate(load(local(0)), n, n, 3));
+    let b3 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3]);
+    let p = program(&[f]);
+    let ub = get_ub::<BasicMem>(p);
+    assert!(matches!(ub, UndefinedBehavior::InvalidDeallocation(_)), "{ub:?}");
+}
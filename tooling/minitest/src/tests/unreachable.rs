@@ -11,3 +11,14 @@ fn reach_unreachable() {
     dump_program(p);
     assert_ub::<BasicMem>(p, "reached unreachable code");
 }
+
+#[test]
+fn unreachable_ub_kind() {
+    let b0 = block!(unreachable());
+    let f = function(Ret::No, 0, &[], &[b0]);
+    let p = program(&[f]);
+    let TerminationInfo::Ub(ub) = run_program::<BasicMem>(p) else {
+        panic!("program did not have UB")
+    };
+    assert!(matches!(ub, UndefinedBehavior::Unreachable(_)));
+}
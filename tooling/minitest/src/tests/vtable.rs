@@ -0,0 +1,230 @@
+use crate::*;
+
+/// Builds a `*const dyn Trait` from a thin pointer and a vtable pointer.
+fn wide_ptr(thin: ValueExpr, vtable: ValueExpr, trait_name: TraitName) -> ValueExpr {
+    let meta_kind = PointerMetaKind::VTablePointer(trait_name);
+    let pair_ty = PtrType::Raw { meta_kind }
+        .as_wide_pair::<miniutil::DefaultTarget>()
+        .expect("PtrType is wide");
+    transmute(tuple(&[thin, vtable], pair_ty), raw_ptr_ty(meta_kind))
+}
+
+/// Declares a method that prints the `u32` its receiver points to, plus `offset`.
+fn print_method(p: &mut ProgramBuilder, offset: u32) -> FnName {
+    let mut f = p.declare_function();
+    let this = f.declare_arg::<*const u32>();
+    f.print(add(load(deref(load(this), <u32>::get_type())), const_int(offset)));
+    f.return_();
+    p.finish_function(f)
+}
+
+/// Declares drop glue that does nothing.
+fn noop_drop(p: &mut ProgramBuilder) -> FnName {
+    let mut f = p.declare_function();
+    let _this = f.declare_arg::<*const u32>();
+    f.return_();
+    p.finish_function(f)
+}
+
+#[test]
+fn dynamic_dispatch() {
+    let mut p = ProgramBuilder::new();
+
+    let trait_name = p.declare_trait(1);
+    let drop = noop_drop(&mut p);
+    let method_a = print_method(&mut p, 0);
+    let method_b = print_method(&mut p, 100);
+    let vtable_a = p.declare_vtable(trait_name, size(4), align(4), drop, &[method_a]);
+    let vtable_b = p.declare_vtable(trait_name, size(4), align(4), drop, &[method_b]);
+
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u32>();
+        let dyn_ptr =
+            f.declare_local_with_ty(raw_ptr_ty(PointerMetaKind::VTablePointer(trait_name)));
+        f.storage_live(x);
+        f.storage_live(dyn_ptr);
+        f.assign(x, const_int(42_u32));
+        for vtable in [vtable_a, vtable_b] {
+            let thin = addr_of(x, raw_void_ptr_ty());
+            f.assign(dyn_ptr, wide_ptr(thin, vtable_ptr(vtable, trait_name), trait_name));
+            let callee = vtable_method_lookup(get_metadata(load(dyn_ptr)), trait_method(0));
+            f.call_ptr(unit_place(), callee, &[by_value(get_thin_pointer(load(dyn_ptr)))]);
+        }
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    dump_program(p);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["42", "142"]);
+}
+
+#[test]
+fn dynamic_drop() {
+    let mut p = ProgramBuilder::new();
+
+    let trait_name = p.declare_trait(0);
+    let drop = print_method(&mut p, 1);
+    let vtable = p.declare_vtable(trait_name, size(4), align(4), drop, &[]);
+
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u32>();
+        let dyn_ptr =
+            f.declare_local_with_ty(raw_ptr_ty(PointerMetaKind::VTablePointer(trait_name)));
+        f.storage_live(x);
+        f.storage_live(dyn_ptr);
+        f.assign(x, const_int(42_u32));
+        let thin = addr_of(x, raw_void_ptr_ty());
+        f.assign(dyn_ptr, wide_ptr(thin, vtable_ptr(vtable, trait_name), trait_name));
+        let callee = vtable_drop_lookup(get_metadata(load(dyn_ptr)));
+        f.call_ptr(unit_place(), callee, &[by_value(get_thin_pointer(load(dyn_ptr)))]);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["43"]);
+}
+
+#[test]
+fn compute_size_align() {
+    let mut p = ProgramBuilder::new();
+
+    let trait_name = p.declare_trait(0);
+    let drop = noop_drop(&mut p);
+    let vtable = p.declare_vtable(trait_name, size(24), align(8), drop, &[]);
+
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u64>();
+        let dyn_ptr =
+            f.declare_local_with_ty(raw_ptr_ty(PointerMetaKind::VTablePointer(trait_name)));
+        f.storage_live(x);
+        f.storage_live(dyn_ptr);
+        let thin = addr_of(x, raw_void_ptr_ty());
+        f.assign(dyn_ptr, wide_ptr(thin, vtable_ptr(vtable, trait_name), trait_name));
+        let meta = get_metadata(load(dyn_ptr));
+        f.print(compute_size(trait_object_ty(trait_name), meta));
+        f.print(compute_align(trait_object_ty(trait_name), meta));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["24", "8"]);
+}
+
+#[test]
+fn vtable_for_wrong_trait() {
+    let mut p = ProgramBuilder::new();
+
+    let trait_a = p.declare_trait(0);
+    let trait_b = p.declare_trait(0);
+    let drop = noop_drop(&mut p);
+    let vtable_b = p.declare_vtable(trait_b, size(4), align(4), drop, &[]);
+
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u32>();
+        let dyn_ptr = f.declare_local_with_ty(raw_ptr_ty(PointerMetaKind::VTablePointer(trait_a)));
+        f.storage_live(x);
+        f.storage_live(dyn_ptr);
+        // Pretend the vtable of `trait_b` is one for `trait_a`.
+        let vtable = transmute(vtable_ptr(vtable_b, trait_b), vtable_ptr_ty(trait_a));
+        f.assign(dyn_ptr, wide_ptr(addr_of(x, raw_void_ptr_ty()), vtable, trait_a));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_ub::<BasicMem>(p, "invalid vtable pointer: the vtable is for a different trait");
+}
+
+#[test]
+fn vtable_ptr_dangling() {
+    let mut p = ProgramBuilder::new();
+
+    let trait_name = p.declare_trait(0);
+
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u32>();
+        let dyn_ptr =
+            f.declare_local_with_ty(raw_ptr_ty(PointerMetaKind::VTablePointer(trait_name)));
+        f.storage_live(x);
+        f.storage_live(dyn_ptr);
+        // Use the address of `x` as vtable pointer.
+        let vtable = transmute(addr_of(x, raw_void_ptr_ty()), vtable_ptr_ty(trait_name));
+        f.assign(dyn_ptr, wide_ptr(addr_of(x, raw_void_ptr_ty()), vtable, trait_name));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_ub::<BasicMem>(p, "invalid vtable pointer: there is no vtable at this address");
+}
+
+#[test]
+fn vtable_missing_method() {
+    let mut p = ProgramBuilder::new();
+
+    let trait_name = p.declare_trait(2);
+    let drop = noop_drop(&mut p);
+    let method = print_method(&mut p, 0);
+    let _vtable = p.declare_vtable(trait_name, size(4), align(4), drop, &[method]);
+
+    let main = {
+        let mut f = p.declare_function();
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_ill_formed::<BasicMem>(p, "Program: vtable is missing a method of its trait");
+}
+
+#[test]
+fn vtable_method_lookup_unknown_method() {
+    let mut p = ProgramBuilder::new();
+
+    let trait_name = p.declare_trait(1);
+    let drop = noop_drop(&mut p);
+    let method = print_method(&mut p, 0);
+    let vtable = p.declare_vtable(trait_name, size(4), align(4), drop, &[method]);
+
+    let main = {
+        let mut f = p.declare_function();
+        let fn_ptr = f.declare_local_with_ty(Type::Ptr(PtrType::FnPtr));
+        f.storage_live(fn_ptr);
+        f.assign(fn_ptr, vtable_method_lookup(vtable_ptr(vtable, trait_name), trait_method(1)));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_ill_formed::<BasicMem>(p, "UnOp::VTableMethodLookup: invalid method name");
+}
+
+#[test]
+fn vtable_drop_lookup_not_vtable_ptr() {
+    let mut p = ProgramBuilder::new();
+
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u32>();
+        let fn_ptr = f.declare_local_with_ty(Type::Ptr(PtrType::FnPtr));
+        f.storage_live(x);
+        f.storage_live(fn_ptr);
+        f.assign(fn_ptr, vtable_drop_lookup(addr_of(x, raw_void_ptr_ty())));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_ill_formed::<BasicMem>(
+        p,
+        "UnOp::VTableDropLookup: invalid operand: not a vtable pointer",
+    );
+}
@@ -0,0 +1,122 @@
+use crate::*;
+
+/// Builds a message-passing program:
+///
+/// ```ignore
+/// static DATA: u32 = 0;
+/// static FLAG: u32 = 0;
+///
+/// fn writer() {
+///     atomic_store::<Relaxed>(&DATA, 1);
+///     if fences { fence::<Release>(); }
+///     atomic_store::<flag_store>(&FLAG, 1);
+/// }
+///
+/// fn main() {
+///     let t = spawn(writer);
+///     let a = atomic_load::<flag_load>(&FLAG);
+///     if fences { fence::<Acquire>(); }
+///     let b = atomic_load::<Relaxed>(&DATA);
+///     join(t);
+///     print(a);
+///     print(b);
+/// }
+/// ```
+fn message_passing(flag_store: MemoryOrdering, flag_load: MemoryOrdering, fences: bool) -> Program {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let flag = p.declare_global_zero_initialized::<u32>();
+
+    let writer = {
+        let mut f = p.declare_function();
+        f.declare_arg::<*const ()>();
+        f.atomic_store_ordered(
+            addr_of(data, raw_void_ptr_ty()),
+            const_int(1_u32),
+            MemoryOrdering::Relaxed,
+        );
+        if fences {
+            f.fence(MemoryOrdering::Release);
+        }
+        f.atomic_store_ordered(addr_of(flag, raw_void_ptr_ty()), const_int(1_u32), flag_store);
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        let thread_id = f.declare_local::<u32>();
+        let a = f.declare_local::<u32>();
+        let b = f.declare_local::<u32>();
+        f.storage_live(thread_id);
+        f.storage_live(a);
+        f.storage_live(b);
+        f.spawn(writer, null(), thread_id);
+        f.atomic_load_ordered(a, addr_of(flag, raw_void_ptr_ty()), flag_load);
+        if fences {
+            f.fence(MemoryOrdering::Acquire);
+        }
+        f.atomic_load_ordered(b, addr_of(data, raw_void_ptr_ty()), MemoryOrdering::Relaxed);
+        f.join(load(thread_id));
+        f.print(load(a));
+        f.print(load(b));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    p.finish_program(main)
+}
+
+/// Explores the program and returns whether the main thread can see the flag set but not the data.
+fn observes_stale_data(p: Program) -> bool {
+    let stale = Outcome::Stop(vec!["1".to_string(), "0".to_string()]);
+    explore_outcomes::<BasicMem>(p).contains(&stale)
+}
+
+#[test]
+fn relaxed_message_passing_stale() {
+    let p = message_passing(MemoryOrdering::Relaxed, MemoryOrdering::Relaxed, false);
+    assert!(observes_stale_data(p));
+}
+
+#[test]
+fn release_acquire_message_passing() {
+    let p = message_passing(MemoryOrdering::Release, MemoryOrdering::Acquire, false);
+    assert!(!observes_stale_data(p));
+}
+
+#[test]
+fn fence_message_passing() {
+    let p = message_passing(MemoryOrdering::Relaxed, MemoryOrdering::Relaxed, true);
+    assert!(!observes_stale_data(p));
+}
+
+#[test]
+fn atomic_load_release_ill_formed() {
+    let mut p = ProgramBuilder::new();
+    let main = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u32>();
+        f.storage_live(x);
+        f.atomic_load_ordered(x, addr_of(x, raw_void_ptr_ty()), MemoryOrdering::Release);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_ill_formed::<BasicMem>(p, "IntrinsicOp::AtomicLoad: invalid memory ordering");
+}
+
+#[test]
+fn relaxed_fence_ill_formed() {
+    let mut p = ProgramBuilder::new();
+    let main = {
+        let mut f = p.declare_function();
+        f.fence(MemoryOrdering::Relaxed);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    assert_ill_formed::<BasicMem>(p, "IntrinsicOp::AtomicFence: invalid memory ordering");
+}
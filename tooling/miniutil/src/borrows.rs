@@ -0,0 +1,187 @@
+//! Showing the state of Tree Borrows, to explain aliasing violations.
+//!
+//! Every live allocation has a tree with a node for each pointer that was derived from another
+//! by a retag. Nodes are named by their path from the root, which is also what the provenance
+//! of their pointers and `BorrowTag::Tree` contain. For each node, we show its permission for
+//! every location, with adjacent locations in the same state merged into ranges, and its
+//! protector. Permissions of locations that were not accessed through the node yet are shown in
+//! parentheses, since they only matter once they are.
+//!
+//! The trees can be shown as a plain-text table or in the DOT language of Graphviz.
+//! For example, `dot -Tsvg` turns the latter into a picture.
+
+use std::io::Write;
+
+use crate::run::StepHook;
+use crate::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorrowFormat {
+    Text,
+    Dot,
+}
+
+/// When a `BorrowDumper` shows the trees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpBorrowsAt {
+    /// Once, with the state right before the step that raised UB.
+    /// Nothing is shown if the program does not have UB.
+    Ub,
+    /// After every step.
+    EveryStep,
+}
+
+/// Formats the trees of all live allocations.
+pub fn fmt_borrows(mem: TreeBorrowMem, format: BorrowFormat) -> String {
+    let trees = mem.borrow_trees();
+    match format {
+        BorrowFormat::Text => fmt_text(trees),
+        BorrowFormat::Dot => fmt_dot(trees),
+    }
+}
+
+/// A `StepHook` for runs with Tree Borrows that writes the trees to `out` at the points given
+/// by `at`.
+pub struct BorrowDumper<W: Write> {
+    at: DumpBorrowsAt,
+    format: BorrowFormat,
+    out: W,
+    /// The number of steps so far.
+    step: usize,
+}
+
+impl<W: Write> BorrowDumper<W> {
+    pub fn new(at: DumpBorrowsAt, format: BorrowFormat, out: W) -> Self {
+        BorrowDumper { at, format, out, step: 0 }
+    }
+}
+
+impl<W: Write> StepHook<TreeBorrowMem> for BorrowDumper<W> {
+    fn after_step(
+        &mut self,
+        machine: &Machine<TreeBorrowMem>,
+        before: TreeBorrowMem,
+        info: Option<&TerminationInfo>,
+    ) {
+        self.step += 1;
+        match (self.at, info) {
+            (DumpBorrowsAt::Ub, Some(TerminationInfo::Ub(_))) => {
+                let trees = fmt_borrows(before, self.format);
+                write!(self.out, "{trees}").expect("could not write borrow trees");
+            }
+            (DumpBorrowsAt::EveryStep, None) => {
+                let trees = fmt_borrows(machine.memory(), self.format);
+                write!(self.out, "after step {}:\n{trees}", self.step)
+                    .expect("could not write borrow trees");
+            }
+            _ => {}
+        }
+    }
+}
+
+/// All nodes of a tree with their paths, parents before their children.
+fn nodes(root: Node) -> Vec<(Vec<u32>, Node)> {
+    fn visit(node: Node, path: &mut Vec<u32>, out: &mut Vec<(Vec<u32>, Node)>) {
+        out.push((path.clone(), node));
+        for (idx, child) in node.children.iter().enumerate() {
+            path.push(idx as u32);
+            visit(child, path, out);
+            path.pop();
+        }
+    }
+    let mut out = Vec::new();
+    visit(root, &mut Vec::new(), &mut out);
+    out
+}
+
+// Allocations are shown by their number rather than their address, so that the output
+// does not depend on the addresses that were picked.
+fn fmt_allocation(tree: &BorrowTree) -> String {
+    let size = tree.root.location_states.len();
+    format!("alloc{} ({:?}, {size} bytes)", tree.alloc_id, tree.kind)
+}
+
+fn fmt_path(path: &[u32]) -> String {
+    let path: Vec<String> = path.iter().map(u32::to_string).collect();
+    format!("[{}]", path.join(", "))
+}
+
+/// The permissions of a node, as ranges of locations in the same state.
+fn fmt_ranges(node: Node) -> Vec<String> {
+    let states: Vec<LocationState> = node.location_states.iter().collect();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for end in 1..=states.len() {
+        if end == states.len() || states[end] != states[start] {
+            ranges.push(format!("{start}..{end}: {}", fmt_state(states[start])));
+            start = end;
+        }
+    }
+    ranges
+}
+
+fn fmt_state(state: LocationState) -> String {
+    let permission = match state.permission {
+        Permission::Reserved { conflicted: false } => "Reserved",
+        Permission::Reserved { conflicted: true } => "Reserved (conflicted)",
+        Permission::ReservedIM => "ReservedIM",
+        Permission::Active => "Active",
+        Permission::Frozen => "Frozen",
+        Permission::Cell => "Cell",
+        Permission::Disabled => "Disabled",
+    };
+    match state.accessed {
+        Accessed::Yes => permission.to_string(),
+        Accessed::No => format!("({permission})"),
+    }
+}
+
+fn fmt_protector(protected: Protected) -> Option<&'static str> {
+    match protected {
+        Protected::Strong => Some("strongly protected"),
+        Protected::Weak => Some("weakly protected"),
+        Protected::No => None,
+    }
+}
+
+fn fmt_text(trees: List<BorrowTree>) -> String {
+    let mut out = String::new();
+    for tree in trees.iter() {
+        out += &format!("{}:\n", fmt_allocation(&tree));
+        for (path, node) in nodes(tree.root) {
+            // Indent children below their parents.
+            let indent = "  ".repeat(path.len() + 1);
+            let mut line = format!("{indent}{}: {}", fmt_path(&path), fmt_ranges(node).join(", "));
+            if let Some(protector) = fmt_protector(node.protected) {
+                line += &format!("; {protector}");
+            }
+            out += &format!("{line}\n");
+        }
+    }
+    out
+}
+
+fn fmt_dot(trees: List<BorrowTree>) -> String {
+    let mut out = String::from("digraph borrows {\n  node [shape=box];\n");
+    for tree in trees.iter() {
+        let alloc_id = tree.alloc_id;
+        let node_id = |path: &[u32]| {
+            let path: String = path.iter().map(|idx| format!("_{idx}")).collect();
+            format!("alloc{alloc_id}{path}")
+        };
+        out += &format!("  subgraph cluster_{alloc_id} {{\n");
+        out += &format!("    label=\"{}\";\n", fmt_allocation(&tree));
+        for (path, node) in nodes(tree.root) {
+            let mut label = vec![fmt_path(&path)];
+            label.extend(fmt_ranges(node));
+            label.extend(fmt_protector(node.protected).map(str::to_string));
+            out += &format!("    {} [label=\"{}\"];\n", node_id(&path), label.join("\\n"));
+            if let Some((_, parent)) = path.split_last() {
+                out += &format!("    {} -> {};\n", node_id(parent), node_id(&path));
+            }
+        }
+        out += "  }\n";
+    }
+    out += "}\n";
+    out
+}
@@ -13,6 +13,18 @@ pub fn const_bool(b: bool) -> ValueExpr {
     ValueExpr::Constant(Constant::Bool(b), Type::Bool)
 }
 
+pub fn const_char(c: char) -> ValueExpr {
+    ValueExpr::Constant(Constant::Int(Int::from(c as u32)), Type::Char)
+}
+
+pub fn const_f32(f: f32) -> ValueExpr {
+    ValueExpr::Constant(Constant::Float(Float::F32(f.to_bits())), <f32>::get_type())
+}
+
+pub fn const_f64(f: f64) -> ValueExpr {
+    ValueExpr::Constant(Constant::Float(Float::F64(f.to_bits())), <f64>::get_type())
+}
+
 #[track_caller]
 pub fn tuple(args: &[ValueExpr], ty: Type) -> ValueExpr {
     let Type::Tuple { fields, .. } = ty else {
@@ -74,6 +86,35 @@ pub fn int_cast<T: TypeConv>(v: ValueExpr) -> ValueExpr {
     ValueExpr::UnOp { operator: UnOp::Cast(CastOp::IntToInt(t)), operand: GcCow::new(v) }
 }
 
+/// Unary `-` on a float.
+pub fn float_neg(v: ValueExpr) -> ValueExpr {
+    ValueExpr::UnOp { operator: UnOp::Float(FloatUnOp::Neg), operand: GcCow::new(v) }
+}
+
+#[track_caller]
+pub fn float_to_int<T: TypeConv>(v: ValueExpr) -> ValueExpr {
+    let Type::Int(t) = T::get_type() else {
+        panic!("float_to_int received non-int type!");
+    };
+    ValueExpr::UnOp { operator: UnOp::Cast(CastOp::FloatToInt(t)), operand: GcCow::new(v) }
+}
+
+#[track_caller]
+pub fn int_to_float<T: TypeConv>(v: ValueExpr) -> ValueExpr {
+    let Type::Float(t) = T::get_type() else {
+        panic!("int_to_float received non-float type!");
+    };
+    ValueExpr::UnOp { operator: UnOp::Cast(CastOp::IntToFloat(t)), operand: GcCow::new(v) }
+}
+
+#[track_caller]
+pub fn float_cast<T: TypeConv>(v: ValueExpr) -> ValueExpr {
+    let Type::Float(t) = T::get_type() else {
+        panic!("float_cast received non-float type!");
+    };
+    ValueExpr::UnOp { operator: UnOp::Cast(CastOp::FloatToFloat(t)), operand: GcCow::new(v) }
+}
+
 pub fn ptr_addr(v: ValueExpr) -> ValueExpr {
     transmute(v, <usize>::get_type())
 }
@@ -107,6 +148,30 @@ pub fn get_metadata(v: ValueExpr) -> ValueExpr {
     ValueExpr::UnOp { operator: UnOp::GetMetadata, operand: GcCow::new(v) }
 }
 
+pub fn get_thin_pointer(v: ValueExpr) -> ValueExpr {
+    ValueExpr::UnOp { operator: UnOp::GetThinPointer, operand: GcCow::new(v) }
+}
+
+/// Looks up `method` in the vtable `v` points to.
+pub fn vtable_method_lookup(v: ValueExpr, method: TraitMethodName) -> ValueExpr {
+    ValueExpr::UnOp { operator: UnOp::VTableMethodLookup(method), operand: GcCow::new(v) }
+}
+
+/// Looks up the drop glue in the vtable `v` points to.
+pub fn vtable_drop_lookup(v: ValueExpr) -> ValueExpr {
+    ValueExpr::UnOp { operator: UnOp::VTableDropLookup, operand: GcCow::new(v) }
+}
+
+/// Computes the size of a value of type `ty` with the pointer metadata `meta`.
+pub fn compute_size(ty: Type, meta: ValueExpr) -> ValueExpr {
+    ValueExpr::UnOp { operator: UnOp::ComputeSize(ty), operand: GcCow::new(meta) }
+}
+
+/// Computes the alignment of a value of type `ty` with the pointer metadata `meta`.
+pub fn compute_align(ty: Type, meta: ValueExpr) -> ValueExpr {
+    ValueExpr::UnOp { operator: UnOp::ComputeAlign(ty), operand: GcCow::new(meta) }
+}
+
 fn int_binop(op: IntBinOp, l: ValueExpr, r: ValueExpr) -> ValueExpr {
     ValueExpr::BinOp { operator: BinOp::Int(op), left: GcCow::new(l), right: GcCow::new(r) }
 }
@@ -160,6 +225,26 @@ pub fn bit_xor(l: ValueExpr, r: ValueExpr) -> ValueExpr {
     int_binop(IntBinOp::BitXor, l, r)
 }
 
+fn float_binop(op: FloatBinOp, l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    ValueExpr::BinOp { operator: BinOp::Float(op), left: GcCow::new(l), right: GcCow::new(r) }
+}
+
+pub fn float_add(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Add, l, r)
+}
+pub fn float_sub(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Sub, l, r)
+}
+pub fn float_mul(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Mul, l, r)
+}
+pub fn float_div(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Div, l, r)
+}
+pub fn float_rem(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Rem, l, r)
+}
+
 fn int_overflow(op: IntBinOpWithOverflow, l: ValueExpr, r: ValueExpr) -> ValueExpr {
     ValueExpr::BinOp {
         operator: BinOp::IntWithOverflow(op),
@@ -3,11 +3,41 @@ use crate::build::*;
 impl ProgramBuilder {
     pub fn declare_global_zero_initialized<T: TypeConv>(&mut self) -> PlaceExpr {
         let bytes = List::from_elem(Some(0), T::get_size().expect_sized("T is `Sized`").bytes());
-        let global = Global { bytes, relocations: list!(), align: <T>::get_align() };
+        let global = Global {
+            bytes,
+            relocations: list!(),
+            fn_pointers: list!(),
+            align: <T>::get_align(),
+            thread_local: false,
+        };
+        global_by_name::<T>(self.declare_global(global))
+    }
+
+    /// Declare a global holding the bytes of `s`, and return a `&str` pointing to it.
+    pub fn declare_global_str(&mut self, s: &str) -> ValueExpr {
+        let bytes = s.bytes().map(Some).collect();
+        let global = Global {
+            bytes,
+            relocations: list!(),
+            fn_pointers: list!(),
+            align: align(1),
+            thread_local: false,
+        };
+        let name = self.declare_global(global);
+        let relocation = Relocation { name, offset: Size::ZERO };
+        let ptr = ValueExpr::Constant(Constant::GlobalPointer(relocation), <*const u8>::get_type());
+        // There is no expression building a wide pointer, so we transmute the (pointer, length) pair.
+        let pair_ty = PtrType::Raw { meta_kind: PointerMetaKind::ElementCount }
+            .as_wide_pair::<DefaultTarget>()
+            .unwrap();
+        transmute(tuple(&[ptr, const_int(s.len())], pair_ty), <&str>::get_type())
+    }
+
+    fn declare_global(&mut self, global: Global) -> GlobalName {
         let name = GlobalName(Name::from_internal(self.next_global));
         self.next_global += 1;
         self.globals.try_insert(name, global).unwrap();
-        global_by_name::<T>(name)
+        name
     }
 }
 
@@ -15,7 +45,13 @@ impl ProgramBuilder {
 pub fn global_int<T: TypeConv>() -> Global {
     let bytes = List::from_elem(Some(0), T::get_size().expect_sized("T is `Sized`").bytes());
 
-    Global { bytes, relocations: list!(), align: T::get_align() }
+    Global {
+        bytes,
+        relocations: list!(),
+        fn_pointers: list!(),
+        align: T::get_align(),
+        thread_local: false,
+    }
 }
 
 /// Global pointer
@@ -23,5 +59,11 @@ pub fn global_ptr<T: TypeConv + ?Sized>() -> Global {
     let bytes =
         List::from_elem(Some(0), <*const T>::get_size().expect_sized("*T is `Sized`").bytes());
 
-    Global { bytes, relocations: list!(), align: <*const T>::get_align() }
+    Global {
+        bytes,
+        relocations: list!(),
+        fn_pointers: list!(),
+        align: <*const T>::get_align(),
+        thread_local: false,
+    }
 }
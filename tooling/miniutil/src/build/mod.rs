@@ -24,6 +24,9 @@ pub use function::*;
 mod global;
 pub use global::*;
 
+mod vtable;
+pub use vtable::*;
+
 mod statement;
 pub use statement::*;
 
@@ -42,8 +45,12 @@ pub use ty_conv::*;
 pub struct ProgramBuilder {
     functions: Map<FnName, Function>,
     globals: Map<GlobalName, Global>,
+    traits: Map<TraitName, Set<TraitMethodName>>,
+    vtables: Map<VTableName, VTable>,
     next_fn: u32,
     next_global: u32,
+    next_trait: u32,
+    next_vtable: u32,
 }
 
 impl ProgramBuilder {
@@ -51,13 +58,23 @@ impl ProgramBuilder {
         ProgramBuilder {
             functions: Default::default(),
             globals: Default::default(),
+            traits: Default::default(),
+            vtables: Default::default(),
             next_fn: 0,
             next_global: 0,
+            next_trait: 0,
+            next_vtable: 0,
         }
     }
 
     pub fn finish_program(self, start_function: FnName) -> Program {
-        Program { functions: self.functions, start: start_function, globals: self.globals }
+        Program {
+            functions: self.functions,
+            start: start_function,
+            globals: self.globals,
+            traits: self.traits,
+            vtables: self.vtables,
+        }
     }
 
     pub fn declare_function(&mut self) -> FunctionBuilder {
@@ -190,12 +207,30 @@ impl FunctionBuilder {
         local_by_name(name)
     }
 
+    #[track_caller]
+    pub fn declare_ret_with_ty(&mut self, t: Type) -> PlaceExpr {
+        let name = match self.ret {
+            Some(_) => panic!("Ret local already set."),
+            None => self.fresh_local_name(),
+        };
+        self.locals.try_insert(name, t).unwrap();
+        self.ret = Some(name);
+        local_by_name(name)
+    }
+
     pub fn declare_arg<T: TypeConv>(&mut self) -> PlaceExpr {
         let name = self.fresh_local_name();
         self.locals.try_insert(name, T::get_type()).unwrap();
         self.args.push(name);
         local_by_name(name)
     }
+
+    pub fn declare_arg_with_ty(&mut self, t: Type) -> PlaceExpr {
+        let name = self.fresh_local_name();
+        self.locals.try_insert(name, t).unwrap();
+        self.args.push(name);
+        local_by_name(name)
+    }
 }
 
 struct CurBlock {
@@ -247,7 +282,13 @@ pub fn program_with_globals(fns: &[Function], globals: &[Global]) -> Program {
         })
         .collect();
 
-    Program { functions, start: FnName(Name::from_internal(0)), globals }
+    Program {
+        functions,
+        start: FnName(Name::from_internal(0)),
+        globals,
+        traits: Default::default(),
+        vtables: Default::default(),
+    }
 }
 
 // The first function in `fns` is the start function of the program.
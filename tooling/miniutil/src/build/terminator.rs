@@ -29,6 +29,19 @@ impl FunctionBuilder {
         self.finish_block(panic());
     }
 
+    /// Panic with the given `&str` as message.
+    pub fn panic_with_message(&mut self, message: ValueExpr) {
+        self.finish_block(panic_with_message(message));
+    }
+
+    pub fn abort(&mut self) {
+        self.finish_block(abort());
+    }
+
+    pub fn resume_unwind(&mut self) {
+        self.finish_block(Terminator::ResumeUnwind);
+    }
+
     /// Call a function that does not return.
     pub fn call_noret(&mut self, ret: PlaceExpr, f: FnName, args: &[ArgumentExpr]) {
         self.finish_block(Terminator::Call {
@@ -37,6 +50,7 @@ impl FunctionBuilder {
             arguments: args.iter().copied().collect(),
             ret,
             next_block: None,
+            unwind_block: None,
         });
     }
 
@@ -49,6 +63,21 @@ impl FunctionBuilder {
             arguments: args.iter().copied().collect(),
             ret,
             next_block: Some(next_block),
+            unwind_block: None,
+        });
+        self.set_cur_block(next_block)
+    }
+
+    /// Call the function that `callee` points to.
+    pub fn call_ptr(&mut self, ret: PlaceExpr, callee: ValueExpr, args: &[ArgumentExpr]) {
+        let next_block = self.declare_block();
+        self.finish_block(Terminator::Call {
+            callee,
+            calling_convention: CallingConvention::C, // FIXME do not hard-code the C calling convention
+            arguments: args.iter().copied().collect(),
+            ret,
+            next_block: Some(next_block),
+            unwind_block: None,
         });
         self.set_cur_block(next_block)
     }
@@ -62,6 +91,7 @@ impl FunctionBuilder {
             arguments: args.iter().copied().collect(),
             ret: unit_place(),
             next_block: Some(next_block),
+            unwind_block: None,
         });
         self.set_cur_block(next_block);
     }
@@ -96,6 +126,26 @@ impl FunctionBuilder {
         self.set_cur_block(next_block)
     }
 
+    pub fn reallocate(
+        &mut self,
+        ptr: ValueExpr,
+        old_size: ValueExpr,
+        align: ValueExpr,
+        new_size: ValueExpr,
+        ret_place: PlaceExpr,
+    ) {
+        let next_block = self.declare_block();
+        self.finish_block(reallocate(
+            ptr,
+            old_size,
+            align,
+            new_size,
+            ret_place,
+            bbname_into_u32(next_block),
+        ));
+        self.set_cur_block(next_block)
+    }
+
     pub fn spawn(&mut self, f: FnName, data_ptr: ValueExpr, ret: PlaceExpr) {
         let next_block = self.declare_block();
         self.finish_block(spawn(fn_ptr(f), data_ptr, ret, bbname_into_u32(next_block)));
@@ -126,6 +176,34 @@ impl FunctionBuilder {
         self.set_cur_block(next_block)
     }
 
+    pub fn atomic_store_ordered(
+        &mut self,
+        ptr: ValueExpr,
+        src: ValueExpr,
+        ordering: MemoryOrdering,
+    ) {
+        let next_block = self.declare_block();
+        self.finish_block(atomic_store_ordered(ptr, src, ordering, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn atomic_load_ordered(
+        &mut self,
+        dest: PlaceExpr,
+        ptr: ValueExpr,
+        ordering: MemoryOrdering,
+    ) {
+        let next_block = self.declare_block();
+        self.finish_block(atomic_load_ordered(dest, ptr, ordering, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn fence(&mut self, ordering: MemoryOrdering) {
+        let next_block = self.declare_block();
+        self.finish_block(fence(ordering, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
     pub fn atomic_fetch(
         &mut self,
         binop: FetchBinOp,
@@ -296,6 +374,7 @@ pub fn call(f: u32, args: &[ArgumentExpr], ret: PlaceExpr, next: Option<u32>) ->
         arguments: args.iter().copied().collect(),
         ret,
         next_block: next.map(|x| BbName(Name::from_internal(x))),
+        unwind_block: None,
     }
 }
 
@@ -305,6 +384,7 @@ pub fn assume(val: ValueExpr, next: u32) -> Terminator {
         arguments: list![val],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -314,6 +394,7 @@ pub fn print(arg: ValueExpr, next: u32) -> Terminator {
         arguments: list![arg],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -323,6 +404,7 @@ pub fn eprint(arg: ValueExpr, next: u32) -> Terminator {
         arguments: list![arg],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -332,6 +414,7 @@ pub fn allocate(size: ValueExpr, align: ValueExpr, ret_place: PlaceExpr, next: u
         arguments: list![size, align],
         ret: ret_place,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -341,6 +424,24 @@ pub fn deallocate(ptr: ValueExpr, size: ValueExpr, align: ValueExpr, next: u32)
         arguments: list![ptr, size, align],
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
+    }
+}
+
+pub fn reallocate(
+    ptr: ValueExpr,
+    old_size: ValueExpr,
+    align: ValueExpr,
+    new_size: ValueExpr,
+    ret_place: PlaceExpr,
+    next: u32,
+) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Reallocate,
+        arguments: list![ptr, old_size, align, new_size],
+        ret: ret_place,
+        next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -350,6 +451,7 @@ pub fn exit() -> Terminator {
         arguments: list![],
         ret: unit_place(),
         next_block: None,
+        unwind_block: None,
     }
 }
 
@@ -359,6 +461,53 @@ pub fn panic() -> Terminator {
         arguments: list![],
         ret: unit_place(),
         next_block: None,
+        unwind_block: None,
+    }
+}
+
+pub fn panic_with_message(message: ValueExpr) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Panic,
+        arguments: list![message],
+        ret: unit_place(),
+        next_block: None,
+        unwind_block: None,
+    }
+}
+
+/// Panic and run `unwind` as cleanup code.
+pub fn panic_unwind(unwind: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Panic,
+        arguments: list![],
+        ret: unit_place(),
+        next_block: None,
+        unwind_block: Some(BbName(Name::from_internal(unwind))),
+    }
+}
+
+pub fn abort() -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Abort,
+        arguments: list![],
+        ret: unit_place(),
+        next_block: None,
+        unwind_block: None,
+    }
+}
+
+pub fn resume_unwind() -> Terminator {
+    Terminator::ResumeUnwind
+}
+
+/// Call `f` with `data_ptr`; `ret` is set to whether `f` unwound.
+pub fn catch_unwind(f: u32, data_ptr: ValueExpr, ret: PlaceExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::CatchUnwind,
+        arguments: list![fn_ptr_internal(f), data_ptr],
+        ret,
+        next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -372,6 +521,7 @@ pub fn spawn(fn_ptr: ValueExpr, data_ptr: ValueExpr, ret: PlaceExpr, next: u32)
         arguments: list!(fn_ptr, data_ptr),
         ret,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -381,6 +531,7 @@ pub fn join(thread_id: ValueExpr, next: u32) -> Terminator {
         arguments: list!(thread_id),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -390,24 +541,55 @@ pub fn raw_eq(ret: PlaceExpr, left_ptr: ValueExpr, right_ptr: ValueExpr, next: u
         arguments: list!(left_ptr, right_ptr),
         ret,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
 pub fn atomic_store(ptr: ValueExpr, src: ValueExpr, next: u32) -> Terminator {
+    atomic_store_ordered(ptr, src, MemoryOrdering::SeqCst, next)
+}
+
+pub fn atomic_store_ordered(
+    ptr: ValueExpr,
+    src: ValueExpr,
+    ordering: MemoryOrdering,
+    next: u32,
+) -> Terminator {
     Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(ordering),
         arguments: list!(ptr, src),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
 pub fn atomic_load(dest: PlaceExpr, ptr: ValueExpr, next: u32) -> Terminator {
+    atomic_load_ordered(dest, ptr, MemoryOrdering::SeqCst, next)
+}
+
+pub fn atomic_load_ordered(
+    dest: PlaceExpr,
+    ptr: ValueExpr,
+    ordering: MemoryOrdering,
+    next: u32,
+) -> Terminator {
     Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicLoad,
+        intrinsic: IntrinsicOp::AtomicLoad(ordering),
         arguments: list!(ptr),
         ret: dest,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
+    }
+}
+
+pub fn fence(ordering: MemoryOrdering, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::AtomicFence(ordering),
+        arguments: list!(),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -429,10 +611,11 @@ pub fn atomic_fetch(
     };
 
     Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicFetchAndOp(binop),
+        intrinsic: IntrinsicOp::AtomicFetchAndOp(binop, MemoryOrdering::SeqCst),
         arguments: list!(ptr, other),
         ret: dest,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -444,10 +627,14 @@ pub fn compare_exchange(
     next: u32,
 ) -> Terminator {
     Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicCompareExchange,
+        intrinsic: IntrinsicOp::AtomicCompareExchange {
+            success: MemoryOrdering::SeqCst,
+            failure: MemoryOrdering::SeqCst,
+        },
         arguments: list!(ptr, current, next_val),
         ret: dest,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -457,6 +644,7 @@ pub fn expose_provenance(dest: PlaceExpr, ptr: ValueExpr, next: u32) -> Terminat
         arguments: list![ptr],
         ret: dest,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -466,6 +654,7 @@ pub fn with_exposed_provenance(dest: PlaceExpr, addr: ValueExpr, next: u32) -> T
         arguments: list![addr],
         ret: dest,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -475,6 +664,7 @@ pub fn lock_create(ret: PlaceExpr, next: u32) -> Terminator {
         arguments: list!(),
         ret: ret,
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -484,6 +674,7 @@ pub fn lock_acquire(lock_id: ValueExpr, next: u32) -> Terminator {
         arguments: list!(lock_id),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
 
@@ -493,5 +684,6 @@ pub fn lock_release(lock_id: ValueExpr, next: u32) -> Terminator {
         arguments: list!(lock_id),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
+        unwind_block: None,
     }
 }
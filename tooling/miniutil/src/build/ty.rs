@@ -8,6 +8,14 @@ pub fn bool_ty() -> Type {
     Type::Bool
 }
 
+pub fn char_ty() -> Type {
+    Type::Char
+}
+
+pub fn float_ty(float_ty: FloatType) -> Type {
+    Type::Float(float_ty)
+}
+
 pub fn ref_ty(pointee: PointeeInfo) -> Type {
     Type::Ptr(PtrType::Ref { mutbl: Mutability::Immutable, pointee })
 }
@@ -45,6 +53,14 @@ pub fn slice_ty(elem: Type) -> Type {
     Type::Slice { elem: GcCow::new(elem) }
 }
 
+pub fn trait_object_ty(trait_name: TraitName) -> Type {
+    Type::TraitObject(trait_name)
+}
+
+pub fn vtable_ptr_ty(trait_name: TraitName) -> Type {
+    Type::Ptr(PtrType::VTablePtr(trait_name))
+}
+
 pub fn enum_variant(ty: Type, tagger: &[(Offset, (IntType, Int))]) -> Variant {
     Variant { ty, tagger: tagger.iter().copied().collect() }
 }
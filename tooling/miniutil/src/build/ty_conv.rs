@@ -46,6 +46,18 @@ type_conv_int_impl!(i128, Signed, size(16));
 type_conv_int_impl!(usize, Unsigned, DefaultTarget::PTR_SIZE);
 type_conv_int_impl!(isize, Signed, DefaultTarget::PTR_SIZE);
 
+impl TypeConv for f32 {
+    fn get_type() -> Type {
+        float_ty(FloatType::F32)
+    }
+}
+
+impl TypeConv for f64 {
+    fn get_type() -> Type {
+        float_ty(FloatType::F64)
+    }
+}
+
 impl<T: TypeConv + ?Sized> TypeConv for *const T {
     fn get_type() -> Type {
         raw_ptr_ty(T::get_type().meta_kind())
@@ -64,6 +76,12 @@ impl TypeConv for bool {
     }
 }
 
+impl TypeConv for char {
+    fn get_type() -> Type {
+        char_ty()
+    }
+}
+
 impl<T: TypeConv + ?Sized> TypeConv for &T {
     fn get_type() -> Type {
         ref_ty(PointeeInfo {
@@ -72,6 +90,8 @@ impl<T: TypeConv + ?Sized> TypeConv for &T {
             inhabited: true,
             freeze: T::FREEZE,
             unpin: T::UNPIN,
+            unsafe_cells: List::new(),
+            byte_slice: T::get_type() == <[u8]>::get_type(),
         })
     }
 }
@@ -84,6 +104,8 @@ impl<T: TypeConv + ?Sized> TypeConv for &mut T {
             inhabited: true,
             freeze: T::FREEZE,
             unpin: T::UNPIN,
+            unsafe_cells: List::new(),
+            byte_slice: T::get_type() == <[u8]>::get_type(),
         })
     }
 }
@@ -100,6 +122,13 @@ impl<T: TypeConv> TypeConv for [T] {
     }
 }
 
+// `str` is `[u8]` for MiniRust.
+impl TypeConv for str {
+    fn get_type() -> Type {
+        slice_ty(<u8>::get_type())
+    }
+}
+
 impl TypeConv for () {
     fn get_type() -> Type {
         tuple_ty(&[], size(0), align(1))
@@ -0,0 +1,43 @@
+use crate::build::*;
+
+impl ProgramBuilder {
+    /// Declares a trait with `num_methods` methods, named `trait_method(0)` to `trait_method(num_methods - 1)`.
+    pub fn declare_trait(&mut self, num_methods: u32) -> TraitName {
+        let name = TraitName(Name::from_internal(self.next_trait));
+        self.next_trait += 1;
+        let methods = (0..num_methods).map(trait_method).collect();
+        self.traits.try_insert(name, methods).unwrap();
+        name
+    }
+
+    /// Declares a vtable for `trait_name` with the drop glue `drop`, where `methods[i]` implements `trait_method(i)`.
+    pub fn declare_vtable(
+        &mut self,
+        trait_name: TraitName,
+        size: Size,
+        align: Align,
+        drop: FnName,
+        methods: &[FnName],
+    ) -> VTableName {
+        let methods = methods
+            .iter()
+            .enumerate()
+            .map(|(i, fn_name)| (trait_method(i as _), *fn_name))
+            .collect();
+        let vtable = VTable { trait_name, size, align, drop, methods };
+        let name = VTableName(Name::from_internal(self.next_vtable));
+        self.next_vtable += 1;
+        self.vtables.try_insert(name, vtable).unwrap();
+        name
+    }
+}
+
+pub fn trait_method(idx: u32) -> TraitMethodName {
+    TraitMethodName(Name::from_internal(idx))
+}
+
+pub fn vtable_ptr(vtable_name: VTableName, trait_name: TraitName) -> ValueExpr {
+    let c = Constant::VTablePointer(vtable_name);
+    let t = vtable_ptr_ty(trait_name);
+    ValueExpr::Constant(c, t)
+}
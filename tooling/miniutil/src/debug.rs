@@ -0,0 +1,269 @@
+//! Interactive debugging of MiniRust programs, as done by `minidebug`.
+//!
+//! A `Debugger` executes a program one step at a time, so that the state of the machine can be
+//! inspected in between: the stacks and locals of all threads, and the live allocations.
+//! Execution can be stopped at the start of functions and basic blocks with breakpoints.
+//!
+//! The thread that executes a step is picked by a `Schedule`, unless a thread was selected
+//! with `select_thread`: then that thread executes every step for as long as it is enabled.
+//! Inspecting the machine does not count as a memory access, so it cannot change
+//! what the program does, not even under an aliasing model.
+
+use crate::fmt::{fmt_address, fmt_bytes, fmt_value, StatementFormatter};
+use crate::run::{thread_frames, thread_u32, Frame};
+use crate::schedule::{ReplayDiverged, Schedule, Scheduler};
+use crate::*;
+
+/// Stops the execution when a thread reaches the start of a function or basic block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub func: FnName,
+    /// The block to stop at. `None` stands for the start block of `func`.
+    pub block: Option<BbName>,
+}
+
+/// Why the debugger stopped executing the program.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stop {
+    /// The given thread reached a breakpoint.
+    Breakpoint(u32, Breakpoint),
+    /// The program terminated.
+    Terminated(TerminationInfo),
+    /// The replayed schedule does not fit the program, so the execution cannot go on.
+    Diverged(ReplayDiverged),
+}
+
+pub struct Debugger<M: Memory> {
+    prog: Program,
+    machine: Machine<M>,
+    scheduler: Scheduler,
+    formatter: StatementFormatter,
+    breakpoints: Vec<Breakpoint>,
+    /// The thread selected with `select_thread`, until it terminates.
+    selected: Option<u32>,
+    /// The thread that executed the most recent step, or that stopped at a breakpoint.
+    last: u32,
+    terminated: bool,
+}
+
+impl<M: Memory> Debugger<M> {
+    /// Set up the machine for `prog`. This fails if the program is ill-formed, or if allocating
+    /// its globals, functions, vtables or the thread-local globals of the start thread fails.
+    /// The output of the program is written to `stdout` and `stderr`.
+    pub fn new(
+        prog: Program,
+        schedule: Schedule,
+        stdout: impl GcWrite,
+        stderr: impl GcWrite,
+    ) -> Result<Self, TerminationInfo> {
+        let mut scheduler = Scheduler::new(schedule);
+        let choices = scheduler.setup_choices(prog);
+        let machine =
+            Machine::<M>::new(prog, Some(choices), DynWrite::new(stdout), DynWrite::new(stderr));
+        let machine = machine.get_internal()?;
+        scheduler.record(&machine);
+        Ok(Debugger {
+            prog,
+            machine,
+            scheduler,
+            formatter: StatementFormatter::new(prog),
+            breakpoints: Vec::new(),
+            selected: None,
+            last: 0,
+            terminated: false,
+        })
+    }
+
+    pub fn terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Execute a single step.
+    /// Returns why execution stopped, if that is for another reason than the end of the step.
+    pub fn step(&mut self) -> Option<Stop> {
+        assert!(!self.terminated, "the program has already terminated");
+        let thread_count = self.machine.thread_count();
+        let enabled = self.machine.enabled_threads();
+        let res = if enabled.is_empty() {
+            // There is nothing to pick; `step` reports the deadlock.
+            self.machine.step()
+        } else {
+            let thread = match self.selected {
+                Some(id) if enabled.iter().any(|t| t == Int::from(id)) => Int::from(id),
+                _ => self.scheduler.pick(enabled),
+            };
+            self.last = thread_u32(thread);
+            self.scheduler.prescribe(&mut self.machine);
+            let res = self.machine.step_thread(thread);
+            self.scheduler.record(&self.machine);
+            res
+        };
+        if let Err(diverged) = self.scheduler.check() {
+            self.terminated = true;
+            return Some(Stop::Diverged(diverged));
+        }
+        if let Err(info) = res.get_internal() {
+            self.terminated = true;
+            return Some(Stop::Terminated(info));
+        }
+        // Drops everything not reachable from `machine`.
+        mark_and_sweep(&self.machine);
+
+        if self.selected.is_some_and(|id| self.frames(id).is_empty()) {
+            self.selected = None;
+        }
+
+        // Only the thread that executed the step and the threads it spawned have moved.
+        let spawned = thread_u32(thread_count)..self.thread_count();
+        for thread in std::iter::once(self.last).chain(spawned) {
+            if let Some(bp) = self.breakpoint_at(thread) {
+                self.last = thread;
+                return Some(Stop::Breakpoint(thread, bp));
+            }
+        }
+        None
+    }
+
+    /// Execute steps until the current thread has finished its current statement or terminator,
+    /// including all the functions that it calls.
+    pub fn next(&mut self) -> Option<Stop> {
+        let thread = self.current_thread();
+        let depth = self.frames(thread).len();
+        loop {
+            if let Some(stop) = self.step() {
+                return Some(stop);
+            }
+            if self.last == thread && self.frames(thread).len() <= depth {
+                return None;
+            }
+        }
+    }
+
+    /// Execute steps until a breakpoint is reached or the program terminates.
+    pub fn cont(&mut self) -> Stop {
+        loop {
+            if let Some(stop) = self.step() {
+                return stop;
+            }
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, bp: Breakpoint) -> Result<(), String> {
+        let Some(func) = self.prog.functions.get(bp.func) else {
+            return Err(format!("there is no function f{}", bp.func.0.get_internal()));
+        };
+        if let Some(block) = bp.block {
+            if !func.blocks.contains_key(block) {
+                let (fn_id, bb_id) = (bp.func.0.get_internal(), block.0.get_internal());
+                return Err(format!("function f{fn_id} has no block bb{bb_id}"));
+            }
+        }
+        self.breakpoints.push(bp);
+        Ok(())
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn remove_breakpoint(&mut self, idx: usize) -> Result<Breakpoint, String> {
+        if idx >= self.breakpoints.len() {
+            return Err(format!("there is no breakpoint {idx}"));
+        }
+        Ok(self.breakpoints.remove(idx))
+    }
+
+    /// The thread that is inspected by default: the selected thread, or else the thread that
+    /// executed the most recent step.
+    pub fn current_thread(&self) -> u32 {
+        self.selected.unwrap_or(self.last)
+    }
+
+    /// Make `thread` the current thread, and let it execute all steps while it is enabled.
+    pub fn select_thread(&mut self, thread: u32) -> Result<(), String> {
+        if thread >= self.thread_count() {
+            return Err(format!("there is no thread {thread}"));
+        }
+        if self.frames(thread).is_empty() {
+            return Err(format!("thread {thread} has terminated"));
+        }
+        self.selected = Some(thread);
+        Ok(())
+    }
+
+    /// The number of threads that were spawned so far, including those that have terminated.
+    pub fn thread_count(&self) -> u32 {
+        thread_u32(self.machine.thread_count())
+    }
+
+    /// Whether `thread` can execute the next step, i.e., it is neither blocked nor terminated.
+    pub fn enabled(&self, thread: u32) -> bool {
+        self.machine.enabled_threads().iter().any(|t| t == Int::from(thread))
+    }
+
+    /// The frames of the given thread, innermost frame first.
+    pub fn frames(&self, thread: u32) -> Vec<Frame> {
+        thread_frames(&self.machine, Int::from(thread))
+    }
+
+    /// Show where a frame is, and the statement or terminator it executes next.
+    pub fn fmt_frame(&mut self, frame: Frame) -> String {
+        let block = self.prog.functions.index_at(frame.func).blocks.index_at(frame.block);
+        let stmt = Int::from(frame.stmt);
+        let (position, text) = if stmt < block.statements.len() {
+            (format!("stmt {stmt}"), self.formatter.fmt_statement(block.statements.index_at(stmt)))
+        } else {
+            ("terminator".to_string(), self.formatter.fmt_terminator(block.terminator))
+        };
+        let (fn_id, bb_id) = (frame.func.0.get_internal(), frame.block.0.get_internal());
+        format!("f{fn_id} bb{bb_id} {position}: {text}")
+    }
+
+    /// Show the live locals of a frame of the given thread, where frame 0 is the innermost one.
+    /// Locals whose bytes are not a valid value of their type are shown as `<invalid>`.
+    pub fn fmt_locals(&mut self, thread: u32, frame: usize) -> Result<Vec<String>, String> {
+        let depth = self.frames(thread).len();
+        if frame >= depth {
+            return Err(format!("thread {thread} has no frame {frame}"));
+        }
+        // `frame_locals` counts the frames from the outside.
+        let locals = self.machine.frame_locals(Int::from(thread), Int::from(depth - 1 - frame));
+        let lines = locals
+            .iter()
+            .map(|local| {
+                let ty = self.formatter.fmt_type(local.ty);
+                let val = local.value.map(fmt_value).unwrap_or_else(|| "<invalid>".to_string());
+                format!("_{}: {ty} = {val}", local.name.0.get_internal())
+            })
+            .collect();
+        Ok(lines)
+    }
+
+    /// Show the live allocations and their contents, ordered by address.
+    pub fn fmt_allocations(&self) -> Vec<String> {
+        let mut allocations: Vec<_> = self.machine.live_allocations().iter().collect();
+        allocations.sort_by_key(|allocation| allocation.addr);
+        allocations
+            .into_iter()
+            .map(|allocation| {
+                let addr = fmt_address(allocation.addr);
+                let (size, align) = (allocation.data.len(), allocation.align.bytes());
+                let kind = allocation.kind;
+                let bytes = fmt_bytes::<M>(allocation.data);
+                format!("{addr}: {kind:?}, size {size}, align {align}: {bytes}")
+            })
+            .collect()
+    }
+
+    /// The breakpoint that the given thread is at, if any.
+    fn breakpoint_at(&self, thread: u32) -> Option<Breakpoint> {
+        let &top = self.frames(thread).first()?;
+        if top.stmt != 0 {
+            return None;
+        }
+        self.breakpoints.iter().copied().find(|bp| {
+            let block = bp.block.unwrap_or(self.prog.functions.index_at(bp.func).start);
+            bp.func == top.func && block == top.block
+        })
+    }
+}
@@ -0,0 +1,493 @@
+//! Exhaustive exploration of the thread interleavings of a program.
+//!
+//! Instead of running a concurrent program many times and hoping that the scheduler hits the
+//! interesting interleavings, `explore` enumerates the scheduling choices depth-first.
+//! Each execution starts the program from scratch, replays a prefix of the choices of an earlier
+//! execution, and then keeps running the current thread for as long as it is enabled.
+//!
+//! Most interleavings are equivalent: steps of different threads that do not interact can be
+//! swapped without changing the outcome. We use dynamic partial-order reduction (Flanagan and
+//! Godefroid, POPL 2005) to only explore interleavings that reorder interacting steps.
+//! Two steps interact if their memory accesses (as reported by `Machine::step_events`) overlap,
+//! if they both use some other shared state (like stdout or the locks), or if one of them
+//! ends the execution. Overlapping loads are also considered to interact, since e.g. with
+//! Stacked Borrows their order can matter.
+//!
+//! Besides the thread of each step, the exploration enumerates the choices the machine makes
+//! with `choose` that have few alternatives: which store a weak-memory load reads from, which
+//! blocked thread acquires a released lock, and which NaN a float operation returns. It prescribes
+//! the indices of all choices (see `Choices::prescribe`): the other choices (allocation addresses)
+//! just take the first possible value, so that an execution makes the same choices as the earlier
+//! execution it replays. If an execution still does not follow the replayed choices, the exploration is
+//! reported as incomplete.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::run::thread_u32;
+use crate::{mock_write::MockWrite, *};
+
+/// The bounds of an exploration.
+#[derive(Clone, Copy, Debug)]
+pub struct ExploreBounds {
+    /// The maximal number of steps of each execution.
+    pub steps: usize,
+    /// The maximal number of preemptions of each execution, i.e., of switches away from a
+    /// thread that is still enabled. `None` means there is no bound.
+    pub preemptions: Option<usize>,
+    /// The maximal number of executions.
+    pub executions: usize,
+}
+
+impl Default for ExploreBounds {
+    fn default() -> Self {
+        ExploreBounds { steps: 10_000, preemptions: None, executions: 10_000 }
+    }
+}
+
+/// How an execution ended.
+/// This is like `TerminationInfo`, but it also holds the output of a normal termination.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// The program stopped without error, after printing these lines to stdout.
+    Stop(Vec<String>),
+    Ub(UndefinedBehavior),
+    Abort(String),
+    IllFormed(String),
+    Deadlock,
+    MemoryLeak,
+}
+
+impl Outcome {
+    fn new(info: TerminationInfo, stdout: MockWrite) -> Self {
+        match info {
+            TerminationInfo::MachineStop => Outcome::Stop(stdout.into_strings()),
+            TerminationInfo::Ub(ub) => Outcome::Ub(ub),
+            TerminationInfo::Abort(msg) => Outcome::Abort(msg.get_internal().to_string()),
+            TerminationInfo::IllFormed(err) =>
+                Outcome::IllFormed(err.msg.get_internal().to_string()),
+            TerminationInfo::Deadlock => Outcome::Deadlock,
+            TerminationInfo::MemoryLeak => Outcome::MemoryLeak,
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Stop(stdout) => write!(f, "stopped with stdout {stdout:?}"),
+            Outcome::Ub(ub) => write!(f, "UB: {ub}"),
+            Outcome::Abort(msg) => write!(f, "Panic: {msg}"),
+            Outcome::IllFormed(msg) => write!(f, "program not well-formed: {msg}"),
+            Outcome::Deadlock => write!(f, "program dead-locked"),
+            Outcome::MemoryLeak => write!(f, "program leaked memory"),
+        }
+    }
+}
+
+/// The result of an exploration.
+#[derive(Clone, Debug)]
+pub struct Exploration {
+    /// The distinct outcomes of all explored executions, in the order in which they were found.
+    pub outcomes: Vec<Outcome>,
+    /// The number of executions.
+    pub executions: usize,
+    /// Whether all interleavings were explored. This is `false` if a bound was hit,
+    /// or if an execution did not follow the replayed choices.
+    pub complete: bool,
+}
+
+/// A scheduling choice of the current execution, together with the alternatives that still need
+/// to be explored.
+struct Node {
+    /// The thread that executes the step.
+    thread: u32,
+    /// The threads that were enabled before the step.
+    enabled: Vec<u32>,
+    /// The threads that need to be explored at this point.
+    backtrack: BTreeSet<u32>,
+    /// The threads that have been explored at this point.
+    done: BTreeSet<u32>,
+    /// The number of preemptions up to and including this step.
+    preemptions: usize,
+    /// The choices the machine made during the step, in order.
+    choices: Vec<MadeChoice>,
+}
+
+/// Whether the exploration enumerates the alternatives of choices of this kind.
+fn explored(kind: ChoiceKind) -> bool {
+    matches!(kind, ChoiceKind::ReadFrom | ChoiceKind::LockAcquirer | ChoiceKind::NanResult)
+}
+
+/// Why an execution did not produce an outcome.
+enum Cut {
+    /// A bound was hit, or the execution did not follow the replayed choices.
+    Incomplete,
+    /// The execution repeats an earlier one, see `compare_choices`.
+    Repeated,
+}
+
+/// How the choices made by a step compare to the replayed choices.
+enum Replay {
+    Followed,
+    Repeated,
+    Diverged,
+}
+
+/// The replayed choices are those of an earlier execution, except that the last one may have
+/// been moved on to its next alternative. If that alternative is not possible, `choose` takes the
+/// next one that is, wrapping around to the alternatives that were already explored.
+fn compare_choices(replayed: &[MadeChoice], made: &[MadeChoice]) -> Replay {
+    if made.len() < replayed.len() {
+        return Replay::Diverged;
+    }
+    for (i, (replayed_choice, made_choice)) in replayed.iter().zip(made).enumerate() {
+        if made_choice.kind != replayed_choice.kind {
+            return Replay::Diverged;
+        }
+        if made_choice.index == replayed_choice.index {
+            continue;
+        }
+        if i + 1 < replayed.len() {
+            return Replay::Diverged;
+        }
+        if made_choice.index < replayed_choice.index {
+            return Replay::Repeated;
+        }
+    }
+    Replay::Followed
+}
+
+/// What `add_backtrack_points` needs to know about an executed step.
+struct Step {
+    thread: u32,
+    /// Whether this step interacts with every step of every other thread.
+    global: bool,
+    /// The address ranges this step accessed, including the pseudo-addresses below.
+    accesses: Vec<std::ops::Range<i128>>,
+    /// The threads that were enabled by this step, by spawning them or unblocking them.
+    enabled: Vec<u32>,
+}
+
+/// Pseudo-addresses for the state outside of memory that steps of different threads can interact
+/// through. Real memory only has non-negative addresses.
+const STDOUT: i128 = -1;
+const STDERR: i128 = -2;
+const THREADS: i128 = -3;
+const LOCKS: i128 = -4;
+const FENCES: i128 = -5;
+const EXPOSED_PROVENANCE: i128 = -6;
+
+/// How a step interacts with other threads, besides its memory accesses.
+enum Interaction {
+    None,
+    /// The step uses the state at this pseudo-address.
+    Uses(i128),
+    /// The step interacts with all steps of all other threads.
+    Global,
+}
+
+/// Explore the interleavings of the program within the given bounds and collect their outcomes.
+pub fn explore<M: Memory>(prog: Program, bounds: ExploreBounds) -> Exploration {
+    let mut stack: Vec<Node> = Vec::new();
+    let mut exploration = Exploration { outcomes: Vec::new(), executions: 0, complete: true };
+    loop {
+        if exploration.executions == bounds.executions {
+            exploration.complete = false;
+            return exploration;
+        }
+        exploration.executions += 1;
+
+        let (outcome, steps) = execute::<M>(prog, &mut stack, bounds.steps);
+        match outcome {
+            Ok(outcome) if !exploration.outcomes.contains(&outcome) =>
+                exploration.outcomes.push(outcome),
+            Ok(_) | Err(Cut::Repeated) => {}
+            Err(Cut::Incomplete) => exploration.complete = false,
+        }
+        add_backtrack_points(&mut stack, &steps);
+
+        // Continue with the deepest choice that has alternatives left.
+        // The choices the machine made during a step come after the choice of its thread.
+        loop {
+            let Some(node) = stack.last_mut() else {
+                return exploration;
+            };
+            let next_choice = node.choices.iter().rposition(|choice| {
+                explored(choice.kind) && choice.index + Int::ONE < choice.options
+            });
+            if let Some(idx) = next_choice {
+                node.choices.truncate(idx + 1);
+                node.choices[idx].index += Int::ONE;
+                break;
+            }
+            let node = stack.last().unwrap();
+            let prev = stack.len().checked_sub(2).map(|idx| &stack[idx]);
+            let Some(&thread) = node.backtrack.difference(&node.done).next() else {
+                stack.pop();
+                continue;
+            };
+            let preempts = prev
+                .is_some_and(|prev| prev.thread != thread && node.enabled.contains(&prev.thread));
+            let preemptions = prev.map_or(0, |prev| prev.preemptions) + usize::from(preempts);
+
+            let node = stack.last_mut().unwrap();
+            node.done.insert(thread);
+            if bounds.preemptions.is_some_and(|max| preemptions > max) {
+                exploration.complete = false;
+                continue;
+            }
+            node.thread = thread;
+            node.preemptions = preemptions;
+            node.choices.clear();
+            break;
+        }
+    }
+}
+
+/// Run the program once, following the choices on `stack` and then pushing the choices made by
+/// running the current thread for as long as possible.
+/// Returns the outcome, or why the execution was cut short, and the executed steps.
+fn execute<M: Memory>(
+    prog: Program,
+    stack: &mut Vec<Node>,
+    max_steps: usize,
+) -> (Result<Outcome, Cut>, Vec<Step>) {
+    let stdout = MockWrite::new();
+    let stderr = std::io::stderr();
+    let mut steps: Vec<Step> = Vec::new();
+
+    // Without prescribed indices, all choices of the setup take the first possible value.
+    let res = Machine::<M>::new(
+        prog,
+        Some(List::new()),
+        DynWrite::new(stdout.clone()),
+        DynWrite::new(stderr),
+    );
+    let mut machine = match res.get_internal() {
+        Ok(machine) => machine,
+        Err(info) => {
+            stack.clear();
+            return (Ok(Outcome::new(info, stdout)), steps);
+        }
+    };
+
+    let mut prev_enabled: Vec<u32> = Vec::new();
+    let info = loop {
+        let enabled: Vec<u32> = machine.enabled_threads().iter().map(thread_u32).collect();
+        if let Some(step) = steps.last_mut() {
+            let thread = step.thread;
+            let newly_enabled = enabled.iter().filter(|id| !prev_enabled.contains(id));
+            step.enabled.extend(newly_enabled.filter(|&&id| id != thread));
+        }
+        prev_enabled = enabled.clone();
+        if enabled.is_empty() {
+            // There is nothing to pick; `step` reports the deadlock.
+            break machine.step().get_internal().unwrap_err();
+        }
+
+        let depth = steps.len();
+        if depth == max_steps {
+            stack.truncate(depth);
+            return (Err(Cut::Incomplete), steps);
+        }
+        let thread = if depth < stack.len() {
+            let thread = stack[depth].thread;
+            if !enabled.contains(&thread) {
+                stack.truncate(depth + 1);
+                return (Err(Cut::Incomplete), steps);
+            }
+            thread
+        } else {
+            let prev = stack.last();
+            let thread = prev.map(|prev| prev.thread).filter(|t| enabled.contains(t));
+            let thread = thread.unwrap_or(enabled[0]);
+            let preemptions = prev.map_or(0, |prev| prev.preemptions);
+            stack.push(Node {
+                thread,
+                enabled,
+                backtrack: BTreeSet::from([thread]),
+                done: BTreeSet::from([thread]),
+                preemptions,
+                choices: Vec::new(),
+            });
+            thread
+        };
+
+        let replayed = std::mem::take(&mut stack[depth].choices);
+        machine.prescribe_choices(replayed.iter().map(|choice| choice.index).collect());
+        let interaction = interaction(prog, &machine, Int::from(thread));
+        let res = machine.step_thread(Int::from(thread)).get_internal();
+        let made: Vec<MadeChoice> = machine.step_choices().iter().collect();
+        let replay = compare_choices(&replayed, &made);
+        let mut accesses: Vec<_> =
+            machine.step_events().iter().filter_map(event_range::<M>).collect();
+        if let Interaction::Uses(addr) = interaction {
+            accesses.push(addr..addr + 1);
+        }
+        // A step that ends the execution keeps all other threads from taking further steps.
+        let global = matches!(interaction, Interaction::Global) || res.is_err();
+        steps.push(Step { thread, global, accesses, enabled: Vec::new() });
+        match replay {
+            Replay::Followed => stack[depth].choices = made,
+            Replay::Repeated => {
+                // The changed choice has no alternatives left.
+                let mut choices = replayed;
+                let last = choices.last_mut().unwrap();
+                last.index = last.options - Int::ONE;
+                stack[depth].choices = choices;
+                stack.truncate(depth + 1);
+                return (Err(Cut::Repeated), steps);
+            }
+            Replay::Diverged => {
+                stack[depth].choices = made;
+                stack.truncate(depth + 1);
+                return (Err(Cut::Incomplete), steps);
+            }
+        }
+        if let Err(info) = res {
+            break info;
+        }
+
+        // Drops everything not reachable from `machine`.
+        mark_and_sweep(&machine);
+    };
+
+    stack.truncate(steps.len());
+    (Ok(Outcome::new(info, stdout)), steps)
+}
+
+/// The address range that a memory operation accesses. Allocations are not included, since
+/// no other step can access fresh memory yet.
+fn event_range<M: Memory>(event: MemoryEvent<M::Provenance>) -> Option<std::ops::Range<i128>> {
+    let (addr, len) = match event {
+        MemoryEvent::Allocate { .. } => return None,
+        MemoryEvent::Deallocate { addr, size } => (addr, size.bytes()),
+        MemoryEvent::Load { addr, bytes, .. } | MemoryEvent::Store { addr, bytes, .. } =>
+            (addr, bytes.len()),
+    };
+    let to_i128 = |int: Int| i128::try_from(int.try_to_usize().unwrap()).unwrap();
+    let start = to_i128(addr);
+    Some(start..start + to_i128(len))
+}
+
+/// How the next step of the given thread interacts with other threads, besides its memory accesses.
+/// Joining a thread needs nothing here: it can only complete after the joined thread terminated,
+/// and the step that unblocks the joining thread is ordered before it.
+fn interaction<M: Memory>(prog: Program, machine: &Machine<M>, thread: ThreadId) -> Interaction {
+    let Some(frame) = machine.thread_stack(thread).last() else {
+        return Interaction::None;
+    };
+    let block = prog.functions.index_at(frame.func).blocks.index_at(frame.block);
+    if frame.stmt < block.statements.len() {
+        return match block.statements.index_at(frame.stmt) {
+            // Retags change the aliasing state of the memory they point to without accessing it.
+            Statement::Validate { .. } => Interaction::Global,
+            _ => Interaction::None,
+        };
+    }
+    let Terminator::Intrinsic { intrinsic, .. } = block.terminator else {
+        return Interaction::None;
+    };
+    match intrinsic {
+        IntrinsicOp::Exit | IntrinsicOp::Abort => Interaction::Global,
+        IntrinsicOp::PrintStdout => Interaction::Uses(STDOUT),
+        IntrinsicOp::PrintStderr => Interaction::Uses(STDERR),
+        // The order of spawns determines the IDs of the new threads.
+        IntrinsicOp::Spawn => Interaction::Uses(THREADS),
+        IntrinsicOp::Lock(_) => Interaction::Uses(LOCKS),
+        IntrinsicOp::AtomicFence(_) => Interaction::Uses(FENCES),
+        IntrinsicOp::PointerExposeProvenance | IntrinsicOp::PointerWithExposedProvenance =>
+            Interaction::Uses(EXPOSED_PROVENANCE),
+        _ => Interaction::None,
+    }
+}
+
+/// Find the races of the execution `steps`, i.e., pairs of interacting steps of different threads
+/// that are not ordered by other interactions, and make sure that the stack explores executing
+/// the second step before the first one.
+fn add_backtrack_points(stack: &mut [Node], steps: &[Step]) {
+    // Vector clocks of the order generated by interactions: for each thread, the number of steps
+    // in `steps` up to the last step of that thread that is ordered before.
+    let mut thread_clocks: Vec<Vec<usize>> = Vec::new();
+    let mut step_clocks: Vec<Vec<usize>> = Vec::new();
+    // For each thread, its last step.
+    let mut last_step: Vec<Option<usize>> = Vec::new();
+    let mut last_global: Option<usize> = None;
+    // For each byte, the last step that accessed it.
+    let mut last_access: HashMap<i128, usize> = HashMap::new();
+
+    for (i, step) in steps.iter().enumerate() {
+        let thread = step.thread as usize;
+        if thread_clocks.len() <= thread {
+            thread_clocks.resize(thread + 1, Vec::new());
+            last_step.resize(thread + 1, None);
+        }
+
+        // The earlier steps this step interacts with. We only need the last step interacting with
+        // each part of the state; all earlier ones are ordered before that one.
+        let mut deps: Vec<usize> = Vec::new();
+        if step.global {
+            deps.extend(last_step.iter().flatten());
+        } else {
+            deps.extend(last_global);
+            for range in &step.accesses {
+                deps.extend(range.clone().filter_map(|addr| last_access.get(&addr)));
+            }
+        }
+
+        let mut clock = thread_clocks[thread].clone();
+        let ordered = |clock: &Vec<usize>, j: usize| {
+            clock.get(steps[j].thread as usize).is_some_and(|&time| time > j)
+        };
+        let race = deps
+            .iter()
+            .copied()
+            .filter(|&j| steps[j].thread != step.thread && !ordered(&clock, j))
+            .max();
+        if let Some(j) = race {
+            let node = &mut stack[j];
+            if node.enabled.contains(&step.thread) {
+                node.backtrack.insert(step.thread);
+            } else {
+                node.backtrack.extend(node.enabled.iter().copied());
+            }
+        }
+
+        for &j in &deps {
+            join(&mut clock, &step_clocks[j]);
+        }
+        if clock.len() <= thread {
+            clock.resize(thread + 1, 0);
+        }
+        clock[thread] = i + 1;
+
+        // Everything up to this step happens before the next steps of the threads it enabled.
+        for &enabled in &step.enabled {
+            let enabled = enabled as usize;
+            if thread_clocks.len() <= enabled {
+                thread_clocks.resize(enabled + 1, Vec::new());
+                last_step.resize(enabled + 1, None);
+            }
+            join(&mut thread_clocks[enabled], &clock);
+        }
+        for range in &step.accesses {
+            last_access.extend(range.clone().map(|addr| (addr, i)));
+        }
+        if step.global {
+            last_global = Some(i);
+        }
+        last_step[thread] = Some(i);
+        thread_clocks[thread] = clock.clone();
+        step_clocks.push(clock);
+    }
+}
+
+/// Set `clock` to the pointwise maximum of itself and `other`.
+fn join(clock: &mut Vec<usize>, other: &[usize]) {
+    if clock.len() < other.len() {
+        clock.resize(other.len(), 0);
+    }
+    for (time, &other) in clock.iter_mut().zip(other) {
+        *time = (*time).max(other);
+    }
+}
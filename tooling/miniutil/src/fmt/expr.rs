@@ -72,12 +72,38 @@ pub(super) fn fmt_global_name(g: GlobalName) -> String {
     format!("global({id})")
 }
 
-pub(super) fn fmt_constant(c: Constant) -> FmtExpr {
+// Constants of their usual type are printed on their own (integers with a type suffix),
+// all others carry their type, e.g. `const<&pointee_info(..)>(global(0))`.
+pub(super) fn fmt_constant(c: Constant, ty: Type, comptypes: &mut Vec<CompType>) -> FmtExpr {
+    match (c, ty) {
+        (Constant::Int(int), Type::Int(int_ty)) => {
+            let int_ty = fmt_int_type(int_ty);
+            FmtExpr::Atomic(format!("{int}_{int_ty}"))
+        }
+        (Constant::Bool(_), Type::Bool) => fmt_untyped_constant(c),
+        (Constant::Float(Float::F32(_)), Type::Float(FloatType::F32))
+        | (Constant::Float(Float::F64(_)), Type::Float(FloatType::F64)) => fmt_untyped_constant(c),
+        (Constant::FnPointer(_), Type::Ptr(PtrType::FnPtr)) => fmt_untyped_constant(c),
+        (
+            Constant::GlobalPointer(_) | Constant::PointerWithoutProvenance(_),
+            Type::Ptr(PtrType::Raw { meta_kind: PointerMetaKind::None }),
+        ) => fmt_untyped_constant(c),
+        _ => {
+            let ty = fmt_type(ty, comptypes).to_string();
+            let c = fmt_untyped_constant(c).to_string();
+            FmtExpr::Atomic(format!("const<{ty}>({c})"))
+        }
+    }
+}
+
+fn fmt_untyped_constant(c: Constant) -> FmtExpr {
     match c {
         Constant::Int(int) => FmtExpr::Atomic(int.to_string()),
         Constant::Bool(b) => FmtExpr::Atomic(b.to_string()),
+        Constant::Float(f) => FmtExpr::Atomic(fmt_float(f)),
         Constant::GlobalPointer(relocation) => fmt_relocation(relocation),
         Constant::FnPointer(fn_name) => FmtExpr::Atomic(fmt_fn_name(fn_name)),
+        Constant::VTablePointer(vtable_name) => FmtExpr::Atomic(fmt_vtable_name(vtable_name)),
         Constant::PointerWithoutProvenance(addr) =>
             if addr == 0 {
                 FmtExpr::Atomic(format!("nullptr"))
@@ -87,27 +113,47 @@ pub(super) fn fmt_constant(c: Constant) -> FmtExpr {
     }
 }
 
+// Finite floats are printed with a type suffix, everything else by its bit pattern,
+// to make sure NaN payloads are not lost.
+pub(super) fn fmt_float(f: Float) -> String {
+    match f {
+        Float::F32(bits) if f32::from_bits(bits).is_finite() =>
+            format!("{:?}f32", f32::from_bits(bits)),
+        Float::F64(bits) if f64::from_bits(bits).is_finite() =>
+            format!("{:?}f64", f64::from_bits(bits)),
+        Float::F32(bits) => format!("f32_from_bits({bits:#x})"),
+        Float::F64(bits) => format!("f64_from_bits({bits:#x})"),
+    }
+}
+
+// Formats the type in front of a tuple, union or variant expression.
+// Types that cannot be used there are wrapped in parens, so that the output stays unambiguous even for ill-formed programs.
+fn fmt_aggregate_type(t: Type, comptypes: &mut Vec<CompType>) -> String {
+    match t {
+        Type::Tuple { .. } | Type::Union { .. } | Type::Enum { .. } | Type::Array { .. } =>
+            fmt_type(t, comptypes).to_string(),
+        _ => format!("({})", fmt_type(t, comptypes).to_string()),
+    }
+}
+
 pub(super) fn fmt_value_expr(v: ValueExpr, comptypes: &mut Vec<CompType>) -> FmtExpr {
     match v {
-        ValueExpr::Constant(c, _ty) => fmt_constant(c),
+        ValueExpr::Constant(c, ty) => fmt_constant(c, ty, comptypes),
         ValueExpr::Tuple(l, t) => {
-            let (lparen, rparen) = match t {
-                Type::Array { .. } => ('[', ']'),
-                Type::Tuple { .. } => ('(', ')'),
-                _ => panic!(),
-            };
+            // The type is printed in front, e.g. `T0(1_u32, true)` or `[u8; 2](0_u8, 1_u8)`.
+            let t = fmt_aggregate_type(t, comptypes);
             let l: Vec<_> = l.iter().map(|x| fmt_value_expr(x, comptypes).to_string()).collect();
             let l = l.join(", ");
 
-            FmtExpr::Atomic(format!("{lparen}{l}{rparen}"))
+            FmtExpr::Atomic(format!("{t}({l})"))
         }
         ValueExpr::Union { field, expr, union_ty } => {
-            let union_ty = fmt_type(union_ty, comptypes).to_string();
+            let union_ty = fmt_aggregate_type(union_ty, comptypes);
             let expr = fmt_value_expr(expr.extract(), comptypes).to_string();
             FmtExpr::NonAtomic(format!("{union_ty} {{ field{field}: {expr} }}"))
         }
         ValueExpr::Variant { discriminant, data, enum_ty } => {
-            let enum_ty = fmt_type(enum_ty, comptypes).to_string();
+            let enum_ty = fmt_aggregate_type(enum_ty, comptypes);
             let expr = fmt_value_expr(data.extract(), comptypes).to_string();
             FmtExpr::NonAtomic(format!("{enum_ty}(variant {discriminant}): {expr}"))
         }
@@ -120,37 +166,67 @@ pub(super) fn fmt_value_expr(v: ValueExpr, comptypes: &mut Vec<CompType>) -> Fmt
             let source = fmt_place_expr(source, comptypes).to_string();
             FmtExpr::Atomic(format!("load({source})"))
         }
-        ValueExpr::AddrOf { target, ptr_ty: PtrType::Raw { .. } } => {
+        ValueExpr::AddrOf { target, ptr_ty: PtrType::Raw { meta_kind: PointerMetaKind::None } } => {
             let target = target.extract();
             let target = fmt_place_expr(target, comptypes).to_atomic_string();
             FmtExpr::NonAtomic(format!("&raw {target}"))
         }
-        ValueExpr::AddrOf { target, ptr_ty: PtrType::Ref { mutbl, .. } } => {
+        ValueExpr::AddrOf { target, ptr_ty: PtrType::Ref { mutbl, pointee } } => {
             let target = target.extract();
             let target = fmt_place_expr(target, comptypes).to_atomic_string();
             let mutbl = match mutbl {
-                Mutability::Mutable => "mut ",
+                Mutability::Mutable => "mut",
                 Mutability::Immutable => "",
             };
-            FmtExpr::NonAtomic(format!("&{mutbl}{target}"))
+            let pointee = fmt_pointee_info(pointee);
+            FmtExpr::NonAtomic(format!("&{mutbl}<{pointee}> {target}"))
         }
-        ValueExpr::AddrOf { target: _, ptr_ty: _ } => {
-            panic!("unsupported ptr_ty for AddrOr!")
+        ValueExpr::AddrOf { target, ptr_ty } => {
+            let target = fmt_place_expr(target.extract(), comptypes).to_string();
+            let ptr_ty = fmt_ptr_type(ptr_ty).to_string();
+            FmtExpr::Atomic(format!("addr_of<{ptr_ty}>({target})"))
         }
         ValueExpr::UnOp { operator, operand } => {
             let operand = fmt_value_expr(operand.extract(), comptypes).to_string();
             match operator {
                 UnOp::Int(IntUnOp::Neg) => FmtExpr::NonAtomic(format!("-({operand})")),
-                UnOp::Int(IntUnOp::BitNot) => FmtExpr::NonAtomic(format!("!({operand}")),
+                UnOp::Int(IntUnOp::BitNot) => FmtExpr::NonAtomic(format!("!({operand})")),
+                UnOp::Float(FloatUnOp::Neg) => FmtExpr::Atomic(format!("FloatNeg({operand})")),
                 UnOp::Cast(CastOp::IntToInt(int_ty)) => {
                     let int_ty = fmt_int_type(int_ty);
                     FmtExpr::Atomic(format!("int2int<{int_ty}>({operand})"))
                 }
+                UnOp::Cast(CastOp::FloatToInt(int_ty)) => {
+                    let int_ty = fmt_int_type(int_ty);
+                    FmtExpr::Atomic(format!("float2int<{int_ty}>({operand})"))
+                }
+                UnOp::Cast(CastOp::IntToFloat(float_ty)) => {
+                    let float_ty = fmt_float_type(float_ty);
+                    FmtExpr::Atomic(format!("int2float<{float_ty}>({operand})"))
+                }
+                UnOp::Cast(CastOp::FloatToFloat(float_ty)) => {
+                    let float_ty = fmt_float_type(float_ty);
+                    FmtExpr::Atomic(format!("float2float<{float_ty}>({operand})"))
+                }
                 UnOp::Cast(CastOp::Transmute(new_ty)) => {
                     let new_ty = fmt_type(new_ty, comptypes).to_string();
                     FmtExpr::Atomic(format!("transmute<{new_ty}>({operand})"))
                 }
                 UnOp::GetMetadata => FmtExpr::Atomic(format!("get_metadata({operand})")),
+                UnOp::GetThinPointer => FmtExpr::Atomic(format!("get_thin_pointer({operand})")),
+                UnOp::VTableMethodLookup(method) => {
+                    let method = fmt_trait_method_name(method);
+                    FmtExpr::Atomic(format!("vtable_method_lookup<{method}>({operand})"))
+                }
+                UnOp::VTableDropLookup => FmtExpr::Atomic(format!("vtable_drop_lookup({operand})")),
+                UnOp::ComputeSize(ty) => {
+                    let ty = fmt_type(ty, comptypes).to_string();
+                    FmtExpr::Atomic(format!("compute_size<{ty}>({operand})"))
+                }
+                UnOp::ComputeAlign(ty) => {
+                    let ty = fmt_type(ty, comptypes).to_string();
+                    FmtExpr::Atomic(format!("compute_align<{ty}>({operand})"))
+                }
             }
         }
         ValueExpr::BinOp { operator: BinOp::Int(int_op), left, right } => {
@@ -179,6 +255,21 @@ pub(super) fn fmt_value_expr(v: ValueExpr, comptypes: &mut Vec<CompType>) -> Fmt
 
             FmtExpr::NonAtomic(format!("{l} {int_op} {r}"))
         }
+        ValueExpr::BinOp { operator: BinOp::Float(float_op), left, right } => {
+            let l = fmt_value_expr(left.extract(), comptypes).to_atomic_string();
+            let r = fmt_value_expr(right.extract(), comptypes).to_atomic_string();
+
+            // These are printed like function calls to distinguish them from integer operations.
+            let float_op = match float_op {
+                FloatBinOp::Add => "FloatAdd",
+                FloatBinOp::Sub => "FloatSub",
+                FloatBinOp::Mul => "FloatMul",
+                FloatBinOp::Div => "FloatDiv",
+                FloatBinOp::Rem => "FloatRem",
+            };
+
+            FmtExpr::Atomic(format!("{float_op}({l}, {r})"))
+        }
         ValueExpr::BinOp { operator: BinOp::IntWithOverflow(op), left, right } => {
             let l = fmt_value_expr(left.extract(), comptypes).to_atomic_string();
             let r = fmt_value_expr(right.extract(), comptypes).to_atomic_string();
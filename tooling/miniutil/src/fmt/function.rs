@@ -33,11 +33,9 @@ fn fmt_function(
     let ret_str = format!("-> {}", fmt_local_name(f.ret));
 
     // Format function signature
-    let mut out = if start {
-        format!("start fn {fn_name}({args}) {ret_str} {{\n")
-    } else {
-        format!("fn {fn_name}({args}) {ret_str} {{\n")
-    };
+    let start = if start { "start " } else { "" };
+    let conv = fmt_calling_convention(f.calling_convention);
+    let mut out = format!("{start}{conv}fn {fn_name}({args}) {ret_str} {{\n");
 
     // Format locals
     let mut locals: Vec<(LocalName, Type)> = f.locals.iter().collect();
@@ -82,7 +80,7 @@ fn fmt_bb(bb_name: BbName, bb: BasicBlock, start: bool, comptypes: &mut Vec<Comp
     out
 }
 
-fn fmt_statement(st: Statement, comptypes: &mut Vec<CompType>) -> String {
+pub(super) fn fmt_statement(st: Statement, comptypes: &mut Vec<CompType>) -> String {
     match st {
         Statement::Assign { destination, source } => {
             let left = fmt_place_expr(destination, comptypes).to_string();
@@ -123,6 +121,7 @@ fn fmt_call(
     args: String,
     ret: PlaceExpr,
     next_block: Option<BbName>,
+    unwind_block: Option<BbName>,
     comptypes: &mut Vec<CompType>,
 ) -> String {
     // Format return place
@@ -137,16 +136,22 @@ fn fmt_call(
         None => String::new(),
     };
 
-    // Format calling convention
-    let conv = match conv {
-        CallingConvention::Rust => format!(""),
-        c => format!("extern \"{c:?}\""),
+    // Format unwind block
+    let unwind = match unwind_block {
+        Some(unwind_block) => {
+            let unwind_str = fmt_bb_name(unwind_block);
+            format!(", unwind -> {unwind_str}")
+        }
+        None => String::new(),
     };
 
-    format!("    {r} = {conv}{callee}({args}){next};")
+    // Format calling convention
+    let conv = fmt_calling_convention(conv);
+
+    format!("    {r} = {conv}{callee}({args}){next}{unwind};")
 }
 
-fn fmt_terminator(t: Terminator, comptypes: &mut Vec<CompType>) -> String {
+pub(super) fn fmt_terminator(t: Terminator, comptypes: &mut Vec<CompType>) -> String {
     match t {
         Terminator::Goto(bb) => {
             let bb = fmt_bb_name(bb);
@@ -169,7 +174,14 @@ fn fmt_terminator(t: Terminator, comptypes: &mut Vec<CompType>) -> String {
         Terminator::Unreachable => {
             format!("    unreachable;")
         }
-        Terminator::Call { callee, calling_convention: conv, arguments, ret, next_block } => {
+        Terminator::Call {
+            callee,
+            calling_convention: conv,
+            arguments,
+            ret,
+            next_block,
+            unwind_block,
+        } => {
             let callee = fmt_value_expr(callee, comptypes).to_atomic_string();
             let args: Vec<_> = arguments
                 .iter()
@@ -184,50 +196,113 @@ fn fmt_terminator(t: Terminator, comptypes: &mut Vec<CompType>) -> String {
                     }
                 })
                 .collect();
-            fmt_call(&callee, conv, args.join(", "), ret, next_block, comptypes)
+            fmt_call(&callee, conv, args.join(", "), ret, next_block, unwind_block, comptypes)
         }
         Terminator::Return => {
             format!("    return;")
         }
-        Terminator::Intrinsic { intrinsic, arguments, ret, next_block } => {
-            let callee = match intrinsic {
+        Terminator::ResumeUnwind => {
+            format!("    resume_unwind;")
+        }
+        Terminator::Intrinsic { intrinsic, arguments, ret, next_block, unwind_block } => {
+            let name = match intrinsic {
                 IntrinsicOp::Assume => "assume",
                 IntrinsicOp::Exit => "exit",
                 IntrinsicOp::Panic => "panic",
+                IntrinsicOp::Abort => "abort",
+                IntrinsicOp::CatchUnwind => "catch_unwind",
                 IntrinsicOp::PrintStdout => "print",
                 IntrinsicOp::PrintStderr => "eprint",
                 IntrinsicOp::Allocate => "allocate",
                 IntrinsicOp::Deallocate => "deallocate",
+                IntrinsicOp::Reallocate => "reallocate",
                 IntrinsicOp::Spawn => "spawn",
                 IntrinsicOp::Join => "join",
                 IntrinsicOp::RawEq => "raw_eq",
-                IntrinsicOp::AtomicStore => "atomic_store",
-                IntrinsicOp::AtomicLoad => "atomic_load",
-                IntrinsicOp::AtomicCompareExchange => "atomic_compare_exchange",
-                IntrinsicOp::AtomicFetchAndOp(binop) => fmt_fetch(binop),
+                IntrinsicOp::AtomicStore(_) => "atomic_store",
+                IntrinsicOp::AtomicLoad(_) => "atomic_load",
+                IntrinsicOp::AtomicCompareExchange { .. } => "atomic_compare_exchange",
+                IntrinsicOp::AtomicFetchAndOp(binop, _) => fmt_fetch(binop),
+                IntrinsicOp::AtomicFence(_) => "atomic_fence",
                 IntrinsicOp::Lock(IntrinsicLockOp::Acquire) => "lock_acquire",
                 IntrinsicOp::Lock(IntrinsicLockOp::Create) => "lock_create",
                 IntrinsicOp::Lock(IntrinsicLockOp::Release) => "lock_release",
                 IntrinsicOp::PointerExposeProvenance => "pointer_expose_provenance",
                 IntrinsicOp::PointerWithExposedProvenance => "pointer_with_exposed_provenance",
             };
+            // Atomic operations show their memory orderings as generic arguments.
+            let callee = match intrinsic {
+                IntrinsicOp::AtomicStore(ordering)
+                | IntrinsicOp::AtomicLoad(ordering)
+                | IntrinsicOp::AtomicFetchAndOp(_, ordering)
+                | IntrinsicOp::AtomicFence(ordering) => {
+                    let ordering = fmt_ordering(ordering);
+                    format!("{name}<{ordering}>")
+                }
+                IntrinsicOp::AtomicCompareExchange { success, failure } => {
+                    let success = fmt_ordering(success);
+                    let failure = fmt_ordering(failure);
+                    format!("{name}<{success}, {failure}>")
+                }
+                _ => name.to_string(),
+            };
             let args: Vec<_> =
                 arguments.iter().map(|arg| fmt_value_expr(arg, comptypes).to_string()).collect();
-            fmt_call(callee, CallingConvention::Rust, args.join(", "), ret, next_block, comptypes)
+            fmt_call(
+                &callee,
+                CallingConvention::Rust,
+                args.join(", "),
+                ret,
+                next_block,
+                unwind_block,
+                comptypes,
+            )
         }
     }
 }
 
+// The Rust calling convention is the default and hence not printed.
+fn fmt_calling_convention(conv: CallingConvention) -> String {
+    match conv {
+        CallingConvention::Rust => format!(""),
+        c => format!("extern \"{c:?}\" "),
+    }
+}
+
+// Only `Add` and `Sub` are well-formed, but we print all operations so that the output can be parsed back.
 fn fmt_fetch(binop: IntBinOp) -> &'static str {
     use IntBinOp as B;
     match binop {
         B::Add => "atomic_fetch_add",
         B::Sub => "atomic_fetch_sub",
-        _ => "atomic_fetch_ILL_FORMED",
+        B::Mul => "atomic_fetch_mul",
+        B::Div => "atomic_fetch_div",
+        B::Rem => "atomic_fetch_rem",
+        B::Shl => "atomic_fetch_shl",
+        B::Shr => "atomic_fetch_shr",
+        B::BitAnd => "atomic_fetch_bit_and",
+        B::BitOr => "atomic_fetch_bit_or",
+        B::BitXor => "atomic_fetch_bit_xor",
+        B::AddUnchecked => "atomic_fetch_add_unchecked",
+        B::SubUnchecked => "atomic_fetch_sub_unchecked",
+        B::MulUnchecked => "atomic_fetch_mul_unchecked",
+        B::DivExact => "atomic_fetch_div_exact",
+        B::ShlUnchecked => "atomic_fetch_shl_unchecked",
+        B::ShrUnchecked => "atomic_fetch_shr_unchecked",
+    }
+}
+
+fn fmt_ordering(ordering: MemoryOrdering) -> &'static str {
+    match ordering {
+        MemoryOrdering::Relaxed => "Relaxed",
+        MemoryOrdering::Acquire => "Acquire",
+        MemoryOrdering::Release => "Release",
+        MemoryOrdering::AcqRel => "AcqRel",
+        MemoryOrdering::SeqCst => "SeqCst",
     }
 }
 
-fn fmt_bb_name(bb: BbName) -> String {
+pub(super) fn fmt_bb_name(bb: BbName) -> String {
     let id = bb.0.get_internal();
     format!("bb{id}")
 }
@@ -34,11 +34,19 @@ fn fmt_global(gname: GlobalName, global: Global) -> String {
   bytes = [{bytes_str}],
   align = {align} bytes,\n"
     );
+    if global.thread_local {
+        out += "  thread_local,\n";
+    }
     for (i, rel) in global.relocations {
         let i = i.bytes();
         let rel_str = fmt_relocation(rel).to_string();
         out += &format!("  at byte {i}: {rel_str},\n");
     }
+    for (i, fn_name) in global.fn_pointers {
+        let i = i.bytes();
+        let fn_name_str = fmt_fn_name(fn_name);
+        out += &format!("  at byte {i}: {fn_name_str},\n");
+    }
     out += "}\n\n";
     out
 }
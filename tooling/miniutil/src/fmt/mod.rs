@@ -12,6 +12,12 @@ use ty::*;
 mod global;
 use global::*;
 
+mod vtable;
+use vtable::*;
+
+mod value;
+pub use value::*;
+
 // Print a program to stdout.
 pub fn dump_program(prog: Program) {
     let s = fmt_program(prog);
@@ -25,6 +31,85 @@ pub fn fmt_program(prog: Program) -> String {
     let functions_string = fmt_functions(prog, &mut comptypes);
     let comptypes_string = fmt_comptypes(comptypes);
     let globals_string = fmt_globals(prog.globals);
+    let traits_string = fmt_traits(prog.traits);
+    let vtables_string = fmt_vtables(prog.vtables);
+
+    // If the start function does not exist, the program is ill-formed, but we still print which one it is.
+    let start_string = match prog.functions.contains_key(prog.start) {
+        true => String::new(),
+        false => format!("start {};\n\n", fmt_fn_name(prog.start)),
+    };
+
+    comptypes_string
+        + &functions_string
+        + &globals_string
+        + &traits_string
+        + &vtables_string
+        + &start_string
+}
+
+/// Formats single statements and terminators of a program on one line each,
+/// using the same names for composite types as `fmt_program`.
+pub struct StatementFormatter {
+    comptypes: Vec<CompType>,
+}
+
+impl StatementFormatter {
+    pub fn new(prog: Program) -> Self {
+        let mut comptypes: Vec<CompType> = Vec::new();
+        fmt_functions(prog, &mut comptypes);
+        StatementFormatter { comptypes }
+    }
+
+    pub fn fmt_statement(&mut self, st: Statement) -> String {
+        one_line(&fmt_statement(st, &mut self.comptypes))
+    }
+
+    pub fn fmt_terminator(&mut self, t: Terminator) -> String {
+        one_line(&fmt_terminator(t, &mut self.comptypes))
+    }
+
+    pub fn fmt_value_expr(&mut self, v: ValueExpr) -> String {
+        one_line(&fmt_value_expr(v, &mut self.comptypes).to_string())
+    }
+
+    pub fn fmt_place_expr(&mut self, p: PlaceExpr) -> String {
+        one_line(&fmt_place_expr(p, &mut self.comptypes).to_string())
+    }
+
+    pub fn fmt_type(&mut self, t: Type) -> String {
+        one_line(&fmt_type(t, &mut self.comptypes).to_string())
+    }
+}
+
+/// Formats a well-formedness error, followed by the statement or terminator
+/// (and the expression inside it) where it was found, if that is known.
+pub fn fmt_ill_formed(prog: Program, err: IllFormedError) -> String {
+    let msg = err.msg.get_internal();
+    let loc = err.location;
+    let Some(fn_name) = loc.func else { return msg.to_string() };
+    let mut out = format!("{msg}\n    in {}", fmt_fn_name(fn_name));
+    let mut formatter = StatementFormatter::new(prog);
+    if let Some((bb_name, position)) = loc.block {
+        let block = prog.functions.index_at(fn_name).blocks.index_at(bb_name);
+        let (position, text) = match position {
+            BlockPosition::Statement(idx) =>
+                (format!("stmt {idx}"), formatter.fmt_statement(block.statements.index_at(idx))),
+            BlockPosition::Terminator =>
+                ("terminator".to_string(), formatter.fmt_terminator(block.terminator)),
+        };
+        out += &format!(" {} {position}: {text}", fmt_bb_name(bb_name));
+    }
+    if let Some(expr) = loc.expr {
+        let expr = match expr {
+            WfExpr::Value(v) => formatter.fmt_value_expr(v),
+            WfExpr::Place(p) => formatter.fmt_place_expr(p),
+        };
+        out += &format!("\n    in expression: {expr}");
+    }
+    out
+}
 
-    comptypes_string + &functions_string + &globals_string
+fn one_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
 }
@@ -5,6 +5,8 @@ pub(super) fn fmt_type(t: Type, comptypes: &mut Vec<CompType>) -> FmtExpr {
         Type::Int(int_ty) => FmtExpr::Atomic(fmt_int_type(int_ty)),
         Type::Ptr(ptr_ty) => fmt_ptr_type(ptr_ty),
         Type::Bool => FmtExpr::Atomic(format!("bool")),
+        Type::Char => FmtExpr::Atomic(format!("char")),
+        Type::Float(float_ty) => FmtExpr::Atomic(fmt_float_type(float_ty)),
         Type::Tuple { .. } | Type::Union { .. } | Type::Enum { .. } => {
             let comp_ty = CompType(t);
             let comptype_index = get_comptype_index(comp_ty, comptypes);
@@ -18,6 +20,10 @@ pub(super) fn fmt_type(t: Type, comptypes: &mut Vec<CompType>) -> FmtExpr {
             let elem = fmt_type(elem.extract(), comptypes).to_string();
             FmtExpr::Atomic(format!("[{elem}]"))
         }
+        Type::TraitObject(trait_name) => {
+            let trait_name = fmt_trait_name(trait_name);
+            FmtExpr::NonAtomic(format!("dyn {trait_name}"))
+        }
     }
 }
 
@@ -31,6 +37,13 @@ pub(super) fn fmt_int_type(int_ty: IntType) -> String {
     format!("{signed}{bits}")
 }
 
+pub(super) fn fmt_float_type(float_ty: FloatType) -> String {
+    match float_ty {
+        FloatType::F32 => format!("f32"),
+        FloatType::F64 => format!("f64"),
+    }
+}
+
 pub(super) fn fmt_ptr_type(ptr_ty: PtrType) -> FmtExpr {
     match ptr_ty {
         PtrType::Ref { mutbl: Mutability::Mutable, pointee } => {
@@ -50,32 +63,60 @@ pub(super) fn fmt_ptr_type(ptr_ty: PtrType) -> FmtExpr {
             FmtExpr::NonAtomic(format!("*raw({meta_kind_str})"))
         }
         PtrType::FnPtr => FmtExpr::Atomic(format!("fn()")),
+        PtrType::VTablePtr(trait_name) => {
+            let trait_name = fmt_trait_name(trait_name);
+            FmtExpr::Atomic(format!("vtable<{trait_name}>"))
+        }
     }
 }
 
-fn fmt_meta_kind(kind: PointerMetaKind) -> &'static str {
+fn fmt_meta_kind(kind: PointerMetaKind) -> String {
     match kind {
-        PointerMetaKind::None => "thin",
-        PointerMetaKind::ElementCount => "meta=len",
+        PointerMetaKind::None => format!("thin"),
+        PointerMetaKind::ElementCount => format!("meta=len"),
+        PointerMetaKind::VTablePointer(trait_name) => {
+            let trait_name = fmt_trait_name(trait_name);
+            format!("meta=vtable<{trait_name}>")
+        }
     }
 }
 
-fn fmt_pointee_info(pointee: PointeeInfo) -> String {
+pub(super) fn fmt_pointee_info(pointee: PointeeInfo) -> String {
     let size_str = match pointee.size {
         SizeStrategy::Sized(size) => format!("{}", size.bytes()),
         SizeStrategy::Slice(size) => format!("{}*len", size.bytes()),
+        SizeStrategy::VTable(_) => format!("vtable.size"),
     };
     let align = pointee.align.bytes();
     let uninhab_str = match pointee.inhabited {
         true => "",
         false => ", uninhabited",
     };
-    let freeze_str = match pointee.inhabited {
+    let freeze_str = match pointee.freeze {
         true => ", freeze",
         false => "",
     };
+    let unpin_str = match pointee.unpin {
+        true => ", unpin",
+        false => "",
+    };
+    let cells: Vec<String> = pointee
+        .unsafe_cells
+        .iter()
+        .map(|(offset, size)| format!("{}..{}", offset.bytes(), (offset + size).bytes()))
+        .collect();
+    let cells_str = match cells.is_empty() {
+        true => String::new(),
+        false => format!(", unsafe_cells=[{}]", cells.join(", ")),
+    };
+    let byte_slice_str = match pointee.byte_slice {
+        true => ", byte_slice",
+        false => "",
+    };
     let meta_str = fmt_meta_kind(pointee.size.meta_kind());
-    format!("pointee_info({meta_str}, size={size_str}, align={align}{uninhab_str}{freeze_str})")
+    format!(
+        "pointee_info({meta_str}, size={size_str}, align={align}{uninhab_str}{freeze_str}{unpin_str}{cells_str}{byte_slice_str})"
+    )
 }
 
 /////////////////////
@@ -150,13 +191,18 @@ fn fmt_comptype(i: CompTypeIndex, t: CompType, comptypes: &mut Vec<CompType>) ->
             s += &fmt_comptype_fields(fields, comptypes);
             s += &fmt_comptype_chunks(chunks);
         }
-        Type::Enum { variants, discriminant_ty, .. } => {
+        Type::Enum { variants, discriminant_ty, discriminator, .. } => {
             let discr = fmt_int_type(discriminant_ty);
             s += &format!("  Discriminant: {discr}\n");
-            variants.iter().for_each(|(discriminant, v)| {
+            let mut variants: Vec<(Int, Variant)> = variants.iter().collect();
+            variants.sort_by_key(|(discriminant, _)| *discriminant);
+            for (discriminant, v) in variants {
                 let typ = fmt_type(v.ty, comptypes).to_string();
-                s += &format!("  Variant {discriminant}: {typ}\n");
-            });
+                let tagger = fmt_tagger(v.tagger);
+                s += &format!("  Variant {discriminant}: {typ}{tagger}\n");
+            }
+            let discriminator = fmt_discriminator(discriminator);
+            s += &format!("  Discriminator: {discriminator}\n");
         }
         _ => panic!("not a supported composite type!"),
     };
@@ -183,3 +229,43 @@ fn fmt_comptype_chunks(chunks: List<(Offset, Size)>) -> String {
     }
     s
 }
+
+// Formats the tag written by a variant, e.g. `, tag(at byte 4: u8 = 1)`.
+fn fmt_tagger(tagger: Map<Offset, (IntType, Int)>) -> String {
+    let mut tagger: Vec<(Offset, (IntType, Int))> = tagger.iter().collect();
+    if tagger.is_empty() {
+        return String::new();
+    }
+    tagger.sort_by_key(|(offset, _)| offset.bytes());
+    let tags: Vec<String> = tagger
+        .into_iter()
+        .map(|(offset, (int_ty, value))| {
+            let offset = offset.bytes();
+            let int_ty = fmt_int_type(int_ty);
+            format!("at byte {offset}: {int_ty} = {value}")
+        })
+        .collect();
+    format!(", tag({})", tags.join(", "))
+}
+
+fn fmt_discriminator(discriminator: Discriminator) -> String {
+    match discriminator {
+        Discriminator::Known(discriminant) => format!("known({discriminant})"),
+        Discriminator::Invalid => format!("invalid"),
+        Discriminator::Branch { offset, value_type, fallback, children } => {
+            let offset = offset.bytes();
+            let value_type = fmt_int_type(value_type);
+            let fallback = fmt_discriminator(fallback.extract());
+            let mut children: Vec<((Int, Int), Discriminator)> = children.iter().collect();
+            children.sort_by_key(|((start, end), _)| (*start, *end));
+            let children: Vec<String> = children
+                .into_iter()
+                .map(|((start, end), child)| {
+                    format!("{start}..{end}: {}", fmt_discriminator(child))
+                })
+                .collect();
+            let children = children.join(", ");
+            format!("branch(at byte {offset}: {value_type}, fallback: {fallback}, [{children}])")
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use super::*;
+
+// Formats a value, e.g. the current value of a local in the debugger.
+// Values do not know their type, so tuples, arrays and structs all look the same.
+pub fn fmt_value<M: Memory>(v: Value<M>) -> String {
+    match v {
+        Value::Int(int) => int.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Float(f) => fmt_float(f),
+        Value::Ptr(ptr) => {
+            let addr = fmt_address(ptr.thin_pointer.addr);
+            match ptr.metadata {
+                None => format!("ptr({addr})"),
+                Some(PointerMeta::ElementCount(count)) => format!("ptr({addr}, len {count})"),
+                Some(PointerMeta::VTablePointer(vtable)) =>
+                    format!("ptr({addr}, vtable {})", fmt_address(vtable.addr)),
+            }
+        }
+        Value::Tuple(vals) => {
+            let vals: Vec<String> = vals.iter().map(fmt_value).collect();
+            format!("({})", vals.join(", "))
+        }
+        Value::Variant { discriminant, data } =>
+            format!("variant {discriminant} {}", fmt_value(data.extract())),
+        Value::Union(chunks) => {
+            let chunks: Vec<String> = chunks.iter().map(fmt_bytes::<M>).collect();
+            format!("union [{}]", chunks.join(", "))
+        }
+    }
+}
+
+// Formats raw bytes in hex. Uninitialized bytes are shown as `__`,
+// and bytes with provenance are marked with a `*`.
+pub fn fmt_bytes<M: Memory>(bytes: List<AbstractByte<M::Provenance>>) -> String {
+    let bytes: Vec<String> = bytes
+        .iter()
+        .map(|byte| {
+            match byte {
+                AbstractByte::Uninit => "__".to_string(),
+                AbstractByte::Init(val, None) => format!("{val:02x}"),
+                AbstractByte::Init(val, Some(_)) => format!("{val:02x}*"),
+            }
+        })
+        .collect();
+    bytes.join(" ")
+}
+
+pub fn fmt_address(addr: Address) -> String {
+    format!("{:#x}", addr.try_to_usize().unwrap())
+}
@@ -0,0 +1,72 @@
+use super::*;
+
+pub(super) fn fmt_trait_name(trait_name: TraitName) -> String {
+    let id = trait_name.0.get_internal();
+    format!("trait({id})")
+}
+
+pub(super) fn fmt_trait_method_name(method: TraitMethodName) -> String {
+    let id = method.0.get_internal();
+    format!("m{id}")
+}
+
+pub(super) fn fmt_vtable_name(vtable_name: VTableName) -> String {
+    let id = vtable_name.0.get_internal();
+    format!("vtable({id})")
+}
+
+pub(super) fn fmt_traits(traits: Map<TraitName, Set<TraitMethodName>>) -> String {
+    let mut out = String::new();
+
+    let mut traits: Vec<(TraitName, Set<TraitMethodName>)> = traits.iter().collect();
+
+    // The traits are formatted in the order of their names.
+    traits.sort_by_key(|(TraitName(name), _methods)| *name);
+
+    for (trait_name, methods) in traits {
+        let trait_name = fmt_trait_name(trait_name);
+        let mut methods: Vec<TraitMethodName> = methods.iter().collect();
+        methods.sort_by_key(|TraitMethodName(name)| *name);
+        let methods: Vec<String> = methods.into_iter().map(fmt_trait_method_name).collect();
+        let methods = methods.join(", ");
+        out += &format!("{trait_name} {{ {methods} }}\n\n");
+    }
+    out
+}
+
+pub(super) fn fmt_vtables(vtables: Map<VTableName, VTable>) -> String {
+    let mut out = String::new();
+
+    let mut vtables: Vec<(VTableName, VTable)> = vtables.iter().collect();
+
+    // The vtables are formatted in the order of their names.
+    vtables.sort_by_key(|(VTableName(name), _vtable)| *name);
+
+    for (vtable_name, vtable) in vtables {
+        out += &fmt_vtable(vtable_name, vtable);
+    }
+    out
+}
+
+fn fmt_vtable(vtable_name: VTableName, vtable: VTable) -> String {
+    let vtable_name = fmt_vtable_name(vtable_name);
+    let trait_name = fmt_trait_name(vtable.trait_name);
+    let size = vtable.size.bytes();
+    let align = vtable.align.bytes();
+    let drop = fmt_fn_name(vtable.drop);
+    let mut out = format!(
+        "{vtable_name} for {trait_name} {{
+  size = {size} bytes,
+  align = {align} bytes,
+  drop = {drop},\n"
+    );
+    let mut methods: Vec<(TraitMethodName, FnName)> = vtable.methods.iter().collect();
+    methods.sort_by_key(|(TraitMethodName(name), _fn_name)| *name);
+    for (method, fn_name) in methods {
+        let method = fmt_trait_method_name(method);
+        let fn_name = fmt_fn_name(fn_name);
+        out += &format!("  {method} = {fn_name},\n");
+    }
+    out += "}\n\n";
+    out
+}
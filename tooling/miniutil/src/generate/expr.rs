@@ -0,0 +1,303 @@
+use super::*;
+
+/// The builder functions for operators, so that `Gen::pick` can choose among them.
+type UnOpFn = fn(ValueExpr) -> ValueExpr;
+type BinOpFn = fn(ValueExpr, ValueExpr) -> ValueExpr;
+
+/// The kinds of integer expressions `gen_int` generates.
+#[derive(Clone, Copy)]
+enum IntExpr {
+    Const,
+    BinOp,
+    Shift,
+    UnOp,
+    Div,
+    Cast,
+    FromBool,
+    FromFloat,
+    Cmp,
+    Discriminant,
+}
+
+impl FnGen<'_> {
+    /// All places of the function: the locals, their fields, array elements and union fields,
+    /// and the places that pointers in them point to.
+    pub(super) fn places(&self) -> Vec<(PlaceExpr, GenTy)> {
+        self.collect_places(false)
+    }
+
+    /// With `init`, only places that can be computed while the locals are being initialized:
+    /// no derefs and no indices loaded from locals.
+    fn collect_places(&self, init: bool) -> Vec<(PlaceExpr, GenTy)> {
+        let mut places = Vec::new();
+        for (local, ty) in &self.locals {
+            self.subplaces(*local, ty, !init, init, &mut places);
+        }
+        places
+    }
+
+    fn subplaces(
+        &self,
+        place: PlaceExpr,
+        ty: &GenTy,
+        deref_ptrs: bool,
+        init: bool,
+        out: &mut Vec<(PlaceExpr, GenTy)>,
+    ) {
+        out.push((place, ty.clone()));
+        match ty {
+            GenTy::Tuple(fields) =>
+                for (field_idx, field_ty) in fields.iter().enumerate() {
+                    self.subplaces(field(place, field_idx), field_ty, deref_ptrs, init, out);
+                },
+            GenTy::Array(elem, count) => {
+                let elem_place = index(place, self.gen_index(*count, init));
+                self.subplaces(elem_place, elem, deref_ptrs, init, out);
+            }
+            GenTy::Union(fields) =>
+                for (field_idx, int_ty) in fields.iter().enumerate() {
+                    out.push((field(place, field_idx), GenTy::Int(*int_ty)));
+                },
+            // We only go through one pointer, to keep the number of places small.
+            GenTy::Ptr(pointee) if deref_ptrs =>
+                self.subplaces(deref(load(place), pointee.ty()), pointee, false, init, out),
+            // The data of enums is only accessed by `gen_match`, which checks the variant first.
+            _ => {}
+        }
+    }
+
+    /// An index into an array of `count` elements, which is always in bounds.
+    fn gen_index(&self, count: u32, init: bool) -> ValueExpr {
+        let ints: Vec<PlaceExpr> = self
+            .locals
+            .iter()
+            .filter(|(_, ty)| matches!(ty, GenTy::Int(_)))
+            .map(|(local, _)| *local)
+            .collect();
+        if init || ints.is_empty() || self.gen.one_in(2) {
+            return const_int(self.gen.below(count as usize));
+        }
+        let int = int_cast::<usize>(load(*self.gen.pick(&ints)));
+        rem(int, const_int(count as usize))
+    }
+
+    /// A random place of type `ty`, if there is one.
+    pub(super) fn pick_place(&self, ty: &GenTy) -> Option<PlaceExpr> {
+        self.pick_place_of(ty, false)
+    }
+
+    fn pick_place_of(&self, ty: &GenTy, init: bool) -> Option<PlaceExpr> {
+        let places: Vec<PlaceExpr> = self
+            .collect_places(init)
+            .into_iter()
+            .filter(|(_, place_ty)| place_ty == ty)
+            .map(|(place, _)| place)
+            .collect();
+        if places.is_empty() { None } else { Some(*self.gen.pick(&places)) }
+    }
+
+    /// A random place of any type.
+    pub(super) fn pick_any_place(&self) -> (PlaceExpr, GenTy) {
+        // There is at least one local besides the arguments.
+        self.gen.pick(&self.places()).clone()
+    }
+
+    /// A random value of type `ty`, with operators nested at most `depth` deep.
+    pub(super) fn gen_value(&self, ty: &GenTy, depth: u32) -> ValueExpr {
+        if depth == 0 || self.gen.one_in(3) {
+            if let Some(place) = self.pick_place(ty) {
+                return load(place);
+            }
+        }
+        match ty {
+            GenTy::Int(int_ty) => self.gen_int(*int_ty, depth),
+            GenTy::Bool => self.gen_bool(depth),
+            GenTy::Float(float) => self.gen_float(*float, depth),
+            _ => self.gen_compound(ty, false, |ty| self.gen_value(ty, depth.saturating_sub(1))),
+        }
+    }
+
+    /// A value of type `ty` that does not read any locals,
+    /// to initialize them at the start of the function.
+    pub(super) fn gen_const(&self, ty: &GenTy) -> ValueExpr {
+        match ty {
+            GenTy::Int(int_ty) => self.int_const(*int_ty),
+            GenTy::Bool => const_bool(self.gen.one_in(2)),
+            GenTy::Float(float) => self.float_const(*float),
+            _ => self.gen_compound(ty, true, |ty| self.gen_const(ty)),
+        }
+    }
+
+    /// A value of the non-scalar type `ty`, using `inner` for the values it consists of.
+    fn gen_compound(
+        &self,
+        ty: &GenTy,
+        init: bool,
+        inner: impl Fn(&GenTy) -> ValueExpr,
+    ) -> ValueExpr {
+        match ty {
+            GenTy::Tuple(fields) => ValueExpr::Tuple(fields.iter().map(&inner).collect(), ty.ty()),
+            GenTy::Array(elem, count) =>
+                ValueExpr::Tuple((0..*count).map(|_| inner(elem)).collect(), ty.ty()),
+            GenTy::Union(fields) => {
+                let field_idx = self.gen.below(fields.len());
+                ValueExpr::Union {
+                    field: Int::from(field_idx),
+                    expr: GcCow::new(inner(&GenTy::Int(fields[field_idx]))),
+                    union_ty: ty.ty(),
+                }
+            }
+            GenTy::TaggedEnum(_) | GenTy::NicheEnum(_) => {
+                let payloads = ty.payloads();
+                let discriminant = self.gen.below(payloads.len());
+                let mut fields = Vec::new();
+                if let Some((field_idx, payload)) = &payloads[discriminant] {
+                    // The fields before the payload hold the niche.
+                    fields.extend((0..*field_idx).map(|_| const_bool(self.gen.one_in(2))));
+                    fields.push(inner(payload));
+                }
+                let data =
+                    ValueExpr::Tuple(fields.into_iter().collect(), ty.variant_data()[discriminant]);
+                variant(discriminant, data, ty.ty())
+            }
+            GenTy::Ptr(pointee) => {
+                let target = self.pick_place_of(pointee, init).expect("pointees have a local");
+                addr_of(target, raw_void_ptr_ty())
+            }
+            GenTy::Int(_) | GenTy::Bool | GenTy::Float(_) => panic!("not a compound type"),
+        }
+    }
+
+    fn gen_int(&self, int_ty: IntType, depth: u32) -> ValueExpr {
+        if depth == 0 {
+            return self.int_const(int_ty);
+        }
+        let ty = GenTy::Int(int_ty);
+        let operand = || self.gen_value(&ty, depth - 1);
+        let other_int = || self.gen_value(&GenTy::Int(self.gen.gen_int_ty()), depth - 1);
+
+        let mut kinds = vec![
+            IntExpr::Const,
+            IntExpr::BinOp,
+            IntExpr::BinOp,
+            IntExpr::Shift,
+            IntExpr::UnOp,
+            IntExpr::Cast,
+            IntExpr::FromBool,
+            IntExpr::FromFloat,
+        ];
+        // Signed division can overflow.
+        if int_ty.signed == Signedness::Unsigned {
+            kinds.push(IntExpr::Div);
+        }
+        if int_ty == IntType::I8 {
+            kinds.push(IntExpr::Cmp);
+        }
+        let enums: Vec<PlaceExpr> = self
+            .places()
+            .into_iter()
+            .filter(|(_, place_ty)| place_ty.is_enum())
+            .map(|(place, _)| place)
+            .collect();
+        if Type::Int(int_ty) == <u8>::get_type() && !enums.is_empty() {
+            kinds.push(IntExpr::Discriminant);
+        }
+
+        match *self.gen.pick(&kinds) {
+            IntExpr::Const => self.int_const(int_ty),
+            IntExpr::BinOp => {
+                let op = self.gen.pick::<BinOpFn>(&[add, sub, mul, bit_and, bit_or, bit_xor]);
+                op(operand(), operand())
+            }
+            // The shift amount is taken modulo the bit width, so any integer works.
+            IntExpr::Shift => self.gen.pick::<BinOpFn>(&[shl, shr])(operand(), other_int()),
+            IntExpr::UnOp => self.gen.pick::<UnOpFn>(&[neg, bit_not])(operand()),
+            IntExpr::Div => {
+                // Make the divisor odd, so that it is not zero.
+                let one = ValueExpr::Constant(Constant::Int(Int::ONE), Type::Int(int_ty));
+                self.gen.pick::<BinOpFn>(&[div, rem])(operand(), bit_or(operand(), one))
+            }
+            IntExpr::Cast => cast(CastOp::IntToInt(int_ty), other_int()),
+            IntExpr::FromBool =>
+                cast(
+                    CastOp::IntToInt(int_ty),
+                    bool_to_int::<u8>(self.gen_value(&GenTy::Bool, depth - 1)),
+                ),
+            // Like `as`, this saturates.
+            IntExpr::FromFloat => {
+                let float = *self.gen.pick(&[FloatType::F32, FloatType::F64]);
+                cast(CastOp::FloatToInt(int_ty), self.gen_value(&GenTy::Float(float), depth - 1))
+            }
+            IntExpr::Cmp => {
+                let compared = GenTy::Int(self.gen.gen_int_ty());
+                cmp(self.gen_value(&compared, depth - 1), self.gen_value(&compared, depth - 1))
+            }
+            IntExpr::Discriminant => get_discriminant(*self.gen.pick(&enums)),
+        }
+    }
+
+    fn int_const(&self, int_ty: IntType) -> ValueExpr {
+        let int = match self.gen.below(4) {
+            0 => int_ty.min(),
+            1 => int_ty.max(),
+            _ => Int::from(self.gen.below(8)),
+        };
+        ValueExpr::Constant(Constant::Int(int), Type::Int(int_ty))
+    }
+
+    fn gen_bool(&self, depth: u32) -> ValueExpr {
+        if depth == 0 {
+            return const_bool(self.gen.one_in(2));
+        }
+        let operand = || self.gen_value(&GenTy::Bool, depth - 1);
+        match self.gen.below(4) {
+            0 => const_bool(self.gen.one_in(2)),
+            1 => not(operand()),
+            2 => self.gen.pick::<BinOpFn>(&[bool_and, bool_or, bool_xor])(operand(), operand()),
+            _ => {
+                let ty = self.gen.gen_scalar_ty();
+                let op = self.gen.pick::<BinOpFn>(&[eq, ne, lt, le, gt, ge]);
+                op(self.gen_value(&ty, depth - 1), self.gen_value(&ty, depth - 1))
+            }
+        }
+    }
+
+    fn gen_float(&self, float: FloatType, depth: u32) -> ValueExpr {
+        if depth == 0 {
+            return self.float_const(float);
+        }
+        let ty = GenTy::Float(float);
+        let operand = || self.gen_value(&ty, depth - 1);
+        match self.gen.below(5) {
+            0 => self.float_const(float),
+            1 => {
+                let op = self
+                    .gen
+                    .pick::<BinOpFn>(&[float_add, float_sub, float_mul, float_div, float_rem]);
+                op(operand(), operand())
+            }
+            2 => float_neg(operand()),
+            3 => {
+                let int = GenTy::Int(self.gen.gen_int_ty());
+                cast(CastOp::IntToFloat(float), self.gen_value(&int, depth - 1))
+            }
+            _ => {
+                let other = GenTy::Float(*self.gen.pick(&[FloatType::F32, FloatType::F64]));
+                cast(CastOp::FloatToFloat(float), self.gen_value(&other, depth - 1))
+            }
+        }
+    }
+
+    fn float_const(&self, float: FloatType) -> ValueExpr {
+        // NaN results have a non-deterministic sign and payload, but they all print the same.
+        let value = *self.gen.pick(&[0.0, -0.0, 1.5, -2.25, 1e10, f64::INFINITY, f64::NAN]);
+        match float {
+            FloatType::F32 => const_f32(value as f32),
+            FloatType::F64 => const_f64(value),
+        }
+    }
+}
+
+fn cast(op: CastOp, v: ValueExpr) -> ValueExpr {
+    ValueExpr::UnOp { operator: UnOp::Cast(op), operand: GcCow::new(v) }
+}
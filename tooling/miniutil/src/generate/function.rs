@@ -0,0 +1,315 @@
+use super::*;
+
+#[derive(Clone)]
+pub(super) struct Signature {
+    pub(super) params: Vec<GenTy>,
+    pub(super) ret: GenTy,
+}
+
+pub(super) struct Callee {
+    pub(super) name: FnName,
+    pub(super) sig: Signature,
+}
+
+pub(super) enum FnKind {
+    /// The start function, which is the only one that prints and spawns threads.
+    Start,
+    Callee(Signature),
+    /// A function run by a spawned thread, taking a (null) data pointer.
+    Thread,
+}
+
+/// The kinds of statements `gen_statement` generates.
+#[derive(Clone, Copy)]
+enum Stmt {
+    Assign,
+    Print,
+    If,
+    Loop,
+    Match,
+    Call,
+    Spawn,
+    Assume,
+    Heap,
+    Atomic,
+    Fence,
+    Lock,
+    Reborrow,
+}
+
+/// Generates the body of a single function.
+pub(super) struct FnGen<'a> {
+    pub(super) gen: &'a Gen<'a>,
+    callees: &'a [Callee],
+    threads: &'a [FnName],
+    print: bool,
+    /// These locals are initialized at the start of the function, and stay live until it returns.
+    /// All places read and written by the function, and all places that pointers point to,
+    /// are inside of them.
+    pub(super) locals: Vec<(PlaceExpr, GenTy)>,
+}
+
+impl<'a> FnGen<'a> {
+    pub(super) fn generate(
+        gen: &'a Gen<'a>,
+        p: &mut ProgramBuilder,
+        kind: FnKind,
+        callees: &'a [Callee],
+        threads: &'a [FnName],
+    ) -> FnName {
+        let config = gen.config;
+        let mut f = p.declare_function();
+        let mut locals = Vec::new();
+        match &kind {
+            FnKind::Start => {}
+            FnKind::Callee(sig) =>
+                for param in &sig.params {
+                    locals.push((f.declare_arg_with_ty(param.ty()), param.clone()));
+                },
+            FnKind::Thread => {
+                f.declare_arg::<*const ()>();
+            }
+        }
+        // The arguments are initialized by the caller, everything else by us.
+        let initialized = locals.len();
+        if let FnKind::Callee(sig) = &kind {
+            locals.push((f.declare_ret_with_ty(sig.ret.ty()), sig.ret.clone()));
+        }
+        for _ in 0..gen.up_to(config.max_locals) {
+            let ty = gen.gen_ty(config.max_type_depth, true);
+            declare_local(&mut f, &mut locals, ty);
+        }
+
+        let fn_gen = FnGen { gen, callees, threads, print: matches!(kind, FnKind::Start), locals };
+        for (local, ty) in &fn_gen.locals[initialized..] {
+            f.assign(*local, fn_gen.gen_const(ty));
+        }
+        fn_gen.gen_block(&mut f, config.max_block_depth);
+        match kind {
+            FnKind::Start => f.exit(),
+            FnKind::Callee(_) | FnKind::Thread => f.return_(),
+        }
+        p.finish_function(f)
+    }
+
+    fn gen_block(&self, f: &mut FunctionBuilder, depth: u32) {
+        for _ in 0..self.gen.up_to(self.gen.config.max_statements) {
+            self.gen_statement(f, depth);
+        }
+    }
+
+    /// Generate a statement, which may contain blocks nested up to `depth` deep.
+    fn gen_statement(&self, f: &mut FunctionBuilder, depth: u32) {
+        let config = self.gen.config;
+        let expr_depth = config.max_expr_depth;
+        let mut kinds = vec![Stmt::Assign, Stmt::Assign, Stmt::Assign];
+        if self.print {
+            kinds.extend([Stmt::Print, Stmt::Print]);
+        }
+        if depth > 0 {
+            kinds.extend([Stmt::If, Stmt::Loop]);
+            if self.places().iter().any(|(_, ty)| ty.is_enum()) {
+                kinds.push(Stmt::Match);
+            }
+        }
+        if !self.callees.is_empty() {
+            kinds.push(Stmt::Call);
+        }
+        if !self.threads.is_empty() {
+            kinds.push(Stmt::Spawn);
+        }
+        if config.intrinsics {
+            kinds.extend([Stmt::Assume, Stmt::Heap, Stmt::Atomic, Stmt::Fence, Stmt::Lock]);
+        }
+        if config.pointers {
+            kinds.push(Stmt::Reborrow);
+        }
+
+        match *self.gen.pick(&kinds) {
+            Stmt::Assign => {
+                let (place, ty) = self.pick_any_place();
+                f.assign(place, self.gen_value(&ty, expr_depth));
+            }
+            Stmt::Print => {
+                let ty = self.gen.gen_scalar_ty();
+                f.print(self.gen_value(&ty, expr_depth));
+            }
+            Stmt::If => {
+                f.if_(
+                    self.gen_value(&GenTy::Bool, expr_depth),
+                    |f| self.gen_block(f, depth - 1),
+                    |f| self.gen_block(f, depth - 1),
+                );
+            }
+            Stmt::Loop => {
+                // The loop runs a fixed number of times, so the program terminates.
+                let counter = self.temp(f, <u32>::get_type());
+                f.assign(counter, const_int(self.gen.up_to(3)));
+                f.while_(gt(load(counter), const_int(0_u32)), |f| {
+                    f.assign(counter, sub(load(counter), const_int(1_u32)));
+                    self.gen_block(f, depth - 1);
+                });
+            }
+            Stmt::Match => self.gen_match(f, depth),
+            Stmt::Call => {
+                let callee = self.gen.pick(self.callees);
+                let args: Vec<ArgumentExpr> = callee
+                    .sig
+                    .params
+                    .iter()
+                    .map(|param| by_value(self.gen_value(param, expr_depth)))
+                    .collect();
+                let ret = self.temp(f, callee.sig.ret.ty());
+                f.call(ret, callee.name, &args);
+                if let Some(dest) = self.pick_place(&callee.sig.ret) {
+                    f.assign(dest, load(ret));
+                }
+            }
+            Stmt::Spawn => {
+                let thread = *self.gen.pick(self.threads);
+                let id = self.temp(f, <u32>::get_type());
+                f.spawn(thread, null(), id);
+                if depth > 0 {
+                    self.gen_block(f, depth - 1);
+                }
+                // The thread must be done before the program exits, or its heap memory leaks.
+                f.join(load(id));
+            }
+            Stmt::Assume => {
+                // `b || !b` always holds.
+                let b = self.gen_value(&GenTy::Bool, expr_depth);
+                f.assume(bool_or(b, not(b)));
+            }
+            Stmt::Heap => {
+                // Move a value through a heap allocation.
+                let (place, ty) = self.pick_any_place();
+                let layout = ty.ty();
+                let heap_size = const_int_typed::<usize>(Int::from(size_of(layout)));
+                let heap_align = const_int_typed::<usize>(Int::from(align_of(layout)));
+                let ptr = self.temp(f, raw_void_ptr_ty());
+                f.allocate(heap_size, heap_align, ptr);
+                let heap = deref(load(ptr), layout);
+                f.assign(heap, self.gen_value(&ty, expr_depth));
+                f.assign(place, load(heap));
+                f.deallocate(load(ptr), heap_size, heap_align);
+            }
+            Stmt::Atomic => {
+                // Integers are at most 8 bytes large and aligned to their size,
+                // which is what atomic accesses need.
+                let ints: Vec<(PlaceExpr, GenTy)> = self
+                    .places()
+                    .into_iter()
+                    .filter(|(_, ty)| matches!(ty, GenTy::Int(_)))
+                    .collect();
+                if ints.is_empty() {
+                    return;
+                }
+                let (place, ty) = self.gen.pick(&ints).clone();
+                let ptr = addr_of(place, raw_void_ptr_ty());
+                if self.gen.one_in(2) {
+                    f.atomic_store(ptr, self.gen_value(&ty, expr_depth));
+                } else {
+                    let dest = self.pick_place(&ty).unwrap();
+                    f.atomic_load(dest, ptr);
+                }
+            }
+            Stmt::Fence => {
+                let orderings = [
+                    MemoryOrdering::Acquire,
+                    MemoryOrdering::Release,
+                    MemoryOrdering::AcqRel,
+                    MemoryOrdering::SeqCst,
+                ];
+                f.fence(*self.gen.pick(&orderings));
+            }
+            Stmt::Lock => {
+                let lock = self.temp(f, <u32>::get_type());
+                f.lock_create(lock);
+                f.lock_acquire(load(lock));
+                if depth > 0 {
+                    self.gen_block(f, depth - 1);
+                }
+                f.lock_release(load(lock));
+            }
+            Stmt::Reborrow => {
+                // Write through a fresh `&mut`. Since it is not used afterwards,
+                // this is fine for the aliasing models.
+                let (place, ty) = self.pick_any_place();
+                let layout = ty.ty();
+                let ref_ty = ref_mut_ty(PointeeInfo {
+                    size: layout.size::<DefaultTarget>(),
+                    align: layout.align::<DefaultTarget>(),
+                    inhabited: true,
+                    freeze: true,
+                    unpin: true,
+                    unsafe_cells: List::new(),
+                    byte_slice: false,
+                });
+                let reference = self.temp(f, ref_ty);
+                f.assign(reference, addr_of(place, ref_ty));
+                f.validate(reference, false);
+                f.assign(deref(load(reference), layout), self.gen_value(&ty, expr_depth));
+            }
+        }
+    }
+
+    /// Switch on the discriminant of an enum, and access the data of the variant in each branch.
+    fn gen_match(&self, f: &mut FunctionBuilder, depth: u32) {
+        let enums: Vec<(PlaceExpr, GenTy)> =
+            self.places().into_iter().filter(|(_, ty)| ty.is_enum()).collect();
+        let (place, ty) = self.gen.pick(&enums).clone();
+        let payloads = &ty.payloads();
+        let branches: Vec<_> = (0..payloads.len())
+            .map(|discriminant| {
+                move |f: &mut FunctionBuilder| {
+                    self.gen_match_arm(f, downcast(place, discriminant), &payloads[discriminant]);
+                    self.gen_block(f, depth - 1);
+                }
+            })
+            .collect();
+        let cases: Vec<(u8, &dyn Fn(&mut FunctionBuilder))> = branches
+            .iter()
+            .enumerate()
+            .map(|(discriminant, branch)| {
+                (discriminant as u8, branch as &dyn Fn(&mut FunctionBuilder))
+            })
+            .collect();
+        // Every valid value of the enum has one of the discriminants.
+        f.switch_int(get_discriminant(place), &cases, |f| f.unreachable());
+    }
+
+    /// Read or write the data of the variant `variant`, if it has any.
+    fn gen_match_arm(
+        &self,
+        f: &mut FunctionBuilder,
+        variant: PlaceExpr,
+        payload: &Option<(u32, GenTy)>,
+    ) {
+        let Some((field_idx, payload_ty)) = payload else { return };
+        let data = field(variant, *field_idx);
+        match self.pick_place(payload_ty) {
+            Some(dest) if self.gen.one_in(2) => f.assign(dest, load(data)),
+            _ => f.assign(data, self.gen_value(payload_ty, self.gen.config.max_expr_depth)),
+        }
+    }
+
+    /// A fresh local for an intermediate result, which is not used for anything else.
+    fn temp(&self, f: &mut FunctionBuilder, ty: Type) -> PlaceExpr {
+        let local = f.declare_local_with_ty(ty);
+        f.storage_live(local);
+        local
+    }
+}
+
+/// Declare a local of type `ty`, and before that, the locals its pointers can point to
+/// (unless there are such locals already).
+fn declare_local(f: &mut FunctionBuilder, locals: &mut Vec<(PlaceExpr, GenTy)>, ty: GenTy) {
+    for pointee in ty.pointees() {
+        if !locals.iter().any(|(_, local_ty)| local_ty.has_place_of(&pointee)) {
+            declare_local(f, locals, pointee);
+        }
+    }
+    let local = f.declare_local_with_ty(ty.ty());
+    f.storage_live(local);
+    locals.push((local, ty));
+}
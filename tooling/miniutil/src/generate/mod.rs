@@ -0,0 +1,140 @@
+//! Random well-formed programs, to fuzz the specification.
+//!
+//! Executing a well-formed program must never panic; if it does, that is a bug in the
+//! specification. `generate_program` builds such programs from a seed, so that they can be run
+//! in bulk to look for those panics.
+//!
+//! The generated programs are also meant to be free of UB under every memory model:
+//! - all locals are initialized at the start of their function and stay live until it returns,
+//! - pointers only ever point to locals of the function that created them, and are never passed
+//!   to other functions,
+//! - integer arithmetic either wraps around or cannot fail, and indices are always in bounds,
+//! - spawned threads only work on their own locals, and are joined before the program exits.
+//!
+//! Only the start function prints, so the output does not depend on the schedule. Hence all
+//! memory models must agree on the output of a generated program, which makes them useful for
+//! comparing the memory models.
+//!
+//! This module needs the `generate` feature.
+
+use std::cell::Cell;
+
+use crate::build::*;
+use crate::schedule::SplitMix64;
+use crate::*;
+
+mod expr;
+mod function;
+mod ty;
+
+use function::*;
+use ty::*;
+
+/// What `generate_program` may put into a program.
+#[derive(Clone, Debug)]
+pub struct GenConfig {
+    /// How deeply types may be nested: 0 only allows scalars, 1 also tuples of scalars, etc.
+    pub max_type_depth: u32,
+    /// How deeply expressions may be nested.
+    pub max_expr_depth: u32,
+    /// How deeply `if`, loops and `match` may be nested inside a function.
+    pub max_block_depth: u32,
+    /// The maximal number of statements in each block.
+    pub max_statements: u32,
+    /// The maximal number of locals of each function, not counting temporaries.
+    pub max_locals: u32,
+    /// The maximal number of functions besides the start function and those run by threads.
+    pub max_functions: u32,
+    /// Use raw pointers to locals, and `&mut` references that get retagged.
+    pub pointers: bool,
+    /// Use unions of integers.
+    pub unions: bool,
+    /// Use enums, both with a tag and with the discriminant in the niche of a `bool`.
+    pub enums: bool,
+    /// Spawn threads.
+    pub threads: bool,
+    /// Use `assume`, heap allocation, atomics, fences and locks. Printing is always used.
+    pub intrinsics: bool,
+}
+
+impl Default for GenConfig {
+    fn default() -> Self {
+        GenConfig {
+            max_type_depth: 2,
+            max_expr_depth: 3,
+            max_block_depth: 2,
+            max_statements: 6,
+            max_locals: 5,
+            max_functions: 3,
+            pointers: true,
+            unions: true,
+            enums: true,
+            threads: true,
+            intrinsics: true,
+        }
+    }
+}
+
+/// Generate a random well-formed program.
+/// The same seed and config always give the same program.
+pub fn generate_program(seed: u64, config: &GenConfig) -> Program {
+    let gen = Gen { config, rng: Cell::new(SplitMix64::new(seed)) };
+    gen.program()
+}
+
+struct Gen<'a> {
+    config: &'a GenConfig,
+    /// This is a `Cell` since the closures taken by `FunctionBuilder::if_` and friends are `Fn`.
+    rng: Cell<SplitMix64>,
+}
+
+impl Gen<'_> {
+    /// A random number in `0..n`.
+    fn below(&self, n: usize) -> usize {
+        let mut rng = self.rng.get();
+        let x = rng.next_u64();
+        self.rng.set(rng);
+        (x % n as u64) as usize
+    }
+
+    /// A random number in `1..=max`, or 1 if `max` is 0.
+    fn up_to(&self, max: u32) -> u32 {
+        1 + self.below(max.max(1) as usize) as u32
+    }
+
+    fn one_in(&self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    fn pick<'v, T>(&self, items: &'v [T]) -> &'v T {
+        &items[self.below(items.len())]
+    }
+
+    fn program(&self) -> Program {
+        let config = self.config;
+        let mut p = ProgramBuilder::new();
+
+        // Functions only call functions generated before them, so there is no recursion.
+        let mut callees = Vec::new();
+        for _ in 0..self.below(config.max_functions as usize + 1) {
+            // Arguments and return values have no pointers, since those must not leave their function.
+            let params = (0..self.below(3)).map(|_| self.gen_ty(config.max_type_depth, false));
+            let sig = Signature {
+                params: params.collect(),
+                ret: self.gen_ty(config.max_type_depth, false),
+            };
+            let name = FnGen::generate(self, &mut p, FnKind::Callee(sig.clone()), &callees, &[]);
+            callees.push(Callee { name, sig });
+        }
+
+        let mut threads = Vec::new();
+        if config.threads {
+            for _ in 0..self.up_to(2) {
+                threads.push(FnGen::generate(self, &mut p, FnKind::Thread, &callees, &[]));
+            }
+        }
+
+        let start = FnGen::generate(self, &mut p, FnKind::Start, &callees, &threads);
+        p.finish_program(start)
+    }
+}
@@ -0,0 +1,247 @@
+use super::*;
+
+/// The type of a place in a generated program.
+/// Unlike `Type`, this knows what pointers point to, so that the generator can make them
+/// point to a place of the right type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum GenTy {
+    Int(IntType),
+    Bool,
+    Float(FloatType),
+    Tuple(Vec<GenTy>),
+    Array(Box<GenTy>, u32),
+    /// The fields are integers of the same size, so all of them are valid whichever was written.
+    Union(Vec<IntType>),
+    /// Every variant has data of the given type, after a `u8` tag.
+    TaggedEnum(Vec<GenTy>),
+    /// Like `Option<(bool, T)>`: `None` is stored as the invalid `bool` value 2.
+    NicheEnum(Box<GenTy>),
+    /// A raw pointer.
+    Ptr(Box<GenTy>),
+}
+
+fn int_types() -> Vec<IntType> {
+    let types = [
+        <u8>::get_type(),
+        <i8>::get_type(),
+        <u16>::get_type(),
+        <i16>::get_type(),
+        <u32>::get_type(),
+        <i32>::get_type(),
+        <u64>::get_type(),
+        <i64>::get_type(),
+    ];
+    types
+        .into_iter()
+        .map(|ty| {
+            let Type::Int(int_ty) = ty else { unreachable!() };
+            int_ty
+        })
+        .collect()
+}
+
+pub(super) fn size_of(ty: Type) -> u32 {
+    let size = ty.size::<DefaultTarget>().expect_sized("generated types are sized");
+    u32::try_from(size.bytes().try_to_usize().unwrap()).unwrap()
+}
+
+pub(super) fn align_of(ty: Type) -> u32 {
+    u32::try_from(ty.align::<DefaultTarget>().bytes().try_to_usize().unwrap()).unwrap()
+}
+
+/// Lay out the fields one after the other, like `repr(C)`, leaving the first `start` bytes free.
+fn tuple_layout(start: u32, fields: &[Type]) -> Type {
+    let mut end = start;
+    let mut max_align = 1;
+    let mut placed = Vec::new();
+    for &field in fields {
+        let field_align = align_of(field);
+        let offset = end.next_multiple_of(field_align);
+        placed.push((size(offset), field));
+        end = offset + size_of(field);
+        max_align = max_align.max(field_align);
+    }
+    tuple_ty(&placed, size(end.next_multiple_of(max_align)), align(max_align))
+}
+
+impl GenTy {
+    pub(super) fn ty(&self) -> Type {
+        match self {
+            GenTy::Int(int_ty) => Type::Int(*int_ty),
+            GenTy::Bool => bool_ty(),
+            GenTy::Float(float) => float_ty(*float),
+            GenTy::Tuple(fields) => {
+                let fields: Vec<Type> = fields.iter().map(GenTy::ty).collect();
+                tuple_layout(0, &fields)
+            }
+            GenTy::Array(elem, count) => array_ty(elem.ty(), *count),
+            GenTy::Union(fields) => {
+                let union_size = fields[0].size;
+                let fields: Vec<(Offset, Type)> =
+                    fields.iter().map(|int_ty| (Size::ZERO, Type::Int(*int_ty))).collect();
+                union_ty(&fields, union_size, align(union_size.bytes()))
+            }
+            GenTy::TaggedEnum(_) | GenTy::NicheEnum(_) => {
+                let data = self.variant_data();
+                let variants: Vec<(u8, Variant)> = data
+                    .iter()
+                    .enumerate()
+                    .map(|(discriminant, &data)| {
+                        (discriminant as u8, enum_variant(data, &self.tagger(discriminant)))
+                    })
+                    .collect();
+                enum_ty::<u8>(
+                    &variants,
+                    self.discriminator(),
+                    size(size_of(data[0])),
+                    align(align_of(data[0])),
+                )
+            }
+            GenTy::Ptr(_) => raw_void_ptr_ty(),
+        }
+    }
+
+    pub(super) fn is_enum(&self) -> bool {
+        matches!(self, GenTy::TaggedEnum(_) | GenTy::NicheEnum(_))
+    }
+
+    /// The type of the data of each variant of an enum. They all have the same size.
+    pub(super) fn variant_data(&self) -> Vec<Type> {
+        let data: Vec<Type> = match self {
+            GenTy::TaggedEnum(variants) =>
+                variants.iter().map(|variant| tuple_layout(1, &[variant.ty()])).collect(),
+            GenTy::NicheEnum(payload) =>
+                vec![tuple_layout(0, &[bool_ty(), payload.ty()]), tuple_layout(0, &[])],
+            _ => panic!("not an enum"),
+        };
+        let enum_align = data.iter().map(|&ty| align_of(ty)).max().unwrap();
+        let enum_size =
+            data.iter().map(|&ty| size_of(ty)).max().unwrap().next_multiple_of(enum_align);
+        data.into_iter()
+            .map(|ty| {
+                let Type::Tuple { fields, .. } = ty else { unreachable!() };
+                Type::Tuple { fields, size: size(enum_size), align: align(enum_align) }
+            })
+            .collect()
+    }
+
+    /// For each variant of an enum, which field of its data has which type,
+    /// not counting the `bool` that holds the niche.
+    pub(super) fn payloads(&self) -> Vec<Option<(u32, GenTy)>> {
+        match self {
+            GenTy::TaggedEnum(variants) =>
+                variants.iter().map(|variant| Some((0, variant.clone()))).collect(),
+            GenTy::NicheEnum(payload) => vec![Some((1, (**payload).clone())), None],
+            _ => panic!("not an enum"),
+        }
+    }
+
+    fn tagger(&self, discriminant: usize) -> Vec<(Offset, (IntType, Int))> {
+        let Type::Int(u8_ty) = <u8>::get_type() else { unreachable!() };
+        match self {
+            GenTy::TaggedEnum(_) => vec![(Size::ZERO, (u8_ty, Int::from(discriminant)))],
+            // `Some` is told apart by the `bool` in its data.
+            GenTy::NicheEnum(_) if discriminant == 0 => Vec::new(),
+            _ => vec![(Size::ZERO, (u8_ty, Int::from(2)))],
+        }
+    }
+
+    fn discriminator(&self) -> Discriminator {
+        let children: Vec<((u8, u8), Discriminator)> = match self {
+            GenTy::TaggedEnum(variants) =>
+                (0..variants.len() as u8)
+                    .map(|discriminant| {
+                        ((discriminant, discriminant + 1), discriminator_known(discriminant))
+                    })
+                    .collect(),
+            // `false` and `true` are `Some`, 2 is `None`.
+            _ => vec![((0, 2), discriminator_known(0)), ((2, 3), discriminator_known(1))],
+        };
+        discriminator_branch::<u8>(Size::ZERO, discriminator_invalid(), &children)
+    }
+
+    /// The types that pointers in a value of this type point to.
+    pub(super) fn pointees(&self) -> Vec<GenTy> {
+        match self {
+            GenTy::Ptr(pointee) => vec![(**pointee).clone()],
+            GenTy::Tuple(fields) | GenTy::TaggedEnum(fields) =>
+                fields.iter().flat_map(GenTy::pointees).collect(),
+            GenTy::Array(elem, _) | GenTy::NicheEnum(elem) => elem.pointees(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether a place of this type contains a place of type `ty`, without following pointers.
+    /// The data of enums does not count, since it can only be accessed for the right variant.
+    pub(super) fn has_place_of(&self, ty: &GenTy) -> bool {
+        self == ty
+            || match self {
+                GenTy::Tuple(fields) => fields.iter().any(|field| field.has_place_of(ty)),
+                GenTy::Array(elem, _) => elem.has_place_of(ty),
+                GenTy::Union(fields) => fields.iter().any(|&int_ty| GenTy::Int(int_ty) == *ty),
+                _ => false,
+            }
+    }
+}
+
+/// The kinds of non-scalar types.
+#[derive(Clone, Copy)]
+enum Compound {
+    Tuple,
+    Array,
+    Union,
+    TaggedEnum,
+    NicheEnum,
+    Ptr,
+}
+
+impl Gen<'_> {
+    /// A random type, nested at most `depth` deep. With `ptr: false`, it does not contain pointers.
+    pub(super) fn gen_ty(&self, depth: u32, ptr: bool) -> GenTy {
+        let config = self.config;
+        // Mostly scalars, so that the types do not get too large.
+        if depth == 0 || self.one_in(2) {
+            return self.gen_scalar_ty();
+        }
+
+        let mut kinds = vec![Compound::Tuple, Compound::Array];
+        if config.unions {
+            kinds.push(Compound::Union);
+        }
+        if config.enums {
+            kinds.extend([Compound::TaggedEnum, Compound::NicheEnum]);
+        }
+        if config.pointers && ptr {
+            kinds.push(Compound::Ptr);
+        }
+        let inner = || self.gen_ty(depth - 1, ptr);
+        match *self.pick(&kinds) {
+            Compound::Tuple => GenTy::Tuple((0..self.up_to(3)).map(|_| inner()).collect()),
+            Compound::Array => GenTy::Array(Box::new(inner()), self.up_to(4)),
+            Compound::Union => {
+                let union_size = *self.pick(&[1_u32, 2, 4, 8]);
+                let candidates: Vec<IntType> = int_types()
+                    .into_iter()
+                    .filter(|int_ty| int_ty.size.bytes() == Int::from(union_size))
+                    .collect();
+                GenTy::Union((0..self.up_to(3) + 1).map(|_| *self.pick(&candidates)).collect())
+            }
+            Compound::TaggedEnum =>
+                GenTy::TaggedEnum((0..self.up_to(3)).map(|_| inner()).collect()),
+            Compound::NicheEnum => GenTy::NicheEnum(Box::new(inner())),
+            Compound::Ptr => GenTy::Ptr(Box::new(inner())),
+        }
+    }
+
+    pub(super) fn gen_scalar_ty(&self) -> GenTy {
+        match self.below(4) {
+            0 | 1 => GenTy::Int(*self.pick(&int_types())),
+            2 => GenTy::Bool,
+            _ => GenTy::Float(*self.pick(&[FloatType::F32, FloatType::F64])),
+        }
+    }
+
+    pub(super) fn gen_int_ty(&self) -> IntType {
+        *self.pick(&int_types())
+    }
+}
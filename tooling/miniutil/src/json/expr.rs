@@ -0,0 +1,326 @@
+use super::*;
+
+impl Json for ValueExpr {
+    fn to_json(self) -> JsonValue {
+        match self {
+            ValueExpr::Constant(c, ty) => variant("Constant", (c, ty).to_json()),
+            ValueExpr::Tuple(exprs, ty) => variant("Tuple", (exprs, ty).to_json()),
+            ValueExpr::Union { field, expr, union_ty } =>
+                variant(
+                    "Union",
+                    json!({
+                        "field": field.to_json(),
+                        "expr": expr.extract().to_json(),
+                        "union_ty": union_ty.to_json(),
+                    }),
+                ),
+            ValueExpr::Variant { discriminant, data, enum_ty } =>
+                variant(
+                    "Variant",
+                    json!({
+                        "discriminant": discriminant.to_json(),
+                        "data": data.extract().to_json(),
+                        "enum_ty": enum_ty.to_json(),
+                    }),
+                ),
+            ValueExpr::GetDiscriminant { place } =>
+                variant("GetDiscriminant", json!({ "place": place.extract().to_json() })),
+            ValueExpr::Load { source } =>
+                variant("Load", json!({ "source": source.extract().to_json() })),
+            ValueExpr::AddrOf { target, ptr_ty } =>
+                variant(
+                    "AddrOf",
+                    json!({ "target": target.extract().to_json(), "ptr_ty": ptr_ty.to_json() }),
+                ),
+            ValueExpr::UnOp { operator, operand } =>
+                variant(
+                    "UnOp",
+                    json!({
+                        "operator": operator.to_json(),
+                        "operand": operand.extract().to_json(),
+                    }),
+                ),
+            ValueExpr::BinOp { operator, left, right } =>
+                variant(
+                    "BinOp",
+                    json!({
+                        "operator": operator.to_json(),
+                        "left": left.extract().to_json(),
+                        "right": right.extract().to_json(),
+                    }),
+                ),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Constant" => {
+                let (c, ty) = <(Constant, Type)>::from_json(data)?;
+                ValueExpr::Constant(c, ty)
+            }
+            "Tuple" => {
+                let (exprs, ty) = <(List<ValueExpr>, Type)>::from_json(data)?;
+                ValueExpr::Tuple(exprs, ty)
+            }
+            "Union" =>
+                ValueExpr::Union {
+                    field: field(data, "field")?,
+                    expr: GcCow::new(field(data, "expr")?),
+                    union_ty: field(data, "union_ty")?,
+                },
+            "Variant" =>
+                ValueExpr::Variant {
+                    discriminant: field(data, "discriminant")?,
+                    data: GcCow::new(field(data, "data")?),
+                    enum_ty: field(data, "enum_ty")?,
+                },
+            "GetDiscriminant" =>
+                ValueExpr::GetDiscriminant { place: GcCow::new(field(data, "place")?) },
+            "Load" => ValueExpr::Load { source: GcCow::new(field(data, "source")?) },
+            "AddrOf" =>
+                ValueExpr::AddrOf {
+                    target: GcCow::new(field(data, "target")?),
+                    ptr_ty: field(data, "ptr_ty")?,
+                },
+            "UnOp" =>
+                ValueExpr::UnOp {
+                    operator: field(data, "operator")?,
+                    operand: GcCow::new(field(data, "operand")?),
+                },
+            "BinOp" =>
+                ValueExpr::BinOp {
+                    operator: field(data, "operator")?,
+                    left: GcCow::new(field(data, "left")?),
+                    right: GcCow::new(field(data, "right")?),
+                },
+            _ => return Err(unknown_variant("ValueExpr", name)),
+        })
+    }
+}
+
+impl Json for Constant {
+    fn to_json(self) -> JsonValue {
+        match self {
+            Constant::Int(int) => variant("Int", int.to_json()),
+            Constant::Bool(b) => variant("Bool", b.to_json()),
+            Constant::Float(float) => variant("Float", float.to_json()),
+            Constant::GlobalPointer(relocation) => variant("GlobalPointer", relocation.to_json()),
+            Constant::FnPointer(fn_name) => variant("FnPointer", fn_name.to_json()),
+            Constant::VTablePointer(vtable_name) => variant("VTablePointer", vtable_name.to_json()),
+            Constant::PointerWithoutProvenance(addr) =>
+                variant("PointerWithoutProvenance", addr.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Int" => Constant::Int(Int::from_json(data)?),
+            "Bool" => Constant::Bool(bool::from_json(data)?),
+            "Float" => Constant::Float(Float::from_json(data)?),
+            "GlobalPointer" => Constant::GlobalPointer(Relocation::from_json(data)?),
+            "FnPointer" => Constant::FnPointer(FnName::from_json(data)?),
+            "VTablePointer" => Constant::VTablePointer(VTableName::from_json(data)?),
+            "PointerWithoutProvenance" => Constant::PointerWithoutProvenance(Int::from_json(data)?),
+            _ => return Err(unknown_variant("Constant", name)),
+        })
+    }
+}
+
+// Floats are encoded as their bit pattern, so that NaN payloads are preserved.
+impl Json for Float {
+    fn to_json(self) -> JsonValue {
+        match self {
+            Float::F32(bits) => variant("F32", bits.to_json()),
+            Float::F64(bits) => variant("F64", bits.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "F32" => Float::F32(u32::from_json(data)?),
+            "F64" => Float::F64(u64::from_json(data)?),
+            _ => return Err(unknown_variant("Float", name)),
+        })
+    }
+}
+
+impl Json for UnOp {
+    fn to_json(self) -> JsonValue {
+        match self {
+            UnOp::Int(op) => variant("Int", op.to_json()),
+            UnOp::Float(op) => variant("Float", op.to_json()),
+            UnOp::Cast(op) => variant("Cast", op.to_json()),
+            UnOp::GetMetadata => JsonValue::from("GetMetadata"),
+            UnOp::GetThinPointer => JsonValue::from("GetThinPointer"),
+            UnOp::VTableMethodLookup(method) => variant("VTableMethodLookup", method.to_json()),
+            UnOp::VTableDropLookup => JsonValue::from("VTableDropLookup"),
+            UnOp::ComputeSize(ty) => variant("ComputeSize", ty.to_json()),
+            UnOp::ComputeAlign(ty) => variant("ComputeAlign", ty.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Int" => UnOp::Int(IntUnOp::from_json(data)?),
+            "Float" => UnOp::Float(FloatUnOp::from_json(data)?),
+            "Cast" => UnOp::Cast(CastOp::from_json(data)?),
+            "GetMetadata" => UnOp::GetMetadata,
+            "GetThinPointer" => UnOp::GetThinPointer,
+            "VTableMethodLookup" => UnOp::VTableMethodLookup(TraitMethodName::from_json(data)?),
+            "VTableDropLookup" => UnOp::VTableDropLookup,
+            "ComputeSize" => UnOp::ComputeSize(Type::from_json(data)?),
+            "ComputeAlign" => UnOp::ComputeAlign(Type::from_json(data)?),
+            _ => return Err(unknown_variant("UnOp", name)),
+        })
+    }
+}
+
+fieldless_enum_json!(IntUnOp { Neg, BitNot });
+fieldless_enum_json!(FloatUnOp { Neg });
+
+impl Json for CastOp {
+    fn to_json(self) -> JsonValue {
+        match self {
+            CastOp::IntToInt(int_ty) => variant("IntToInt", int_ty.to_json()),
+            CastOp::FloatToInt(int_ty) => variant("FloatToInt", int_ty.to_json()),
+            CastOp::IntToFloat(float_ty) => variant("IntToFloat", float_ty.to_json()),
+            CastOp::FloatToFloat(float_ty) => variant("FloatToFloat", float_ty.to_json()),
+            CastOp::Transmute(ty) => variant("Transmute", ty.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "IntToInt" => CastOp::IntToInt(IntType::from_json(data)?),
+            "FloatToInt" => CastOp::FloatToInt(IntType::from_json(data)?),
+            "IntToFloat" => CastOp::IntToFloat(FloatType::from_json(data)?),
+            "FloatToFloat" => CastOp::FloatToFloat(FloatType::from_json(data)?),
+            "Transmute" => CastOp::Transmute(Type::from_json(data)?),
+            _ => return Err(unknown_variant("CastOp", name)),
+        })
+    }
+}
+
+impl Json for BinOp {
+    fn to_json(self) -> JsonValue {
+        match self {
+            BinOp::Int(op) => variant("Int", op.to_json()),
+            BinOp::Float(op) => variant("Float", op.to_json()),
+            BinOp::IntWithOverflow(op) => variant("IntWithOverflow", op.to_json()),
+            BinOp::Rel(op) => variant("Rel", op.to_json()),
+            BinOp::PtrOffset { inbounds } =>
+                variant("PtrOffset", json!({ "inbounds": inbounds.to_json() })),
+            BinOp::PtrOffsetFrom { inbounds, nonneg } =>
+                variant(
+                    "PtrOffsetFrom",
+                    json!({ "inbounds": inbounds.to_json(), "nonneg": nonneg.to_json() }),
+                ),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Int" => BinOp::Int(IntBinOp::from_json(data)?),
+            "Float" => BinOp::Float(FloatBinOp::from_json(data)?),
+            "IntWithOverflow" => BinOp::IntWithOverflow(IntBinOpWithOverflow::from_json(data)?),
+            "Rel" => BinOp::Rel(RelOp::from_json(data)?),
+            "PtrOffset" => BinOp::PtrOffset { inbounds: field(data, "inbounds")? },
+            "PtrOffsetFrom" =>
+                BinOp::PtrOffsetFrom {
+                    inbounds: field(data, "inbounds")?,
+                    nonneg: field(data, "nonneg")?,
+                },
+            _ => return Err(unknown_variant("BinOp", name)),
+        })
+    }
+}
+
+fieldless_enum_json!(IntBinOp {
+    Add,
+    AddUnchecked,
+    Sub,
+    SubUnchecked,
+    Mul,
+    MulUnchecked,
+    Div,
+    DivExact,
+    Rem,
+    Shl,
+    ShlUnchecked,
+    Shr,
+    ShrUnchecked,
+    BitAnd,
+    BitOr,
+    BitXor,
+});
+fieldless_enum_json!(IntBinOpWithOverflow { Add, Sub, Mul });
+fieldless_enum_json!(RelOp { Lt, Gt, Le, Ge, Eq, Ne, Cmp });
+fieldless_enum_json!(FloatBinOp { Add, Sub, Mul, Div, Rem });
+
+impl Json for PlaceExpr {
+    fn to_json(self) -> JsonValue {
+        match self {
+            PlaceExpr::Local(local) => variant("Local", local.to_json()),
+            PlaceExpr::Deref { operand, ty } =>
+                variant(
+                    "Deref",
+                    json!({ "operand": operand.extract().to_json(), "ty": ty.to_json() }),
+                ),
+            PlaceExpr::Field { root, field } =>
+                variant(
+                    "Field",
+                    json!({ "root": root.extract().to_json(), "field": field.to_json() }),
+                ),
+            PlaceExpr::Index { root, index } =>
+                variant(
+                    "Index",
+                    json!({
+                        "root": root.extract().to_json(),
+                        "index": index.extract().to_json(),
+                    }),
+                ),
+            PlaceExpr::Downcast { root, discriminant } =>
+                variant(
+                    "Downcast",
+                    json!({
+                        "root": root.extract().to_json(),
+                        "discriminant": discriminant.to_json(),
+                    }),
+                ),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Local" => PlaceExpr::Local(LocalName::from_json(data)?),
+            "Deref" =>
+                PlaceExpr::Deref {
+                    operand: GcCow::new(field(data, "operand")?),
+                    ty: field(data, "ty")?,
+                },
+            "Field" =>
+                PlaceExpr::Field {
+                    root: GcCow::new(field(data, "root")?),
+                    field: field(data, "field")?,
+                },
+            "Index" =>
+                PlaceExpr::Index {
+                    root: GcCow::new(field(data, "root")?),
+                    index: GcCow::new(field(data, "index")?),
+                },
+            "Downcast" =>
+                PlaceExpr::Downcast {
+                    root: GcCow::new(field(data, "root")?),
+                    discriminant: field(data, "discriminant")?,
+                },
+            _ => return Err(unknown_variant("PlaceExpr", name)),
+        })
+    }
+}
@@ -0,0 +1,266 @@
+use super::*;
+
+impl Json for Function {
+    fn to_json(self) -> JsonValue {
+        json!({
+            "locals": self.locals.to_json(),
+            "args": self.args.to_json(),
+            "ret": self.ret.to_json(),
+            "calling_convention": self.calling_convention.to_json(),
+            "blocks": self.blocks.to_json(),
+            "start": self.start.to_json(),
+        })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(Function {
+            locals: field(json, "locals")?,
+            args: field(json, "args")?,
+            ret: field(json, "ret")?,
+            calling_convention: field(json, "calling_convention")?,
+            blocks: field(json, "blocks")?,
+            start: field(json, "start")?,
+        })
+    }
+}
+
+fieldless_enum_json!(CallingConvention { Rust, C });
+
+impl Json for BasicBlock {
+    fn to_json(self) -> JsonValue {
+        json!({ "statements": self.statements.to_json(), "terminator": self.terminator.to_json() })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(BasicBlock {
+            statements: field(json, "statements")?,
+            terminator: field(json, "terminator")?,
+        })
+    }
+}
+
+impl Json for Statement {
+    fn to_json(self) -> JsonValue {
+        match self {
+            Statement::Assign { destination, source } =>
+                variant(
+                    "Assign",
+                    json!({ "destination": destination.to_json(), "source": source.to_json() }),
+                ),
+            Statement::PlaceMention(place) => variant("PlaceMention", place.to_json()),
+            Statement::SetDiscriminant { destination, value } =>
+                variant(
+                    "SetDiscriminant",
+                    json!({ "destination": destination.to_json(), "value": value.to_json() }),
+                ),
+            Statement::Validate { place, fn_entry } =>
+                variant(
+                    "Validate",
+                    json!({ "place": place.to_json(), "fn_entry": fn_entry.to_json() }),
+                ),
+            Statement::Deinit { place } => variant("Deinit", json!({ "place": place.to_json() })),
+            Statement::StorageLive(local) => variant("StorageLive", local.to_json()),
+            Statement::StorageDead(local) => variant("StorageDead", local.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Assign" =>
+                Statement::Assign {
+                    destination: field(data, "destination")?,
+                    source: field(data, "source")?,
+                },
+            "PlaceMention" => Statement::PlaceMention(PlaceExpr::from_json(data)?),
+            "SetDiscriminant" =>
+                Statement::SetDiscriminant {
+                    destination: field(data, "destination")?,
+                    value: field(data, "value")?,
+                },
+            "Validate" =>
+                Statement::Validate {
+                    place: field(data, "place")?,
+                    fn_entry: field(data, "fn_entry")?,
+                },
+            "Deinit" => Statement::Deinit { place: field(data, "place")? },
+            "StorageLive" => Statement::StorageLive(LocalName::from_json(data)?),
+            "StorageDead" => Statement::StorageDead(LocalName::from_json(data)?),
+            _ => return Err(unknown_variant("Statement", name)),
+        })
+    }
+}
+
+impl Json for Terminator {
+    fn to_json(self) -> JsonValue {
+        match self {
+            Terminator::Goto(bb) => variant("Goto", bb.to_json()),
+            Terminator::Switch { value, cases, fallback } =>
+                variant(
+                    "Switch",
+                    json!({
+                        "value": value.to_json(),
+                        "cases": cases.to_json(),
+                        "fallback": fallback.to_json(),
+                    }),
+                ),
+            Terminator::Unreachable => JsonValue::from("Unreachable"),
+            Terminator::Intrinsic { intrinsic, arguments, ret, next_block, unwind_block } =>
+                variant(
+                    "Intrinsic",
+                    json!({
+                        "intrinsic": intrinsic.to_json(),
+                        "arguments": arguments.to_json(),
+                        "ret": ret.to_json(),
+                        "next_block": next_block.to_json(),
+                        "unwind_block": unwind_block.to_json(),
+                    }),
+                ),
+            Terminator::Call {
+                callee,
+                calling_convention,
+                arguments,
+                ret,
+                next_block,
+                unwind_block,
+            } =>
+                variant(
+                    "Call",
+                    json!({
+                        "callee": callee.to_json(),
+                        "calling_convention": calling_convention.to_json(),
+                        "arguments": arguments.to_json(),
+                        "ret": ret.to_json(),
+                        "next_block": next_block.to_json(),
+                        "unwind_block": unwind_block.to_json(),
+                    }),
+                ),
+            Terminator::Return => JsonValue::from("Return"),
+            Terminator::ResumeUnwind => JsonValue::from("ResumeUnwind"),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Goto" => Terminator::Goto(BbName::from_json(data)?),
+            "Switch" =>
+                Terminator::Switch {
+                    value: field(data, "value")?,
+                    cases: field(data, "cases")?,
+                    fallback: field(data, "fallback")?,
+                },
+            "Unreachable" => Terminator::Unreachable,
+            "Intrinsic" =>
+                Terminator::Intrinsic {
+                    intrinsic: field(data, "intrinsic")?,
+                    arguments: field(data, "arguments")?,
+                    ret: field(data, "ret")?,
+                    next_block: field(data, "next_block")?,
+                    unwind_block: field(data, "unwind_block")?,
+                },
+            "Call" =>
+                Terminator::Call {
+                    callee: field(data, "callee")?,
+                    calling_convention: field(data, "calling_convention")?,
+                    arguments: field(data, "arguments")?,
+                    ret: field(data, "ret")?,
+                    next_block: field(data, "next_block")?,
+                    unwind_block: field(data, "unwind_block")?,
+                },
+            "Return" => Terminator::Return,
+            "ResumeUnwind" => Terminator::ResumeUnwind,
+            _ => return Err(unknown_variant("Terminator", name)),
+        })
+    }
+}
+
+impl Json for ArgumentExpr {
+    fn to_json(self) -> JsonValue {
+        match self {
+            ArgumentExpr::ByValue(value) => variant("ByValue", value.to_json()),
+            ArgumentExpr::InPlace(place) => variant("InPlace", place.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "ByValue" => ArgumentExpr::ByValue(ValueExpr::from_json(data)?),
+            "InPlace" => ArgumentExpr::InPlace(PlaceExpr::from_json(data)?),
+            _ => return Err(unknown_variant("ArgumentExpr", name)),
+        })
+    }
+}
+
+impl Json for IntrinsicOp {
+    fn to_json(self) -> JsonValue {
+        match self {
+            IntrinsicOp::Assume => JsonValue::from("Assume"),
+            IntrinsicOp::Exit => JsonValue::from("Exit"),
+            IntrinsicOp::Panic => JsonValue::from("Panic"),
+            IntrinsicOp::Abort => JsonValue::from("Abort"),
+            IntrinsicOp::CatchUnwind => JsonValue::from("CatchUnwind"),
+            IntrinsicOp::PrintStdout => JsonValue::from("PrintStdout"),
+            IntrinsicOp::PrintStderr => JsonValue::from("PrintStderr"),
+            IntrinsicOp::Allocate => JsonValue::from("Allocate"),
+            IntrinsicOp::Deallocate => JsonValue::from("Deallocate"),
+            IntrinsicOp::Reallocate => JsonValue::from("Reallocate"),
+            IntrinsicOp::Spawn => JsonValue::from("Spawn"),
+            IntrinsicOp::Join => JsonValue::from("Join"),
+            IntrinsicOp::RawEq => JsonValue::from("RawEq"),
+            IntrinsicOp::AtomicStore(ordering) => variant("AtomicStore", ordering.to_json()),
+            IntrinsicOp::AtomicLoad(ordering) => variant("AtomicLoad", ordering.to_json()),
+            IntrinsicOp::AtomicCompareExchange { success, failure } =>
+                variant(
+                    "AtomicCompareExchange",
+                    json!({ "success": success.to_json(), "failure": failure.to_json() }),
+                ),
+            IntrinsicOp::AtomicFetchAndOp(op, ordering) =>
+                variant("AtomicFetchAndOp", (op, ordering).to_json()),
+            IntrinsicOp::AtomicFence(ordering) => variant("AtomicFence", ordering.to_json()),
+            IntrinsicOp::Lock(op) => variant("Lock", op.to_json()),
+            IntrinsicOp::PointerExposeProvenance => JsonValue::from("PointerExposeProvenance"),
+            IntrinsicOp::PointerWithExposedProvenance =>
+                JsonValue::from("PointerWithExposedProvenance"),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Assume" => IntrinsicOp::Assume,
+            "Exit" => IntrinsicOp::Exit,
+            "Panic" => IntrinsicOp::Panic,
+            "Abort" => IntrinsicOp::Abort,
+            "CatchUnwind" => IntrinsicOp::CatchUnwind,
+            "PrintStdout" => IntrinsicOp::PrintStdout,
+            "PrintStderr" => IntrinsicOp::PrintStderr,
+            "Allocate" => IntrinsicOp::Allocate,
+            "Deallocate" => IntrinsicOp::Deallocate,
+            "Reallocate" => IntrinsicOp::Reallocate,
+            "Spawn" => IntrinsicOp::Spawn,
+            "Join" => IntrinsicOp::Join,
+            "RawEq" => IntrinsicOp::RawEq,
+            "AtomicStore" => IntrinsicOp::AtomicStore(MemoryOrdering::from_json(data)?),
+            "AtomicLoad" => IntrinsicOp::AtomicLoad(MemoryOrdering::from_json(data)?),
+            "AtomicCompareExchange" =>
+                IntrinsicOp::AtomicCompareExchange {
+                    success: field(data, "success")?,
+                    failure: field(data, "failure")?,
+                },
+            "AtomicFetchAndOp" => {
+                let (op, ordering) = <(IntBinOp, MemoryOrdering)>::from_json(data)?;
+                IntrinsicOp::AtomicFetchAndOp(op, ordering)
+            }
+            "AtomicFence" => IntrinsicOp::AtomicFence(MemoryOrdering::from_json(data)?),
+            "Lock" => IntrinsicOp::Lock(IntrinsicLockOp::from_json(data)?),
+            "PointerExposeProvenance" => IntrinsicOp::PointerExposeProvenance,
+            "PointerWithExposedProvenance" => IntrinsicOp::PointerWithExposedProvenance,
+            _ => return Err(unknown_variant("IntrinsicOp", name)),
+        })
+    }
+}
+
+fieldless_enum_json!(IntrinsicLockOp { Acquire, Release, Create });
+fieldless_enum_json!(MemoryOrdering { Relaxed, Acquire, Release, AcqRel, SeqCst });
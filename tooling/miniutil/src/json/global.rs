@@ -0,0 +1,33 @@
+use super::*;
+
+impl Json for Global {
+    fn to_json(self) -> JsonValue {
+        json!({
+            "bytes": self.bytes.to_json(),
+            "relocations": self.relocations.to_json(),
+            "fn_pointers": self.fn_pointers.to_json(),
+            "align": self.align.to_json(),
+            "thread_local": self.thread_local.to_json(),
+        })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(Global {
+            bytes: field(json, "bytes")?,
+            relocations: field(json, "relocations")?,
+            fn_pointers: field(json, "fn_pointers")?,
+            align: field(json, "align")?,
+            thread_local: field(json, "thread_local")?,
+        })
+    }
+}
+
+impl Json for Relocation {
+    fn to_json(self) -> JsonValue {
+        json!({ "name": self.name.to_json(), "offset": self.offset.to_json() })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(Relocation { name: field(json, "name")?, offset: field(json, "offset")? })
+    }
+}
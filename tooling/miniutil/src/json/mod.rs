@@ -0,0 +1,397 @@
+//! This module converts programs to and from JSON, so that tools which do not link against
+//! MiniRust (or rustc) can consume them.
+//!
+//! The encoding follows serde's default representation of the syntax types:
+//! structs are objects, fieldless enum variants are strings, and all other enum variants are
+//! objects with a single key naming the variant. Tuples and tuple variants with several fields
+//! are lists. Maps are lists of `[key, value]` pairs and names are encoded as their index.
+//! Integers are numbers if they fit into 64 bits, and decimal strings otherwise.
+//!
+//! A document has the form `{ "version": JSON_SCHEMA_VERSION, "program": ... }`.
+
+use crate::*;
+
+use serde_json::{json, Value as JsonValue};
+use std::cmp::Ordering;
+
+// Implements `Json` for enums without fields, which are encoded as the name of their variant.
+macro_rules! fieldless_enum_json {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl Json for $ty {
+            fn to_json(self) -> JsonValue {
+                match self {
+                    $($ty::$variant => JsonValue::from(stringify!($variant)),)*
+                }
+            }
+
+            fn from_json(json: &JsonValue) -> JResult<Self> {
+                match json.as_str() {
+                    $(Some(stringify!($variant)) => Ok($ty::$variant),)*
+                    _ => Err(JsonError::new(concat!("expected a `", stringify!($ty), "`"))),
+                }
+            }
+        }
+    };
+}
+
+// The conversions are split into modules in the same way as the `fmt` module.
+mod expr;
+mod function;
+mod global;
+mod ty;
+mod vtable;
+
+/// The version of the JSON encoding. It is bumped whenever the encoding changes.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// An error that occurred while decoding a program from JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    /// The fields and list indices leading to the value that could not be decoded, outermost first.
+    pub path: Vec<String>,
+    pub msg: String,
+}
+
+impl JsonError {
+    fn new(msg: impl Into<String>) -> Self {
+        JsonError { path: Vec::new(), msg: msg.into() }
+    }
+
+    // Records that the error happened inside of `segment`.
+    fn within(mut self, segment: &str) -> Self {
+        self.path.insert(0, segment.to_string());
+        self
+    }
+}
+
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "JSON error: {}", self.msg)
+        } else {
+            write!(f, "JSON error at `{}`: {}", self.path.join("."), self.msg)
+        }
+    }
+}
+
+type JResult<T> = Result<T, JsonError>;
+
+// Encode a program as a JSON document.
+pub fn program_to_json(prog: Program) -> String {
+    let doc = json!({ "version": JSON_SCHEMA_VERSION, "program": prog.to_json() });
+    serde_json::to_string_pretty(&doc).unwrap()
+}
+
+// Decode a program from a JSON document.
+pub fn program_from_json(src: &str) -> JResult<Program> {
+    let doc: JsonValue =
+        serde_json::from_str(src).map_err(|err| JsonError::new(err.to_string()))?;
+    let version: u32 = field(&doc, "version")?;
+    if version != JSON_SCHEMA_VERSION {
+        return Err(JsonError::new(format!(
+            "unsupported schema version {version}, expected {JSON_SCHEMA_VERSION}"
+        )));
+    }
+    field(&doc, "program")
+}
+
+/// Types that can be converted to and from JSON.
+trait Json: Sized {
+    fn to_json(self) -> JsonValue;
+    fn from_json(json: &JsonValue) -> JResult<Self>;
+}
+
+/////////////////////
+// helpers
+/////////////////////
+
+// Reads the field `name` of a JSON object.
+fn field<T: Json>(json: &JsonValue, name: &str) -> JResult<T> {
+    let Some(obj) = json.as_object() else {
+        return Err(JsonError::new("expected an object"));
+    };
+    let Some(value) = obj.get(name) else {
+        return Err(JsonError::new(format!("missing field `{name}`")));
+    };
+    T::from_json(value).map_err(|err| err.within(name))
+}
+
+// Encodes an enum variant that carries data.
+fn variant(name: &str, data: JsonValue) -> JsonValue {
+    let mut obj = serde_json::Map::new();
+    obj.insert(name.to_string(), data);
+    JsonValue::Object(obj)
+}
+
+static NULL: JsonValue = JsonValue::Null;
+
+// Splits an encoded enum into the name of its variant and its data.
+// Fieldless variants have `null` as their data.
+fn split_variant(json: &JsonValue) -> JResult<(&str, &JsonValue)> {
+    match json {
+        JsonValue::String(name) => Ok((name, &NULL)),
+        JsonValue::Object(obj) if obj.len() == 1 => {
+            let (name, data) = obj.iter().next().unwrap();
+            Ok((name, data))
+        }
+        _ => Err(JsonError::new("expected an enum variant")),
+    }
+}
+
+fn unknown_variant(ty: &str, name: &str) -> JsonError {
+    JsonError::new(format!("unknown `{ty}` variant `{name}`"))
+}
+
+// An ordering on JSON values, so that maps and sets are encoded deterministically.
+fn cmp_json(a: &JsonValue, b: &JsonValue) -> Ordering {
+    match (a, b) {
+        (JsonValue::Number(a), JsonValue::Number(b)) =>
+            match (a.as_i64(), b.as_i64()) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                _ => a.as_f64().unwrap().total_cmp(&b.as_f64().unwrap()),
+            },
+        (JsonValue::Array(a), JsonValue::Array(b)) =>
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| cmp_json(a, b))
+                .find(|ord| ord.is_ne())
+                .unwrap_or(a.len().cmp(&b.len())),
+        _ => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+fn sorted(mut elems: Vec<JsonValue>) -> JsonValue {
+    elems.sort_by(cmp_json);
+    JsonValue::Array(elems)
+}
+
+fn json_list(json: &JsonValue) -> JResult<&Vec<JsonValue>> {
+    json.as_array().ok_or_else(|| JsonError::new("expected a list"))
+}
+
+/////////////////////
+// basic types
+/////////////////////
+
+impl Json for bool {
+    fn to_json(self) -> JsonValue {
+        self.into()
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        json.as_bool().ok_or_else(|| JsonError::new("expected a boolean"))
+    }
+}
+
+impl Json for u8 {
+    fn to_json(self) -> JsonValue {
+        self.into()
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        json.as_u64()
+            .and_then(|n| n.try_into().ok())
+            .ok_or_else(|| JsonError::new("expected a byte"))
+    }
+}
+
+impl Json for u32 {
+    fn to_json(self) -> JsonValue {
+        self.into()
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        json.as_u64()
+            .and_then(|n| n.try_into().ok())
+            .ok_or_else(|| JsonError::new("expected a 32-bit unsigned integer"))
+    }
+}
+
+impl Json for u64 {
+    fn to_json(self) -> JsonValue {
+        self.into()
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        json.as_u64().ok_or_else(|| JsonError::new("expected a 64-bit unsigned integer"))
+    }
+}
+
+impl Json for Int {
+    fn to_json(self) -> JsonValue {
+        // Many JSON parsers lose precision on numbers beyond 64 bits, so those become strings.
+        let s = self.to_string();
+        if let Ok(i) = s.parse::<i64>() {
+            i.into()
+        } else if let Ok(u) = s.parse::<u64>() {
+            u.into()
+        } else {
+            s.into()
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let int = match json {
+            JsonValue::Number(n) =>
+                n.as_i64()
+                    .map(|i| Int::from(i as i128))
+                    .or_else(|| n.as_u64().map(|u| Int::from(u as u128))),
+            JsonValue::String(s) =>
+                s.parse::<i128>()
+                    .ok()
+                    .map(Int::from)
+                    .or_else(|| s.parse::<u128>().ok().map(Int::from)),
+            _ => None,
+        };
+        int.ok_or_else(|| JsonError::new("expected an integer"))
+    }
+}
+
+impl Json for Size {
+    fn to_json(self) -> JsonValue {
+        self.bytes().to_json()
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Size::from_bytes(Int::from_json(json)?).ok_or_else(|| JsonError::new("invalid size"))
+    }
+}
+
+impl Json for Align {
+    fn to_json(self) -> JsonValue {
+        self.bytes().to_json()
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Align::from_bytes(Int::from_json(json)?).ok_or_else(|| JsonError::new("invalid alignment"))
+    }
+}
+
+fieldless_enum_json!(Signedness { Signed, Unsigned });
+fieldless_enum_json!(Mutability { Mutable, Immutable });
+
+/////////////////////
+// collections
+/////////////////////
+
+impl<T: Json> Json for Option<T> {
+    fn to_json(self) -> JsonValue {
+        match self {
+            Some(t) => t.to_json(),
+            None => JsonValue::Null,
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        if json.is_null() { Ok(None) } else { Ok(Some(T::from_json(json)?)) }
+    }
+}
+
+impl<A: Json, B: Json> Json for (A, B) {
+    fn to_json(self) -> JsonValue {
+        json!([self.0.to_json(), self.1.to_json()])
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let [a, b] = json_list(json)?.as_slice() else {
+            return Err(JsonError::new("expected a list of two elements"));
+        };
+        let a = A::from_json(a).map_err(|err| err.within("0"))?;
+        let b = B::from_json(b).map_err(|err| err.within("1"))?;
+        Ok((a, b))
+    }
+}
+
+impl<T: Json + Obj> Json for List<T> {
+    fn to_json(self) -> JsonValue {
+        JsonValue::Array(self.iter().map(Json::to_json).collect())
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        json_list(json)?
+            .iter()
+            .enumerate()
+            .map(|(i, elem)| T::from_json(elem).map_err(|err| err.within(&i.to_string())))
+            .collect()
+    }
+}
+
+impl<T: Json + Obj> Json for Set<T> {
+    fn to_json(self) -> JsonValue {
+        sorted(self.iter().map(Json::to_json).collect())
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let elems: List<T> = List::from_json(json)?;
+        let set: Set<T> = elems.iter().collect();
+        if set.len() != elems.len() {
+            return Err(JsonError::new("duplicate element in set"));
+        }
+        Ok(set)
+    }
+}
+
+impl<K: Json + Obj, V: Json + Obj> Json for Map<K, V> {
+    fn to_json(self) -> JsonValue {
+        sorted(self.iter().map(|(k, v)| json!([k.to_json(), v.to_json()])).collect())
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let mut map = Map::new();
+        for (i, pair) in json_list(json)?.iter().enumerate() {
+            let (k, v) = <(K, V)>::from_json(pair).map_err(|err| err.within(&i.to_string()))?;
+            map.try_insert(k, v)
+                .map_err(|_| JsonError::new("duplicate key in map").within(&i.to_string()))?;
+        }
+        Ok(map)
+    }
+}
+
+/////////////////////
+// names
+/////////////////////
+
+// Implements `Json` for the name types, which are encoded as their index.
+macro_rules! name_json {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl Json for $name {
+                fn to_json(self) -> JsonValue {
+                    self.0.get_internal().into()
+                }
+
+                fn from_json(json: &JsonValue) -> JResult<Self> {
+                    Ok($name(Name::from_internal(u32::from_json(json)?)))
+                }
+            }
+        )*
+    };
+}
+
+name_json!(FnName, GlobalName, TraitName, TraitMethodName, VTableName, LocalName, BbName);
+
+/////////////////////
+// programs
+/////////////////////
+
+impl Json for Program {
+    fn to_json(self) -> JsonValue {
+        json!({
+            "functions": self.functions.to_json(),
+            "start": self.start.to_json(),
+            "globals": self.globals.to_json(),
+            "traits": self.traits.to_json(),
+            "vtables": self.vtables.to_json(),
+        })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(Program {
+            functions: field(json, "functions")?,
+            start: field(json, "start")?,
+            globals: field(json, "globals")?,
+            traits: field(json, "traits")?,
+            vtables: field(json, "vtables")?,
+        })
+    }
+}
@@ -0,0 +1,238 @@
+use super::*;
+
+impl Json for Type {
+    fn to_json(self) -> JsonValue {
+        match self {
+            Type::Int(int_ty) => variant("Int", int_ty.to_json()),
+            Type::Bool => JsonValue::from("Bool"),
+            Type::Char => JsonValue::from("Char"),
+            Type::Float(float_ty) => variant("Float", float_ty.to_json()),
+            Type::Ptr(ptr_ty) => variant("Ptr", ptr_ty.to_json()),
+            Type::Tuple { fields, size, align } =>
+                variant(
+                    "Tuple",
+                    json!({
+                        "fields": fields.to_json(),
+                        "size": size.to_json(),
+                        "align": align.to_json(),
+                    }),
+                ),
+            Type::Array { elem, count } =>
+                variant(
+                    "Array",
+                    json!({ "elem": elem.extract().to_json(), "count": count.to_json() }),
+                ),
+            Type::Slice { elem } => variant("Slice", json!({ "elem": elem.extract().to_json() })),
+            Type::TraitObject(trait_name) => variant("TraitObject", trait_name.to_json()),
+            Type::Union { fields, chunks, size, align } =>
+                variant(
+                    "Union",
+                    json!({
+                        "fields": fields.to_json(),
+                        "chunks": chunks.to_json(),
+                        "size": size.to_json(),
+                        "align": align.to_json(),
+                    }),
+                ),
+            Type::Enum { variants, discriminant_ty, discriminator, size, align } =>
+                variant(
+                    "Enum",
+                    json!({
+                        "variants": variants.to_json(),
+                        "discriminant_ty": discriminant_ty.to_json(),
+                        "discriminator": discriminator.to_json(),
+                        "size": size.to_json(),
+                        "align": align.to_json(),
+                    }),
+                ),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Int" => Type::Int(IntType::from_json(data)?),
+            "Bool" => Type::Bool,
+            "Char" => Type::Char,
+            "Float" => Type::Float(FloatType::from_json(data)?),
+            "Ptr" => Type::Ptr(PtrType::from_json(data)?),
+            "Tuple" =>
+                Type::Tuple {
+                    fields: field(data, "fields")?,
+                    size: field(data, "size")?,
+                    align: field(data, "align")?,
+                },
+            "Array" =>
+                Type::Array { elem: GcCow::new(field(data, "elem")?), count: field(data, "count")? },
+            "Slice" => Type::Slice { elem: GcCow::new(field(data, "elem")?) },
+            "TraitObject" => Type::TraitObject(TraitName::from_json(data)?),
+            "Union" =>
+                Type::Union {
+                    fields: field(data, "fields")?,
+                    chunks: field(data, "chunks")?,
+                    size: field(data, "size")?,
+                    align: field(data, "align")?,
+                },
+            "Enum" =>
+                Type::Enum {
+                    variants: field(data, "variants")?,
+                    discriminant_ty: field(data, "discriminant_ty")?,
+                    discriminator: field(data, "discriminator")?,
+                    size: field(data, "size")?,
+                    align: field(data, "align")?,
+                },
+            _ => return Err(unknown_variant("Type", name)),
+        })
+    }
+}
+
+impl Json for IntType {
+    fn to_json(self) -> JsonValue {
+        json!({ "signed": self.signed.to_json(), "size": self.size.to_json() })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(IntType { signed: field(json, "signed")?, size: field(json, "size")? })
+    }
+}
+
+fieldless_enum_json!(FloatType { F32, F64 });
+
+impl Json for PtrType {
+    fn to_json(self) -> JsonValue {
+        match self {
+            PtrType::Ref { mutbl, pointee } =>
+                variant("Ref", json!({ "mutbl": mutbl.to_json(), "pointee": pointee.to_json() })),
+            PtrType::Box { pointee } => variant("Box", json!({ "pointee": pointee.to_json() })),
+            PtrType::Raw { meta_kind } =>
+                variant("Raw", json!({ "meta_kind": meta_kind.to_json() })),
+            PtrType::FnPtr => JsonValue::from("FnPtr"),
+            PtrType::VTablePtr(trait_name) => variant("VTablePtr", trait_name.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Ref" =>
+                PtrType::Ref { mutbl: field(data, "mutbl")?, pointee: field(data, "pointee")? },
+            "Box" => PtrType::Box { pointee: field(data, "pointee")? },
+            "Raw" => PtrType::Raw { meta_kind: field(data, "meta_kind")? },
+            "FnPtr" => PtrType::FnPtr,
+            "VTablePtr" => PtrType::VTablePtr(TraitName::from_json(data)?),
+            _ => return Err(unknown_variant("PtrType", name)),
+        })
+    }
+}
+
+impl Json for PointeeInfo {
+    fn to_json(self) -> JsonValue {
+        json!({
+            "size": self.size.to_json(),
+            "align": self.align.to_json(),
+            "inhabited": self.inhabited.to_json(),
+            "freeze": self.freeze.to_json(),
+            "unpin": self.unpin.to_json(),
+            "unsafe_cells": self.unsafe_cells.to_json(),
+            "byte_slice": self.byte_slice.to_json(),
+        })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(PointeeInfo {
+            size: field(json, "size")?,
+            align: field(json, "align")?,
+            inhabited: field(json, "inhabited")?,
+            freeze: field(json, "freeze")?,
+            unpin: field(json, "unpin")?,
+            unsafe_cells: field(json, "unsafe_cells")?,
+            byte_slice: field(json, "byte_slice")?,
+        })
+    }
+}
+
+impl Json for SizeStrategy {
+    fn to_json(self) -> JsonValue {
+        match self {
+            SizeStrategy::Sized(size) => variant("Sized", size.to_json()),
+            SizeStrategy::Slice(elem_size) => variant("Slice", elem_size.to_json()),
+            SizeStrategy::VTable(trait_name) => variant("VTable", trait_name.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Sized" => SizeStrategy::Sized(Size::from_json(data)?),
+            "Slice" => SizeStrategy::Slice(Size::from_json(data)?),
+            "VTable" => SizeStrategy::VTable(TraitName::from_json(data)?),
+            _ => return Err(unknown_variant("SizeStrategy", name)),
+        })
+    }
+}
+
+impl Json for PointerMetaKind {
+    fn to_json(self) -> JsonValue {
+        match self {
+            PointerMetaKind::None => JsonValue::from("None"),
+            PointerMetaKind::ElementCount => JsonValue::from("ElementCount"),
+            PointerMetaKind::VTablePointer(trait_name) =>
+                variant("VTablePointer", trait_name.to_json()),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "None" => PointerMetaKind::None,
+            "ElementCount" => PointerMetaKind::ElementCount,
+            "VTablePointer" => PointerMetaKind::VTablePointer(TraitName::from_json(data)?),
+            _ => return Err(unknown_variant("PointerMetaKind", name)),
+        })
+    }
+}
+
+impl Json for Variant {
+    fn to_json(self) -> JsonValue {
+        json!({ "ty": self.ty.to_json(), "tagger": self.tagger.to_json() })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(Variant { ty: field(json, "ty")?, tagger: field(json, "tagger")? })
+    }
+}
+
+impl Json for Discriminator {
+    fn to_json(self) -> JsonValue {
+        match self {
+            Discriminator::Known(discriminant) => variant("Known", discriminant.to_json()),
+            Discriminator::Invalid => JsonValue::from("Invalid"),
+            Discriminator::Branch { offset, value_type, fallback, children } =>
+                variant(
+                    "Branch",
+                    json!({
+                        "offset": offset.to_json(),
+                        "value_type": value_type.to_json(),
+                        "fallback": fallback.extract().to_json(),
+                        "children": children.to_json(),
+                    }),
+                ),
+        }
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        let (name, data) = split_variant(json)?;
+        Ok(match name {
+            "Known" => Discriminator::Known(Int::from_json(data)?),
+            "Invalid" => Discriminator::Invalid,
+            "Branch" =>
+                Discriminator::Branch {
+                    offset: field(data, "offset")?,
+                    value_type: field(data, "value_type")?,
+                    fallback: GcCow::new(field(data, "fallback")?),
+                    children: field(data, "children")?,
+                },
+            _ => return Err(unknown_variant("Discriminator", name)),
+        })
+    }
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+impl Json for VTable {
+    fn to_json(self) -> JsonValue {
+        json!({
+            "trait_name": self.trait_name.to_json(),
+            "size": self.size.to_json(),
+            "align": self.align.to_json(),
+            "drop": self.drop.to_json(),
+            "methods": self.methods.to_json(),
+        })
+    }
+
+    fn from_json(json: &JsonValue) -> JResult<Self> {
+        Ok(VTable {
+            trait_name: field(json, "trait_name")?,
+            size: field(json, "size")?,
+            align: field(json, "align")?,
+            drop: field(json, "drop")?,
+            methods: field(json, "methods")?,
+        })
+    }
+}
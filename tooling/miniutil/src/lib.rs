@@ -17,11 +17,21 @@ pub use std::format;
 pub use std::result::Result;
 pub use std::string::String;
 
+pub mod borrows;
 pub mod build;
+pub mod debug;
+pub mod explore;
 pub mod fmt;
+#[cfg(feature = "generate")]
+pub mod generate;
+pub mod json;
 pub mod mock_write;
+pub mod parse;
 pub mod run;
+pub mod schedule;
+mod trace;
 
 pub type DefaultTarget = x86_64;
 pub type BasicMem = BasicMemory<DefaultTarget>;
 pub type TreeBorrowMem = TreeBorrowsMemory<DefaultTarget>;
+pub type StackedBorrowMem = StackedBorrowsMemory<DefaultTarget>;
@@ -0,0 +1,423 @@
+use super::*;
+
+impl<'a> Parser<'a> {
+    /////////////////////
+    // place expressions
+    /////////////////////
+
+    pub(super) fn parse_place_expr(&mut self) -> PResult<PlaceExpr> {
+        let place = self.parse_atomic_place_expr()?;
+        if self.eat_keyword("as") {
+            self.expect_keyword("variant")?;
+            let discriminant = self.parse_int()?;
+            return Ok(PlaceExpr::Downcast { root: GcCow::new(place), discriminant });
+        }
+        Ok(place)
+    }
+
+    // Parses a place expression that does not need to be wrapped in parens, i.e. everything but a downcast.
+    pub(super) fn parse_atomic_place_expr(&mut self) -> PResult<PlaceExpr> {
+        let mut place = if self.eat("(") {
+            let place = self.parse_place_expr()?;
+            self.expect(")")?;
+            place
+        } else if self.eat_keyword("deref") {
+            self.expect("<")?;
+            let ty = self.parse_type()?;
+            self.expect(">")?;
+            self.expect("(")?;
+            let operand = self.parse_value_expr()?;
+            self.expect(")")?;
+            PlaceExpr::Deref { operand: GcCow::new(operand), ty }
+        } else {
+            PlaceExpr::Local(self.parse_local_name()?)
+        };
+
+        // Field and index projections.
+        loop {
+            if self.eat(".") {
+                let field = self.parse_int()?;
+                place = PlaceExpr::Field { root: GcCow::new(place), field };
+            } else if self.eat("[") {
+                let index = self.parse_value_expr()?;
+                self.expect("]")?;
+                place = PlaceExpr::Index { root: GcCow::new(place), index: GcCow::new(index) };
+            } else {
+                return Ok(place);
+            }
+        }
+    }
+
+    pub(super) fn parse_local_name(&mut self) -> PResult<LocalName> {
+        Ok(LocalName(self.parse_prefixed_name("_")?))
+    }
+
+    /////////////////////
+    // value expressions
+    /////////////////////
+
+    pub(super) fn parse_value_expr(&mut self) -> PResult<ValueExpr> {
+        let left = self.parse_atomic_value_expr()?;
+
+        // Infix operators always have atomic operands, so there is no need for operator precedence.
+        // Longer operators have to be checked before their prefixes.
+        use IntBinOp::*;
+        let ops = [
+            ("<=>", BinOp::Rel(RelOp::Cmp)),
+            ("<<", BinOp::Int(Shl)),
+            (">>", BinOp::Int(Shr)),
+            ("<=", BinOp::Rel(RelOp::Le)),
+            (">=", BinOp::Rel(RelOp::Ge)),
+            ("==", BinOp::Rel(RelOp::Eq)),
+            ("!=", BinOp::Rel(RelOp::Ne)),
+            ("<", BinOp::Rel(RelOp::Lt)),
+            (">", BinOp::Rel(RelOp::Gt)),
+            ("+", BinOp::Int(Add)),
+            ("-", BinOp::Int(Sub)),
+            ("*", BinOp::Int(Mul)),
+            ("/", BinOp::Int(Div)),
+            ("%", BinOp::Int(Rem)),
+            ("&", BinOp::Int(BitAnd)),
+            ("|", BinOp::Int(BitOr)),
+            ("^", BinOp::Int(BitXor)),
+        ];
+        // `->` follows the value of a `switch`, it is not a subtraction.
+        if self.peek("->") {
+            return Ok(left);
+        }
+        for (op, operator) in ops {
+            if self.eat(op) {
+                let right = self.parse_atomic_value_expr()?;
+                return Ok(ValueExpr::BinOp {
+                    operator,
+                    left: GcCow::new(left),
+                    right: GcCow::new(right),
+                });
+            }
+        }
+        Ok(left)
+    }
+
+    fn parse_atomic_value_expr(&mut self) -> PResult<ValueExpr> {
+        let rest = self.rest();
+        let pos = self.token_start();
+
+        if rest.starts_with(|c: char| c.is_ascii_digit())
+            || (rest.starts_with('-') && rest[1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            return self.parse_literal();
+        }
+        if self.eat("-") {
+            let operand = self.parse_paren_value_expr()?;
+            return Ok(un_op(UnOp::Int(IntUnOp::Neg), operand));
+        }
+        if self.eat("!") {
+            let operand = self.parse_paren_value_expr()?;
+            return Ok(un_op(UnOp::Int(IntUnOp::BitNot), operand));
+        }
+        if self.eat("&") {
+            let ptr_ty = if self.eat_keyword("raw") {
+                PtrType::Raw { meta_kind: PointerMetaKind::None }
+            } else {
+                let mutbl = if self.eat_keyword("mut") {
+                    Mutability::Mutable
+                } else {
+                    Mutability::Immutable
+                };
+                self.expect("<")?;
+                let pointee = self.parse_pointee_info()?;
+                self.expect(">")?;
+                PtrType::Ref { mutbl, pointee }
+            };
+            let target = self.parse_atomic_place_expr()?;
+            return Ok(ValueExpr::AddrOf { target: GcCow::new(target), ptr_ty });
+        }
+        if self.peek("[") {
+            let ty = self.parse_type()?;
+            return self.parse_aggregate(ty);
+        }
+        if self.peek("(") {
+            // This is either a parenthesized type in front of an aggregate, or a parenthesized expression.
+            let ty = self.attempt(|p| {
+                p.expect("(")?;
+                let ty = p.parse_type()?;
+                p.expect(")")?;
+                Ok(ty)
+            });
+            if let Some(ty) = ty {
+                return self.parse_aggregate(ty);
+            }
+            return self.parse_paren_value_expr();
+        }
+
+        let ident = self.peek_ident();
+        if ident.starts_with('T') && ident[1..].parse::<u32>().is_ok() {
+            let ty = self.parse_type()?;
+            return self.parse_aggregate(ty);
+        }
+        if let Some(c) = self.attempt(|p| p.parse_untyped_constant()) {
+            let ty = match c {
+                Constant::Bool(_) => Type::Bool,
+                Constant::Float(Float::F32(_)) => Type::Float(FloatType::F32),
+                Constant::Float(Float::F64(_)) => Type::Float(FloatType::F64),
+                Constant::FnPointer(_) => Type::Ptr(PtrType::FnPtr),
+                Constant::GlobalPointer(_) | Constant::PointerWithoutProvenance(_) =>
+                    Type::Ptr(PtrType::Raw { meta_kind: PointerMetaKind::None }),
+                // Integers are handled by `parse_literal` and vtable pointers are printed with their type.
+                Constant::Int(_) | Constant::VTablePointer(_) =>
+                    return Err(self.error_at(pos, "constant is missing its type")),
+            };
+            return Ok(ValueExpr::Constant(c, ty));
+        }
+
+        self.pos += ident.len();
+        let expr = match ident.as_str() {
+            "const" => {
+                self.expect("<")?;
+                let ty = self.parse_type()?;
+                self.expect(">")?;
+                self.expect("(")?;
+                let c = self.parse_untyped_constant()?;
+                self.expect(")")?;
+                ValueExpr::Constant(c, ty)
+            }
+            "load" => {
+                let source = self.parse_paren_place_expr()?;
+                ValueExpr::Load { source: GcCow::new(source) }
+            }
+            "discriminant" => {
+                let place = self.parse_paren_place_expr()?;
+                ValueExpr::GetDiscriminant { place: GcCow::new(place) }
+            }
+            "addr_of" => {
+                self.expect("<")?;
+                let ptr_ty = self.parse_ptr_type()?;
+                self.expect(">")?;
+                let target = self.parse_paren_place_expr()?;
+                ValueExpr::AddrOf { target: GcCow::new(target), ptr_ty }
+            }
+
+            // Unary operators with a type argument.
+            "int2int" | "float2int" => {
+                self.expect("<")?;
+                let int_ty = self.parse_int_type()?;
+                self.expect(">")?;
+                let cast = match ident.as_str() {
+                    "int2int" => CastOp::IntToInt(int_ty),
+                    _ => CastOp::FloatToInt(int_ty),
+                };
+                un_op(UnOp::Cast(cast), self.parse_paren_value_expr()?)
+            }
+            "int2float" | "float2float" => {
+                self.expect("<")?;
+                let float_ty = self.parse_float_type()?;
+                self.expect(">")?;
+                let cast = match ident.as_str() {
+                    "int2float" => CastOp::IntToFloat(float_ty),
+                    _ => CastOp::FloatToFloat(float_ty),
+                };
+                un_op(UnOp::Cast(cast), self.parse_paren_value_expr()?)
+            }
+            "transmute" | "compute_size" | "compute_align" => {
+                self.expect("<")?;
+                let ty = self.parse_type()?;
+                self.expect(">")?;
+                let operator = match ident.as_str() {
+                    "transmute" => UnOp::Cast(CastOp::Transmute(ty)),
+                    "compute_size" => UnOp::ComputeSize(ty),
+                    _ => UnOp::ComputeAlign(ty),
+                };
+                un_op(operator, self.parse_paren_value_expr()?)
+            }
+            "vtable_method_lookup" => {
+                self.expect("<")?;
+                let method = self.parse_trait_method_name()?;
+                self.expect(">")?;
+                un_op(UnOp::VTableMethodLookup(method), self.parse_paren_value_expr()?)
+            }
+
+            // Unary operators printed like functions.
+            "FloatNeg" => un_op(UnOp::Float(FloatUnOp::Neg), self.parse_paren_value_expr()?),
+            "get_metadata" => un_op(UnOp::GetMetadata, self.parse_paren_value_expr()?),
+            "get_thin_pointer" => un_op(UnOp::GetThinPointer, self.parse_paren_value_expr()?),
+            "vtable_drop_lookup" => un_op(UnOp::VTableDropLookup, self.parse_paren_value_expr()?),
+
+            // Binary operators printed like functions.
+            _ => {
+                let operator = match ident.as_str() {
+                    "AddUnchecked" => BinOp::Int(IntBinOp::AddUnchecked),
+                    "SubUnchecked" => BinOp::Int(IntBinOp::SubUnchecked),
+                    "MulUnchecked" => BinOp::Int(IntBinOp::MulUnchecked),
+                    "DivExact" => BinOp::Int(IntBinOp::DivExact),
+                    "ShlUnchecked" => BinOp::Int(IntBinOp::ShlUnchecked),
+                    "ShrUnchecked" => BinOp::Int(IntBinOp::ShrUnchecked),
+                    "FloatAdd" => BinOp::Float(FloatBinOp::Add),
+                    "FloatSub" => BinOp::Float(FloatBinOp::Sub),
+                    "FloatMul" => BinOp::Float(FloatBinOp::Mul),
+                    "FloatDiv" => BinOp::Float(FloatBinOp::Div),
+                    "FloatRem" => BinOp::Float(FloatBinOp::Rem),
+                    "AddWithOverflow" => BinOp::IntWithOverflow(IntBinOpWithOverflow::Add),
+                    "SubWithOverflow" => BinOp::IntWithOverflow(IntBinOpWithOverflow::Sub),
+                    "MulWithOverflow" => BinOp::IntWithOverflow(IntBinOpWithOverflow::Mul),
+                    "offset_inbounds" => BinOp::PtrOffset { inbounds: true },
+                    "offset_wrapping" => BinOp::PtrOffset { inbounds: false },
+                    "offset_from_inbounds" =>
+                        BinOp::PtrOffsetFrom { inbounds: true, nonneg: false },
+                    "offset_from_inbounds_nonneg" =>
+                        BinOp::PtrOffsetFrom { inbounds: true, nonneg: true },
+                    "offset_from_wrapping" =>
+                        BinOp::PtrOffsetFrom { inbounds: false, nonneg: false },
+                    "offset_from_wrapping_nonneg" =>
+                        BinOp::PtrOffsetFrom { inbounds: false, nonneg: true },
+                    _ => return Err(self.error_at(pos, "expected a value expression")),
+                };
+                self.expect("(")?;
+                let left = self.parse_value_expr()?;
+                self.expect(",")?;
+                let right = self.parse_value_expr()?;
+                self.expect(")")?;
+                ValueExpr::BinOp { operator, left: GcCow::new(left), right: GcCow::new(right) }
+            }
+        };
+        Ok(expr)
+    }
+
+    fn parse_paren_value_expr(&mut self) -> PResult<ValueExpr> {
+        self.expect("(")?;
+        let v = self.parse_value_expr()?;
+        self.expect(")")?;
+        Ok(v)
+    }
+
+    pub(super) fn parse_paren_place_expr(&mut self) -> PResult<PlaceExpr> {
+        self.expect("(")?;
+        let p = self.parse_place_expr()?;
+        self.expect(")")?;
+        Ok(p)
+    }
+
+    // Parses what follows the type of a tuple, union or variant expression.
+    fn parse_aggregate(&mut self, ty: Type) -> PResult<ValueExpr> {
+        if self.eat("{") {
+            let field = Int::from(self.parse_prefixed_name("field")?.get_internal());
+            self.expect(":")?;
+            let expr = self.parse_value_expr()?;
+            self.expect("}")?;
+            return Ok(ValueExpr::Union { field, expr: GcCow::new(expr), union_ty: ty });
+        }
+        self.expect("(")?;
+        if self.eat_keyword("variant") {
+            let discriminant = self.parse_int()?;
+            self.expect(")")?;
+            self.expect(":")?;
+            let data = self.parse_value_expr()?;
+            return Ok(ValueExpr::Variant { discriminant, data: GcCow::new(data), enum_ty: ty });
+        }
+        let elems = self.parse_list(")", |p| p.parse_value_expr())?;
+        Ok(ValueExpr::Tuple(elems.into_iter().collect(), ty))
+    }
+
+    /////////////////////
+    // constants
+    /////////////////////
+
+    // Parses an integer with type suffix like `-5_i32`, or a float like `1.5f32`.
+    fn parse_literal(&mut self) -> PResult<ValueExpr> {
+        let pos = self.token_start();
+        let int = self.parse_int()?;
+        if self.src[self.pos..].starts_with('_') {
+            self.pos += 1;
+            let int_ty = self.parse_int_type()?;
+            return Ok(ValueExpr::Constant(Constant::Int(int), Type::Int(int_ty)));
+        }
+        self.pos = pos;
+        let float = self.parse_float()?;
+        let ty = match float {
+            Float::F32(_) => FloatType::F32,
+            Float::F64(_) => FloatType::F64,
+        };
+        Ok(ValueExpr::Constant(Constant::Float(float), Type::Float(ty)))
+    }
+
+    // Parses a float, either like `1.5f32` or `f32_from_bits(0x7fc00000)`.
+    fn parse_float(&mut self) -> PResult<Float> {
+        let pos = self.token_start();
+        if self.eat_keyword("f32_from_bits") || self.eat_keyword("f64_from_bits") {
+            let f32 = self.src[pos..].trim_start().starts_with("f32");
+            self.expect("(")?;
+            self.expect("0x")?;
+            let hex = self.parse_ident()?;
+            self.expect(")")?;
+            let float = match f32 {
+                true => u32::from_str_radix(&hex, 16).ok().map(Float::F32),
+                false => u64::from_str_radix(&hex, 16).ok().map(Float::F64),
+            };
+            return float.ok_or_else(|| self.error_at(pos, "invalid float bits"));
+        }
+
+        // Floats are printed using Rust's `Debug` format, e.g. `-1.5e-7f32`.
+        let rest = self.rest();
+        let mut len = 0;
+        for (i, c) in rest.char_indices() {
+            let exponent_sign = (c == '-' || c == '+') && rest[..i].ends_with('e');
+            if !(c.is_ascii_alphanumeric() || c == '.' || exponent_sign || (i == 0 && c == '-')) {
+                break;
+            }
+            len = i + c.len_utf8();
+        }
+        let token = &rest[..len];
+        let float = if let Some(f) = token.strip_suffix("f32") {
+            f.parse::<f32>().ok().map(|f| Float::F32(f.to_bits()))
+        } else if let Some(f) = token.strip_suffix("f64") {
+            f.parse::<f64>().ok().map(|f| Float::F64(f.to_bits()))
+        } else {
+            None
+        };
+        let Some(float) = float else {
+            return Err(self.error("expected a float"));
+        };
+        self.pos += len;
+        Ok(float)
+    }
+
+    // Parses a constant without its type, like it is printed inside `const<TYPE>(VALUE)`.
+    pub(super) fn parse_untyped_constant(&mut self) -> PResult<Constant> {
+        let rest = self.rest();
+        if rest.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+            // Integers are printed without suffix here, floats always have one.
+            if let Some(float) = self.attempt(|p| p.parse_float()) {
+                return Ok(Constant::Float(float));
+            }
+            return Ok(Constant::Int(self.parse_int()?));
+        }
+
+        let ident = self.peek_ident();
+        match ident.as_str() {
+            "true" | "false" => {
+                self.pos += ident.len();
+                Ok(Constant::Bool(ident == "true"))
+            }
+            "f32_from_bits" | "f64_from_bits" => Ok(Constant::Float(self.parse_float()?)),
+            "global" => Ok(Constant::GlobalPointer(self.parse_relocation()?)),
+            "vtable" => Ok(Constant::VTablePointer(self.parse_vtable_name()?)),
+            "nullptr" => {
+                self.pos += ident.len();
+                Ok(Constant::PointerWithoutProvenance(Int::ZERO))
+            }
+            "invalid_ptr" => {
+                self.pos += ident.len();
+                self.expect("(")?;
+                let addr = self.parse_int()?;
+                self.expect(")")?;
+                Ok(Constant::PointerWithoutProvenance(addr))
+            }
+            _ if ident.starts_with('f') && ident[1..].parse::<u32>().is_ok() =>
+                Ok(Constant::FnPointer(self.parse_fn_name()?)),
+            _ => Err(self.error("expected a constant")),
+        }
+    }
+}
+
+fn un_op(operator: UnOp, operand: ValueExpr) -> ValueExpr {
+    ValueExpr::UnOp { operator, operand: GcCow::new(operand) }
+}
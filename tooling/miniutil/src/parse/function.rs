@@ -0,0 +1,366 @@
+use super::*;
+
+impl<'a> Parser<'a> {
+    // Parses a function like `[start ][extern "C" ]fn f0(_1, _2) -> _0 { .. }`.
+    // The `start` keyword has already been consumed.
+    pub(super) fn parse_function(&mut self) -> PResult<(FnName, Function)> {
+        let calling_convention = self.parse_calling_convention()?;
+        self.expect_keyword("fn")?;
+        let fn_name = self.parse_fn_name()?;
+        self.expect("(")?;
+        let args = self.parse_list(")", |p| p.parse_local_name())?;
+        self.expect("->")?;
+        let ret = self.parse_local_name()?;
+        self.expect("{")?;
+
+        // Parse locals
+        let mut locals = Map::new();
+        while self.eat_keyword("let") {
+            let pos = self.token_start();
+            let local = self.parse_local_name()?;
+            self.expect(":")?;
+            let ty = self.parse_type()?;
+            self.expect(";")?;
+            locals.try_insert(local, ty).map_err(|_| self.error_at(pos, "duplicate local"))?;
+        }
+
+        // Parse basic blocks
+        let mut blocks = Map::new();
+        let mut start = None;
+        while !self.eat("}") {
+            let pos = self.token_start();
+            let is_start = self.eat_keyword("start");
+            let bb_name = self.parse_bb_name()?;
+            self.expect(":")?;
+            let bb = self.parse_bb()?;
+            if is_start {
+                if start.is_some() {
+                    return Err(self.error_at(pos, "more than one start block"));
+                }
+                start = Some(bb_name);
+            }
+            blocks
+                .try_insert(bb_name, bb)
+                .map_err(|_| self.error_at(pos, "duplicate basic block"))?;
+        }
+        let Some(start) = start else {
+            return Err(self.error("function has no start block"));
+        };
+
+        let f = Function {
+            locals,
+            args: args.into_iter().collect(),
+            ret,
+            calling_convention,
+            blocks,
+            start,
+        };
+        Ok((fn_name, f))
+    }
+
+    fn parse_calling_convention(&mut self) -> PResult<CallingConvention> {
+        if !self.eat_keyword("extern") {
+            return Ok(CallingConvention::Rust);
+        }
+        self.expect("\"")?;
+        let conv = match self.parse_ident()?.as_str() {
+            "C" => CallingConvention::C,
+            "Rust" => CallingConvention::Rust,
+            _ => return Err(self.error("unknown calling convention")),
+        };
+        self.expect("\"")?;
+        Ok(conv)
+    }
+
+    // Parses the statements and the terminator of a basic block.
+    fn parse_bb(&mut self) -> PResult<BasicBlock> {
+        let mut statements = List::new();
+        loop {
+            if let Some(terminator) = self.parse_terminator()? {
+                return Ok(BasicBlock { statements, terminator });
+            }
+            statements.push(self.parse_statement()?);
+        }
+    }
+
+    fn parse_statement(&mut self) -> PResult<Statement> {
+        let ident = self.peek_ident();
+        let st = match ident.as_str() {
+            "_" => {
+                self.pos += ident.len();
+                self.expect("=")?;
+                Statement::PlaceMention(self.parse_place_expr()?)
+            }
+            "discriminant" => {
+                self.pos += ident.len();
+                self.expect("(")?;
+                let destination = self.parse_place_expr()?;
+                self.expect(")")?;
+                self.expect("=")?;
+                let value = self.parse_int()?;
+                Statement::SetDiscriminant { destination, value }
+            }
+            "validate" => {
+                self.pos += ident.len();
+                self.expect("(")?;
+                let place = self.parse_place_expr()?;
+                self.expect(",")?;
+                let fn_entry = if self.eat_keyword("true") {
+                    true
+                } else {
+                    self.expect_keyword("false")?;
+                    false
+                };
+                self.expect(")")?;
+                Statement::Validate { place, fn_entry }
+            }
+            "deinit" => {
+                self.pos += ident.len();
+                Statement::Deinit { place: self.parse_paren_place_expr()? }
+            }
+            "storage_live" | "storage_dead" => {
+                self.pos += ident.len();
+                self.expect("(")?;
+                let local = self.parse_local_name()?;
+                self.expect(")")?;
+                match ident.as_str() {
+                    "storage_live" => Statement::StorageLive(local),
+                    _ => Statement::StorageDead(local),
+                }
+            }
+            _ => {
+                let destination = self.parse_place_expr()?;
+                self.expect("=")?;
+                let source = self.parse_value_expr()?;
+                Statement::Assign { destination, source }
+            }
+        };
+        self.expect(";")?;
+        Ok(st)
+    }
+
+    // Parses a terminator, or returns `None` if the next line is a statement.
+    fn parse_terminator(&mut self) -> PResult<Option<Terminator>> {
+        let ident = self.peek_ident();
+        let terminator = match ident.as_str() {
+            "goto" => {
+                self.pos += ident.len();
+                self.expect("->")?;
+                Terminator::Goto(self.parse_bb_name()?)
+            }
+            "switch" => {
+                self.pos += ident.len();
+                self.expect("(")?;
+                let value = self.parse_value_expr()?;
+                self.expect(")")?;
+                self.expect("->")?;
+                self.expect("[")?;
+                let mut cases = Map::new();
+                let fallback = loop {
+                    if self.eat_keyword("otherwise") {
+                        self.expect(":")?;
+                        let fallback = self.parse_bb_name()?;
+                        break fallback;
+                    }
+                    let pos = self.token_start();
+                    let case = self.parse_int()?;
+                    self.expect(":")?;
+                    let successor = self.parse_bb_name()?;
+                    self.expect(",")?;
+                    cases
+                        .try_insert(case, successor)
+                        .map_err(|_| self.error_at(pos, "duplicate case"))?;
+                };
+                self.expect("]")?;
+                Terminator::Switch { value, cases, fallback }
+            }
+            "unreachable" => {
+                self.pos += ident.len();
+                Terminator::Unreachable
+            }
+            "return" => {
+                self.pos += ident.len();
+                Terminator::Return
+            }
+            "resume_unwind" => {
+                self.pos += ident.len();
+                Terminator::ResumeUnwind
+            }
+            _ => {
+                // Otherwise, this is an assignment, or a call that looks like one.
+                let Some(terminator) = self.attempt(|p| p.parse_call()) else {
+                    return Ok(None);
+                };
+                terminator
+            }
+        };
+        self.expect(";")?;
+        Ok(Some(terminator))
+    }
+
+    // Parses `ret = callee(args) -> bbN, unwind -> bbM`, where `callee` is a function or an intrinsic.
+    fn parse_call(&mut self) -> PResult<Terminator> {
+        let ret = self.parse_place_expr()?;
+        self.expect("=")?;
+
+        let terminator = if let Some(intrinsic) = self.parse_intrinsic()? {
+            self.expect("(")?;
+            let arguments = self.parse_list(")", |p| p.parse_value_expr())?;
+            let (next_block, unwind_block) = self.parse_call_targets()?;
+            Terminator::Intrinsic {
+                intrinsic,
+                arguments: arguments.into_iter().collect(),
+                ret,
+                next_block,
+                unwind_block,
+            }
+        } else {
+            let calling_convention = self.parse_calling_convention()?;
+            let callee = self.parse_value_expr()?;
+            self.expect("(")?;
+            let arguments = self.parse_list(")", |p| {
+                if p.eat_keyword("by") {
+                    p.expect("-")?;
+                    p.expect_keyword("value")?;
+                    p.expect("(")?;
+                    let value = p.parse_value_expr()?;
+                    p.expect(")")?;
+                    Ok(ArgumentExpr::ByValue(value))
+                } else {
+                    p.expect_keyword("in")?;
+                    p.expect("-")?;
+                    p.expect_keyword("place")?;
+                    let place = p.parse_paren_place_expr()?;
+                    Ok(ArgumentExpr::InPlace(place))
+                }
+            })?;
+            let (next_block, unwind_block) = self.parse_call_targets()?;
+            Terminator::Call {
+                callee,
+                calling_convention,
+                arguments: arguments.into_iter().collect(),
+                ret,
+                next_block,
+                unwind_block,
+            }
+        };
+        // Make sure this is the end of the terminator, so that failing to parse a call can fall back to an assignment.
+        if !self.peek(";") {
+            return Err(self.error("expected `;`"));
+        }
+        Ok(terminator)
+    }
+
+    // Parses the optional `-> bbN` and `, unwind -> bbM` after a call.
+    fn parse_call_targets(&mut self) -> PResult<(Option<BbName>, Option<BbName>)> {
+        let next_block = if self.eat("->") { Some(self.parse_bb_name()?) } else { None };
+        let unwind_block = if self.eat(",") {
+            self.expect_keyword("unwind")?;
+            self.expect("->")?;
+            Some(self.parse_bb_name()?)
+        } else {
+            None
+        };
+        Ok((next_block, unwind_block))
+    }
+
+    // Parses the name of an intrinsic including its generic arguments, if the next identifier is one.
+    fn parse_intrinsic(&mut self) -> PResult<Option<IntrinsicOp>> {
+        let ident = self.peek_ident();
+        let intrinsic = match ident.as_str() {
+            "assume" => IntrinsicOp::Assume,
+            "exit" => IntrinsicOp::Exit,
+            "panic" => IntrinsicOp::Panic,
+            "abort" => IntrinsicOp::Abort,
+            "catch_unwind" => IntrinsicOp::CatchUnwind,
+            "print" => IntrinsicOp::PrintStdout,
+            "eprint" => IntrinsicOp::PrintStderr,
+            "allocate" => IntrinsicOp::Allocate,
+            "deallocate" => IntrinsicOp::Deallocate,
+            "reallocate" => IntrinsicOp::Reallocate,
+            "spawn" => IntrinsicOp::Spawn,
+            "join" => IntrinsicOp::Join,
+            "raw_eq" => IntrinsicOp::RawEq,
+            "lock_acquire" => IntrinsicOp::Lock(IntrinsicLockOp::Acquire),
+            "lock_create" => IntrinsicOp::Lock(IntrinsicLockOp::Create),
+            "lock_release" => IntrinsicOp::Lock(IntrinsicLockOp::Release),
+            "pointer_expose_provenance" => IntrinsicOp::PointerExposeProvenance,
+            "pointer_with_exposed_provenance" => IntrinsicOp::PointerWithExposedProvenance,
+            "atomic_store" | "atomic_load" | "atomic_fence" | "atomic_compare_exchange" => {
+                self.pos += ident.len();
+                self.expect("<")?;
+                let ordering = self.parse_ordering()?;
+                let intrinsic = match ident.as_str() {
+                    "atomic_store" => IntrinsicOp::AtomicStore(ordering),
+                    "atomic_load" => IntrinsicOp::AtomicLoad(ordering),
+                    "atomic_fence" => IntrinsicOp::AtomicFence(ordering),
+                    _ => {
+                        self.expect(",")?;
+                        let failure = self.parse_ordering()?;
+                        IntrinsicOp::AtomicCompareExchange { success: ordering, failure }
+                    }
+                };
+                self.expect(">")?;
+                return Ok(Some(intrinsic));
+            }
+            _ if ident.starts_with("atomic_fetch_") => {
+                let pos = self.token_start();
+                self.pos += ident.len();
+                let Some(binop) = parse_fetch(&ident["atomic_fetch_".len()..]) else {
+                    return Err(self.error_at(pos, "unknown atomic operation"));
+                };
+                self.expect("<")?;
+                let ordering = self.parse_ordering()?;
+                self.expect(">")?;
+                return Ok(Some(IntrinsicOp::AtomicFetchAndOp(binop, ordering)));
+            }
+            _ => return Ok(None),
+        };
+        self.pos += ident.len();
+        Ok(Some(intrinsic))
+    }
+
+    fn parse_ordering(&mut self) -> PResult<MemoryOrdering> {
+        let ordering = match self.parse_ident()?.as_str() {
+            "Relaxed" => MemoryOrdering::Relaxed,
+            "Acquire" => MemoryOrdering::Acquire,
+            "Release" => MemoryOrdering::Release,
+            "AcqRel" => MemoryOrdering::AcqRel,
+            "SeqCst" => MemoryOrdering::SeqCst,
+            _ => return Err(self.error("expected a memory ordering")),
+        };
+        Ok(ordering)
+    }
+
+    fn parse_bb_name(&mut self) -> PResult<BbName> {
+        Ok(BbName(self.parse_prefixed_name("bb")?))
+    }
+
+    pub(super) fn parse_fn_name(&mut self) -> PResult<FnName> {
+        Ok(FnName(self.parse_prefixed_name("f")?))
+    }
+}
+
+fn parse_fetch(op: &str) -> Option<IntBinOp> {
+    use IntBinOp as B;
+    let binop = match op {
+        "add" => B::Add,
+        "sub" => B::Sub,
+        "mul" => B::Mul,
+        "div" => B::Div,
+        "rem" => B::Rem,
+        "shl" => B::Shl,
+        "shr" => B::Shr,
+        "bit_and" => B::BitAnd,
+        "bit_or" => B::BitOr,
+        "bit_xor" => B::BitXor,
+        "add_unchecked" => B::AddUnchecked,
+        "sub_unchecked" => B::SubUnchecked,
+        "mul_unchecked" => B::MulUnchecked,
+        "div_exact" => B::DivExact,
+        "shl_unchecked" => B::ShlUnchecked,
+        "shr_unchecked" => B::ShrUnchecked,
+        _ => return None,
+    };
+    Some(binop)
+}
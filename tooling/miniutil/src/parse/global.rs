@@ -0,0 +1,81 @@
+use super::*;
+
+impl<'a> Parser<'a> {
+    // Parses a global like `global(0) { bytes = [00 __], align = 1 bytes, at byte 0: global(1), }`.
+    // Thread-local globals have `thread_local,` after the alignment.
+    // Function pointers like `at byte 8: f1,` come after the relocations.
+    pub(super) fn parse_global(&mut self) -> PResult<(GlobalName, Global)> {
+        let name = self.parse_global_name()?;
+        self.expect("{")?;
+
+        self.expect_keyword("bytes")?;
+        self.expect("=")?;
+        self.expect("[")?;
+        let mut bytes = List::new();
+        while !self.eat("]") {
+            if self.eat("__") {
+                bytes.push(None);
+                continue;
+            }
+            let pos = self.token_start();
+            let byte = self.parse_ident()?;
+            let byte = u8::from_str_radix(&byte, 16)
+                .ok()
+                .filter(|_| byte.len() == 2)
+                .ok_or_else(|| self.error_at(pos, "expected a byte in hexadecimal"))?;
+            bytes.push(Some(byte));
+        }
+        self.expect(",")?;
+
+        self.expect_keyword("align")?;
+        self.expect("=")?;
+        let align = self.parse_align()?;
+        self.expect_keyword("bytes")?;
+        self.expect(",")?;
+
+        let thread_local = self.eat_keyword("thread_local");
+        if thread_local {
+            self.expect(",")?;
+        }
+
+        let mut relocations = List::new();
+        let mut fn_pointers = List::new();
+        while self.eat_keyword("at") {
+            self.expect_keyword("byte")?;
+            let offset = self.parse_size()?;
+            self.expect(":")?;
+            if self.peek_keyword("global") {
+                relocations.push((offset, self.parse_relocation()?));
+            } else {
+                fn_pointers.push((offset, self.parse_fn_name()?));
+            }
+            self.expect(",")?;
+        }
+        self.expect("}")?;
+
+        Ok((name, Global { bytes, relocations, fn_pointers, align, thread_local }))
+    }
+
+    // Parses a relocation like `global(0)` or `global(0) + 8`.
+    pub(super) fn parse_relocation(&mut self) -> PResult<Relocation> {
+        let name = self.parse_global_name()?;
+        // An integer constant with a type suffix is not an offset, but the right operand of an addition.
+        let offset = self
+            .attempt(|p| {
+                p.expect("+")?;
+                let offset = p.parse_size()?;
+                if p.src[p.pos..]
+                    .starts_with(|c: char| c == '_' || c == '.' || c.is_ascii_alphanumeric())
+                {
+                    return Err(p.error("not an offset"));
+                }
+                Ok(offset)
+            })
+            .unwrap_or(Size::ZERO);
+        Ok(Relocation { name, offset })
+    }
+
+    pub(super) fn parse_global_name(&mut self) -> PResult<GlobalName> {
+        Ok(GlobalName(self.parse_paren_name("global")?))
+    }
+}
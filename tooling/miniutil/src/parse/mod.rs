@@ -0,0 +1,331 @@
+//! This module parses the textual syntax printed by `fmt::fmt_program` back into a `Program`.
+//!
+//! The parser is a hand-written recursive descent parser working directly on the characters of the input.
+//! It accepts exactly the syntax that `fmt_program` emits; whitespace between tokens is ignored.
+
+use crate::*;
+
+use std::collections::HashMap;
+
+// The parser methods are split into modules in the same way as the `fmt` module.
+mod expr;
+mod function;
+mod global;
+mod ty;
+mod vtable;
+
+/// An error that occurred while parsing, pointing to the line and column (both starting at 1) where it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub msg: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "parse error at {}:{}: {}", self.line, self.column, self.msg)
+    }
+}
+
+type PResult<T> = Result<T, ParseError>;
+
+// Parse a program from its textual representation.
+pub fn parse_program(src: &str) -> PResult<Program> {
+    let mut p = Parser::new(src);
+    p.scan_comptypes();
+
+    let mut functions: Map<FnName, Function> = Map::new();
+    let mut globals: Map<GlobalName, Global> = Map::new();
+    let mut traits: Map<TraitName, Set<TraitMethodName>> = Map::new();
+    let mut vtables: Map<VTableName, VTable> = Map::new();
+    let mut start = None;
+
+    while !p.at_end() {
+        let item_start = p.token_start();
+        let ident = p.peek_ident();
+        match ident.as_str() {
+            "tuple" | "union" | "enum" => {
+                p.parse_comptype_def()?;
+            }
+            "global" => {
+                let (name, global) = p.parse_global()?;
+                globals
+                    .try_insert(name, global)
+                    .map_err(|_| p.error_at(item_start, "duplicate global"))?;
+            }
+            "trait" => {
+                let (name, methods) = p.parse_trait()?;
+                traits
+                    .try_insert(name, methods)
+                    .map_err(|_| p.error_at(item_start, "duplicate trait"))?;
+            }
+            "vtable" => {
+                let (name, vtable) = p.parse_vtable()?;
+                vtables
+                    .try_insert(name, vtable)
+                    .map_err(|_| p.error_at(item_start, "duplicate vtable"))?;
+            }
+            "start" | "extern" | "fn" => {
+                let is_start = p.eat_keyword("start");
+                // `start fN;` names a start function that is not part of the program.
+                if is_start && !p.peek_keyword("fn") && !p.peek_keyword("extern") {
+                    let fn_name = p.parse_fn_name()?;
+                    p.expect(";")?;
+                    p.set_start(&mut start, fn_name, item_start)?;
+                    continue;
+                }
+                let (fn_name, f) = p.parse_function()?;
+                if is_start {
+                    p.set_start(&mut start, fn_name, item_start)?;
+                }
+                functions
+                    .try_insert(fn_name, f)
+                    .map_err(|_| p.error_at(item_start, "duplicate function"))?;
+            }
+            _ => return Err(p.error("expected a type, function, global, trait or vtable")),
+        }
+    }
+
+    let Some(start) = start else {
+        return Err(p.error("program has no start function"));
+    };
+
+    Ok(Program { functions, start, globals, traits, vtables })
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    // The current byte offset into `src`.
+    pos: usize,
+    // The positions of all composite type definitions, by their index.
+    comptype_defs: HashMap<u32, usize>,
+    // The composite types that have been parsed so far.
+    // Composite types may be used before their definition, so they are parsed on first use.
+    comptypes: HashMap<u32, Type>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Parser { src, pos: 0, comptype_defs: HashMap::new(), comptypes: HashMap::new() }
+    }
+
+    // Records where each composite type is defined.
+    // These definitions are always at the beginning of a line.
+    fn scan_comptypes(&mut self) {
+        let mut line_start = 0;
+        for line in self.src.split_inclusive('\n') {
+            let mut p = Parser::new(self.src);
+            p.pos = line_start;
+            if p.eat_keyword("tuple") || p.eat_keyword("union") || p.eat_keyword("enum") {
+                if let Ok(idx) = p.parse_comptype_index() {
+                    self.comptype_defs.entry(idx).or_insert(line_start);
+                }
+            }
+            line_start += line.len();
+        }
+    }
+
+    fn set_start(&self, start: &mut Option<FnName>, fn_name: FnName, pos: usize) -> PResult<()> {
+        if start.is_some() {
+            return Err(self.error_at(pos, "more than one start function"));
+        }
+        *start = Some(fn_name);
+        Ok(())
+    }
+
+    /////////////////////
+    // errors
+    /////////////////////
+
+    fn error(&self, msg: &str) -> ParseError {
+        self.error_at(self.pos, msg)
+    }
+
+    fn error_at(&self, pos: usize, msg: &str) -> ParseError {
+        let before = &self.src[..pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+        ParseError { line, column, msg: msg.to_string() }
+    }
+
+    /////////////////////
+    // tokens
+    /////////////////////
+
+    fn skip_ws(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    // Skips whitespace and returns the position of the next token.
+    fn token_start(&mut self) -> usize {
+        self.skip_ws();
+        self.pos
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_ws();
+        self.pos == self.src.len()
+    }
+
+    // The remaining input, starting at the next token.
+    fn rest(&mut self) -> &'a str {
+        self.skip_ws();
+        &self.src[self.pos..]
+    }
+
+    fn peek(&mut self, s: &str) -> bool {
+        self.rest().starts_with(s)
+    }
+
+    // Consumes `s` if the input continues with it.
+    fn eat(&mut self, s: &str) -> bool {
+        if self.peek(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> PResult<()> {
+        if self.eat(s) { Ok(()) } else { Err(self.error(&format!("expected `{s}`"))) }
+    }
+
+    // Returns the identifier at the current position, without consuming it.
+    // Returns the empty string if there is none.
+    fn peek_ident(&mut self) -> String {
+        let rest = self.rest();
+        let len = rest.find(|c: char| !is_ident_char(c)).unwrap_or(rest.len());
+        rest[..len].to_string()
+    }
+
+    fn parse_ident(&mut self) -> PResult<String> {
+        let ident = self.peek_ident();
+        if ident.is_empty() {
+            return Err(self.error("expected an identifier"));
+        }
+        self.pos += ident.len();
+        Ok(ident)
+    }
+
+    fn peek_keyword(&mut self, keyword: &str) -> bool {
+        self.peek_ident() == keyword
+    }
+
+    // Consumes `keyword` if it is the next identifier.
+    // Unlike `eat`, this does not consume a prefix of a longer identifier.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.pos += keyword.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> PResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{keyword}`")))
+        }
+    }
+
+    // Runs `f`, and resets the position if it fails.
+    fn attempt<T>(&mut self, f: impl FnOnce(&mut Self) -> PResult<T>) -> Option<T> {
+        let pos = self.pos;
+        match f(self) {
+            Ok(t) => Some(t),
+            Err(_) => {
+                self.pos = pos;
+                None
+            }
+        }
+    }
+
+    // Parses a comma-separated list of elements, ending with `end`.
+    // A trailing comma is allowed.
+    fn parse_list<T>(
+        &mut self,
+        end: &str,
+        mut f: impl FnMut(&mut Self) -> PResult<T>,
+    ) -> PResult<Vec<T>> {
+        let mut elems = Vec::new();
+        while !self.eat(end) {
+            elems.push(f(self)?);
+            if !self.eat(",") {
+                self.expect(end)?;
+                break;
+            }
+        }
+        Ok(elems)
+    }
+
+    /////////////////////
+    // numbers
+    /////////////////////
+
+    // Parses a decimal number, which may be negative.
+    fn parse_int(&mut self) -> PResult<Int> {
+        let rest = self.rest();
+        let neg = rest.starts_with('-');
+        let digits = &rest[neg as usize..];
+        let len = digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len());
+        let s = &rest[..neg as usize + len];
+        let int = if neg {
+            s.parse::<i128>().ok().map(Int::from)
+        } else {
+            s.parse::<u128>().ok().map(Int::from)
+        };
+        let Some(int) = int else {
+            return Err(self.error("expected an integer"));
+        };
+        self.pos += s.len();
+        Ok(int)
+    }
+
+    fn parse_u32(&mut self) -> PResult<u32> {
+        let int = self.parse_int()?;
+        int.try_to_usize()
+            .and_then(|int| u32::try_from(int).ok())
+            .ok_or_else(|| self.error("integer out of range"))
+    }
+
+    fn parse_size(&mut self) -> PResult<Size> {
+        let pos = self.token_start();
+        let int = self.parse_int()?;
+        Size::from_bytes(int).ok_or_else(|| self.error_at(pos, "invalid size"))
+    }
+
+    fn parse_align(&mut self) -> PResult<Align> {
+        let pos = self.token_start();
+        let int = self.parse_int()?;
+        Align::from_bytes(int).ok_or_else(|| self.error_at(pos, "invalid alignment"))
+    }
+
+    // Parses a name of the form `{prefix}N`, like `bb3` or `_2`.
+    fn parse_prefixed_name(&mut self, prefix: &str) -> PResult<Name> {
+        let pos = self.token_start();
+        let ident = self.parse_ident()?;
+        let idx = ident.strip_prefix(prefix).and_then(|idx| idx.parse::<u32>().ok());
+        let Some(idx) = idx else {
+            return Err(self.error_at(pos, &format!("expected `{prefix}` followed by a number")));
+        };
+        Ok(Name::from_internal(idx))
+    }
+
+    // Parses a name of the form `{keyword}(N)`, like `global(3)`.
+    fn parse_paren_name(&mut self, keyword: &str) -> PResult<Name> {
+        self.expect_keyword(keyword)?;
+        self.expect("(")?;
+        let idx = self.parse_u32()?;
+        self.expect(")")?;
+        Ok(Name::from_internal(idx))
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
@@ -0,0 +1,383 @@
+use super::*;
+
+impl<'a> Parser<'a> {
+    pub(super) fn parse_type(&mut self) -> PResult<Type> {
+        let pos = self.token_start();
+        if self.peek("&") || self.peek("*") {
+            return Ok(Type::Ptr(self.parse_ptr_type()?));
+        }
+        if self.eat("[") {
+            let elem = self.parse_type()?;
+            if self.eat(";") {
+                let count = self.parse_int()?;
+                self.expect("]")?;
+                return Ok(Type::Array { elem: GcCow::new(elem), count });
+            }
+            self.expect("]")?;
+            return Ok(Type::Slice { elem: GcCow::new(elem) });
+        }
+        if self.eat("(") {
+            let ty = self.parse_type()?;
+            self.expect(")")?;
+            return Ok(ty);
+        }
+
+        let ident = self.peek_ident();
+        match ident.as_str() {
+            "bool" => {
+                self.pos += ident.len();
+                Ok(Type::Bool)
+            }
+            "char" => {
+                self.pos += ident.len();
+                Ok(Type::Char)
+            }
+            "f32" | "f64" => Ok(Type::Float(self.parse_float_type()?)),
+            "Box" | "fn" | "vtable" => Ok(Type::Ptr(self.parse_ptr_type()?)),
+            "dyn" => {
+                self.pos += ident.len();
+                Ok(Type::TraitObject(self.parse_trait_name()?))
+            }
+            _ if ident.starts_with('T') => {
+                let idx = self.parse_comptype_index()?;
+                self.comptype(idx, pos)
+            }
+            _ if ident.starts_with('u') || ident.starts_with('i') =>
+                Ok(Type::Int(self.parse_int_type()?)),
+            _ => Err(self.error("expected a type")),
+        }
+    }
+
+    pub(super) fn parse_int_type(&mut self) -> PResult<IntType> {
+        let pos = self.token_start();
+        let ident = self.parse_ident()?;
+        let (signed, bits) = ident.split_at(1);
+        let signed = match signed {
+            "u" => Unsigned,
+            "i" => Signed,
+            _ => return Err(self.error_at(pos, "expected an integer type")),
+        };
+        let size = bits
+            .parse::<u32>()
+            .ok()
+            .filter(|bits| bits % 8 == 0)
+            .and_then(|bits| Size::from_bytes(bits / 8));
+        let Some(size) = size else {
+            return Err(self.error_at(pos, "expected an integer type"));
+        };
+        Ok(IntType { signed, size })
+    }
+
+    pub(super) fn parse_float_type(&mut self) -> PResult<FloatType> {
+        if self.eat_keyword("f32") {
+            Ok(FloatType::F32)
+        } else if self.eat_keyword("f64") {
+            Ok(FloatType::F64)
+        } else {
+            Err(self.error("expected a float type"))
+        }
+    }
+
+    pub(super) fn parse_ptr_type(&mut self) -> PResult<PtrType> {
+        if self.eat("&") {
+            let mutbl =
+                if self.eat_keyword("mut") { Mutability::Mutable } else { Mutability::Immutable };
+            let pointee = self.parse_pointee_info()?;
+            return Ok(PtrType::Ref { mutbl, pointee });
+        }
+        if self.eat("*") {
+            self.expect_keyword("raw")?;
+            self.expect("(")?;
+            let meta_kind = self.parse_meta_kind()?;
+            self.expect(")")?;
+            return Ok(PtrType::Raw { meta_kind });
+        }
+        if self.eat_keyword("Box") {
+            self.expect("<")?;
+            let pointee = self.parse_pointee_info()?;
+            self.expect(">")?;
+            return Ok(PtrType::Box { pointee });
+        }
+        if self.eat_keyword("fn") {
+            self.expect("(")?;
+            self.expect(")")?;
+            return Ok(PtrType::FnPtr);
+        }
+        if self.eat_keyword("vtable") {
+            self.expect("<")?;
+            let trait_name = self.parse_trait_name()?;
+            self.expect(">")?;
+            return Ok(PtrType::VTablePtr(trait_name));
+        }
+        Err(self.error("expected a pointer type"))
+    }
+
+    fn parse_meta_kind(&mut self) -> PResult<PointerMetaKind> {
+        if self.eat_keyword("thin") {
+            return Ok(PointerMetaKind::None);
+        }
+        self.expect_keyword("meta")?;
+        self.expect("=")?;
+        if self.eat_keyword("len") {
+            return Ok(PointerMetaKind::ElementCount);
+        }
+        self.expect_keyword("vtable")?;
+        self.expect("<")?;
+        let trait_name = self.parse_trait_name()?;
+        self.expect(">")?;
+        Ok(PointerMetaKind::VTablePointer(trait_name))
+    }
+
+    // Parses `pointee_info(meta, size=.., align=..[, uninhabited][, freeze][, unpin][, unsafe_cells=[..]])`.
+    pub(super) fn parse_pointee_info(&mut self) -> PResult<PointeeInfo> {
+        self.expect_keyword("pointee_info")?;
+        self.expect("(")?;
+        let meta_kind = self.parse_meta_kind()?;
+        self.expect(",")?;
+
+        self.expect_keyword("size")?;
+        self.expect("=")?;
+        let size_pos = self.token_start();
+        let size = if self.eat_keyword("vtable") {
+            self.expect(".")?;
+            self.expect_keyword("size")?;
+            None
+        } else {
+            let size = self.parse_size()?;
+            if self.eat("*") {
+                self.expect_keyword("len")?;
+                Some((size, true))
+            } else {
+                Some((size, false))
+            }
+        };
+        let size = match (meta_kind, size) {
+            (PointerMetaKind::None, Some((size, false))) => SizeStrategy::Sized(size),
+            (PointerMetaKind::ElementCount, Some((size, true))) => SizeStrategy::Slice(size),
+            (PointerMetaKind::VTablePointer(trait_name), None) => SizeStrategy::VTable(trait_name),
+            _ => return Err(self.error_at(size_pos, "size does not match the pointer metadata")),
+        };
+        self.expect(",")?;
+
+        self.expect_keyword("align")?;
+        self.expect("=")?;
+        let align = self.parse_align()?;
+
+        let mut pointee = PointeeInfo {
+            size,
+            align,
+            inhabited: true,
+            freeze: false,
+            unpin: false,
+            unsafe_cells: List::new(),
+            byte_slice: false,
+        };
+        while self.eat(",") {
+            if self.eat_keyword("uninhabited") {
+                pointee.inhabited = false;
+            } else if self.eat_keyword("freeze") {
+                pointee.freeze = true;
+            } else if self.eat_keyword("unpin") {
+                pointee.unpin = true;
+            } else if self.eat_keyword("unsafe_cells") {
+                self.expect("=")?;
+                self.expect("[")?;
+                let cells = self.parse_list("]", |p| {
+                    let start = p.parse_size()?;
+                    p.expect("..")?;
+                    let pos = p.token_start();
+                    let end = p.parse_size()?;
+                    let size = Size::from_bytes(end.bytes() - start.bytes())
+                        .ok_or_else(|| p.error_at(pos, "range ends before it starts"))?;
+                    Ok((start, size))
+                })?;
+                pointee.unsafe_cells = cells.into_iter().collect();
+            } else if self.eat_keyword("byte_slice") {
+                pointee.byte_slice = true;
+            } else {
+                return Err(self.error("expected a pointee property"));
+            }
+        }
+        self.expect(")")?;
+
+        Ok(pointee)
+    }
+
+    /////////////////////
+    // composite types
+    /////////////////////
+
+    // Parses `T{idx}`.
+    pub(super) fn parse_comptype_index(&mut self) -> PResult<u32> {
+        Ok(self.parse_prefixed_name("T")?.get_internal())
+    }
+
+    // Returns the composite type with the given index, parsing its definition if necessary.
+    fn comptype(&mut self, idx: u32, pos: usize) -> PResult<Type> {
+        if let Some(ty) = self.comptypes.get(&idx) {
+            return Ok(*ty);
+        }
+        let Some(def_pos) = self.comptype_defs.get(&idx).copied() else {
+            return Err(self.error_at(pos, "undefined composite type"));
+        };
+        let pos = self.pos;
+        self.pos = def_pos;
+        // Remove the definition while parsing it, so that a type containing itself is an error instead of a loop.
+        self.comptype_defs.remove(&idx);
+        let res = self.parse_comptype_def();
+        self.comptype_defs.insert(idx, def_pos);
+        self.pos = pos;
+        res
+    }
+
+    // Parses the definition of a composite type, e.g.
+    // `tuple T0 (8 bytes, aligned 4 bytes) { at byte 0: u32, at byte 4: u32, }`.
+    pub(super) fn parse_comptype_def(&mut self) -> PResult<Type> {
+        let keyword = self.parse_ident()?;
+        let idx = self.parse_comptype_index()?;
+        self.expect("(")?;
+        let size = self.parse_size()?;
+        self.expect_keyword("bytes")?;
+        self.expect(",")?;
+        self.expect_keyword("aligned")?;
+        let align = self.parse_align()?;
+        self.expect_keyword("bytes")?;
+        self.expect(")")?;
+        self.expect("{")?;
+
+        let ty = match keyword.as_str() {
+            "tuple" => {
+                let fields = self.parse_fields()?;
+                Type::Tuple { fields, size, align }
+            }
+            "union" => {
+                let fields = self.parse_fields()?;
+                let mut chunks = List::new();
+                while self.eat_keyword("chunk") {
+                    self.expect("(")?;
+                    self.expect_keyword("at")?;
+                    self.expect("=")?;
+                    let offset = self.parse_size()?;
+                    self.expect(",")?;
+                    self.expect_keyword("size")?;
+                    self.expect("=")?;
+                    let size = self.parse_size()?;
+                    self.expect(")")?;
+                    self.expect(",")?;
+                    chunks.push((offset, size));
+                }
+                Type::Union { fields, chunks, size, align }
+            }
+            "enum" => {
+                self.expect_keyword("Discriminant")?;
+                self.expect(":")?;
+                let discriminant_ty = self.parse_int_type()?;
+                let mut variants = Map::new();
+                while self.eat_keyword("Variant") {
+                    let pos = self.token_start();
+                    let discriminant = self.parse_int()?;
+                    self.expect(":")?;
+                    let ty = self.parse_type()?;
+                    let tagger = self.parse_tagger()?;
+                    variants
+                        .try_insert(discriminant, Variant { ty, tagger })
+                        .map_err(|_| self.error_at(pos, "duplicate variant"))?;
+                }
+                self.expect_keyword("Discriminator")?;
+                self.expect(":")?;
+                let discriminator = self.parse_discriminator()?;
+                Type::Enum { variants, discriminant_ty, discriminator, size, align }
+            }
+            _ => return Err(self.error("expected `tuple`, `union` or `enum`")),
+        };
+        self.expect("}")?;
+
+        self.comptypes.insert(idx, ty);
+        Ok(ty)
+    }
+
+    // Parses fields of the form `at byte 0: u32,`.
+    fn parse_fields(&mut self) -> PResult<Fields> {
+        let mut fields = List::new();
+        while self.eat_keyword("at") {
+            self.expect_keyword("byte")?;
+            let offset = self.parse_size()?;
+            self.expect(":")?;
+            let ty = self.parse_type()?;
+            self.expect(",")?;
+            fields.push((offset, ty));
+        }
+        Ok(fields)
+    }
+
+    // Parses an optional tagger of the form `, tag(at byte 4: u8 = 1)`.
+    fn parse_tagger(&mut self) -> PResult<Map<Offset, (IntType, Int)>> {
+        let mut tagger = Map::new();
+        if !self.eat(",") {
+            return Ok(tagger);
+        }
+        self.expect_keyword("tag")?;
+        self.expect("(")?;
+        let tags = self.parse_list(")", |p| {
+            p.expect_keyword("at")?;
+            p.expect_keyword("byte")?;
+            let offset = p.parse_size()?;
+            p.expect(":")?;
+            let int_ty = p.parse_int_type()?;
+            p.expect("=")?;
+            let value = p.parse_int()?;
+            Ok((offset, (int_ty, value)))
+        })?;
+        for (offset, tag) in tags {
+            tagger.try_insert(offset, tag).map_err(|_| self.error("duplicate tag offset"))?;
+        }
+        Ok(tagger)
+    }
+
+    fn parse_discriminator(&mut self) -> PResult<Discriminator> {
+        if self.eat_keyword("known") {
+            self.expect("(")?;
+            let discriminant = self.parse_int()?;
+            self.expect(")")?;
+            return Ok(Discriminator::Known(discriminant));
+        }
+        if self.eat_keyword("invalid") {
+            return Ok(Discriminator::Invalid);
+        }
+        self.expect_keyword("branch")?;
+        self.expect("(")?;
+        self.expect_keyword("at")?;
+        self.expect_keyword("byte")?;
+        let offset = self.parse_size()?;
+        self.expect(":")?;
+        let value_type = self.parse_int_type()?;
+        self.expect(",")?;
+        self.expect_keyword("fallback")?;
+        self.expect(":")?;
+        let fallback = self.parse_discriminator()?;
+        self.expect(",")?;
+        self.expect("[")?;
+        let children = self.parse_list("]", |p| {
+            let start = p.parse_int()?;
+            p.expect("..")?;
+            let end = p.parse_int()?;
+            p.expect(":")?;
+            let child = p.parse_discriminator()?;
+            Ok(((start, end), child))
+        })?;
+        self.expect(")")?;
+
+        let mut children_map = Map::new();
+        for (range, child) in children {
+            children_map
+                .try_insert(range, child)
+                .map_err(|_| self.error("duplicate branch range"))?;
+        }
+        Ok(Discriminator::Branch {
+            offset,
+            value_type,
+            fallback: GcCow::new(fallback),
+            children: children_map,
+        })
+    }
+}
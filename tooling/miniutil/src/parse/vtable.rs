@@ -0,0 +1,62 @@
+use super::*;
+
+impl<'a> Parser<'a> {
+    // Parses a trait like `trait(0) { m0, m1 }`.
+    pub(super) fn parse_trait(&mut self) -> PResult<(TraitName, Set<TraitMethodName>)> {
+        let name = self.parse_trait_name()?;
+        self.expect("{")?;
+        let methods = self.parse_list("}", |p| p.parse_trait_method_name())?;
+        Ok((name, methods.into_iter().collect()))
+    }
+
+    // Parses a vtable like `vtable(0) for trait(0) { size = 4 bytes, align = 4 bytes, drop = f2, m0 = f1, }`.
+    pub(super) fn parse_vtable(&mut self) -> PResult<(VTableName, VTable)> {
+        let name = self.parse_vtable_name()?;
+        self.expect_keyword("for")?;
+        let trait_name = self.parse_trait_name()?;
+        self.expect("{")?;
+
+        self.expect_keyword("size")?;
+        self.expect("=")?;
+        let size = self.parse_size()?;
+        self.expect_keyword("bytes")?;
+        self.expect(",")?;
+
+        self.expect_keyword("align")?;
+        self.expect("=")?;
+        let align = self.parse_align()?;
+        self.expect_keyword("bytes")?;
+        self.expect(",")?;
+
+        self.expect_keyword("drop")?;
+        self.expect("=")?;
+        let drop = self.parse_fn_name()?;
+        self.expect(",")?;
+
+        let mut methods = Map::new();
+        while !self.eat("}") {
+            let pos = self.token_start();
+            let method = self.parse_trait_method_name()?;
+            self.expect("=")?;
+            let fn_name = self.parse_fn_name()?;
+            self.expect(",")?;
+            methods
+                .try_insert(method, fn_name)
+                .map_err(|_| self.error_at(pos, "duplicate method"))?;
+        }
+
+        Ok((name, VTable { trait_name, size, align, drop, methods }))
+    }
+
+    pub(super) fn parse_trait_name(&mut self) -> PResult<TraitName> {
+        Ok(TraitName(self.parse_paren_name("trait")?))
+    }
+
+    pub(super) fn parse_trait_method_name(&mut self) -> PResult<TraitMethodName> {
+        Ok(TraitMethodName(self.parse_prefixed_name("m")?))
+    }
+
+    pub(super) fn parse_vtable_name(&mut self) -> PResult<VTableName> {
+        Ok(VTableName(self.parse_paren_name("vtable")?))
+    }
+}
@@ -1,3 +1,5 @@
+use crate::schedule::{ReplayDiverged, Schedule, ScheduleTrace, Scheduler};
+use crate::trace::Tracer;
 use crate::{mock_write::MockWrite, *};
 
 /// Run the program and return its TerminationInfo.
@@ -6,11 +8,22 @@ pub fn run_program<M: Memory>(prog: Program) -> TerminationInfo {
     let out = std::io::stdout();
     let err = std::io::stderr();
 
-    let res: Result<!, TerminationInfo> = run::<M>(prog, out, err);
-    match res {
-        Ok(never) => never,
-        Err(t) => t,
-    }
+    run::<M>(prog, out, err, None, None, None, None).unwrap()
+}
+
+/// Run the program with the given schedule and return its TerminationInfo,
+/// together with the choices that were made.
+/// Stdout/stderr are just forwarded to the host.
+pub fn run_program_with_schedule<M: Memory>(
+    prog: Program,
+    schedule: Schedule,
+) -> Result<(TerminationInfo, ScheduleTrace), ReplayDiverged> {
+    let out = std::io::stdout();
+    let err = std::io::stderr();
+
+    let mut scheduler = Scheduler::new(schedule);
+    let info = run::<M>(prog, out, err, Some(&mut scheduler), None, None, None)?;
+    Ok((info, scheduler.into_trace()))
 }
 
 /// Run the program and return stdout as a `Vec<String>`  or a termination info
@@ -19,33 +32,207 @@ pub fn get_stdout<M: Memory>(prog: Program) -> Result<Vec<String>, TerminationIn
     let out = MockWrite::new();
     let err = std::io::stderr();
 
-    let res = run::<M>(prog, out.clone(), err);
-    match res {
-        Ok(never) => never,
-        Err(TerminationInfo::MachineStop) => Ok(out.into_strings()),
-        Err(info) => Err(info),
+    match run::<M>(prog, out.clone(), err, None, None, None, None).unwrap() {
+        TerminationInfo::MachineStop => Ok(out.into_strings()),
+        info => Err(info),
     }
 }
 
-/// Run the program to completion using the given writers for stdout/stderr.
+/// Like `get_stdout`, but with the given schedule.
+/// Also returns the choices that were made.
+pub fn get_stdout_with_schedule<M: Memory>(
+    prog: Program,
+    schedule: Schedule,
+) -> Result<(Result<Vec<String>, TerminationInfo>, ScheduleTrace), ReplayDiverged> {
+    let out = MockWrite::new();
+    let err = std::io::stderr();
+
+    let mut scheduler = Scheduler::new(schedule);
+    let res = match run::<M>(prog, out.clone(), err, Some(&mut scheduler), None, None, None)? {
+        TerminationInfo::MachineStop => Ok(out.into_strings()),
+        info => Err(info),
+    };
+    Ok((res, scheduler.into_trace()))
+}
+
+/// Like `run_program_with_schedule`, but also writes a trace of the execution to `trace`,
+/// in the format described in `crate::trace`.
+pub fn trace_program<M: Memory>(
+    prog: Program,
+    schedule: Schedule,
+    trace: impl std::io::Write + 'static,
+) -> Result<(TerminationInfo, ScheduleTrace), ReplayDiverged> {
+    let out = std::io::stdout();
+    let err = std::io::stderr();
+
+    let mut scheduler = Scheduler::new(schedule);
+    let mut tracer = Tracer::new(prog, trace);
+    let info = run::<M>(prog, out, err, Some(&mut scheduler), Some(&mut tracer), None, None)?;
+    tracer.end(&info);
+    Ok((info, scheduler.into_trace()))
+}
+
+/// A frame on the stack of a thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub func: FnName,
+    pub block: BbName,
+    /// The index of the statement that executes next.
+    /// As for `StackFrameInfo::stmt`, the number of statements in the block refers to the terminator.
+    pub stmt: usize,
+}
+
+/// Where a thread was when the program terminated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backtrace {
+    pub thread: u32,
+    /// Whether this thread caused the termination, i.e., by UB or by panicking.
+    pub active: bool,
+    /// The frames of the thread's stack, innermost frame first.
+    pub frames: Vec<Frame>,
+}
+
+/// Lets a tool inspect the machine after every step of a run.
+pub trait StepHook<M: Memory> {
+    /// Called after every step, with the memory as it was right before that step.
+    /// `info` says how the program terminated, if this step ended it.
+    fn after_step(&mut self, machine: &Machine<M>, before: M, info: Option<&TerminationInfo>);
+}
+
+/// Run the program and return its TerminationInfo, together with a backtrace for each thread
+/// that had not terminated yet. Stdout/stderr are just forwarded to the host.
 ///
-/// We fix `BasicMemory` as a memory for now.
+/// With a `schedule`, this works like `run_program_with_schedule` and also returns the choices
+/// that were made; otherwise the machine makes them itself. With a `trace`, this works like
+/// `trace_program`, which needs a `schedule`. A `hook` is called after every step.
+pub fn run_program_with_backtraces<M: Memory>(
+    prog: Program,
+    schedule: Option<Schedule>,
+    trace: Option<Box<dyn std::io::Write>>,
+    hook: Option<&mut dyn StepHook<M>>,
+) -> Result<(TerminationInfo, Option<ScheduleTrace>, Vec<Backtrace>), ReplayDiverged> {
+    let out = std::io::stdout();
+    let err = std::io::stderr();
+
+    let mut scheduler = schedule.map(Scheduler::new);
+    let mut tracer = trace.map(|trace| Tracer::new(prog, trace));
+    let mut backtraces = Vec::new();
+    let info =
+        run::<M>(prog, out, err, scheduler.as_mut(), tracer.as_mut(), Some(&mut backtraces), hook)?;
+    if let Some(tracer) = &mut tracer {
+        tracer.end(&info);
+    }
+    Ok((info, scheduler.map(Scheduler::into_trace), backtraces))
+}
+
+/// Run the program to completion using the given writers for stdout/stderr.
+/// If there is a `scheduler`, it makes the choices of the run; otherwise the machine does.
+/// This fails if the `scheduler` replays a trace that does not fit the run.
+/// A `tracer` needs a `scheduler`, since it has to know which thread executes the next step.
+/// If there is a `backtraces` vector, it is filled with the stacks of the threads at the end.
+/// If there is a `hook`, it is called after every step.
 fn run<M: Memory>(
     prog: Program,
     stdout: impl GcWrite,
     stderr: impl GcWrite,
-) -> Result<!, TerminationInfo> {
-    let res: NdResult<!> = try {
-        let mut machine = Machine::<M>::new(prog, DynWrite::new(stdout), DynWrite::new(stderr))?;
+    mut scheduler: Option<&mut Scheduler>,
+    mut tracer: Option<&mut Tracer>,
+    backtraces: Option<&mut Vec<Backtrace>>,
+    mut hook: Option<&mut dyn StepHook<M>>,
+) -> Result<TerminationInfo, ReplayDiverged> {
+    assert!(tracer.is_none() || scheduler.is_some(), "tracing needs a scheduler");
+    // Setting up the machine already allocates, so it makes choices, too.
+    let choices = scheduler.as_mut().map(|scheduler| scheduler.setup_choices(prog));
+    let machine = Machine::<M>::new(prog, choices, DynWrite::new(stdout), DynWrite::new(stderr));
+    let mut machine = match machine.get_internal() {
+        Ok(machine) => machine,
+        Err(info) => return Ok(info),
+    };
+    if let Some(scheduler) = &mut scheduler {
+        scheduler.record(&machine);
+        scheduler.check()?;
+    }
+    if let Some(tracer) = &mut tracer {
+        tracer.init(&machine);
+    }
 
-        loop {
-            machine.step()?;
+    loop {
+        // The memory is persistent, so this keeps the state from before the step.
+        let before = hook.is_some().then(|| machine.memory());
+        let res = match &mut scheduler {
+            Some(scheduler) => {
+                let enabled = machine.enabled_threads();
+                if enabled.is_empty() {
+                    // There is nothing to pick; `step` reports the deadlock.
+                    machine.step()
+                } else {
+                    let thread = scheduler.pick(enabled);
+                    scheduler.check()?;
+                    if let Some(tracer) = &mut tracer {
+                        tracer.before_step(&machine, thread);
+                    }
+                    scheduler.prescribe(&mut machine);
+                    let res = machine.step_thread(thread);
+                    scheduler.record(&machine);
+                    if let Some(tracer) = &mut tracer {
+                        tracer.after_step(&machine);
+                    }
+                    res
+                }
+            }
+            None => machine.step(),
+        };
+        if let Some(scheduler) = &scheduler {
+            scheduler.check()?;
+        }
 
-            // Drops everything not reachable from `machine`.
-            mark_and_sweep(&machine);
+        let res = res.get_internal();
+        if let (Some(hook), Some(before)) = (&mut hook, before) {
+            hook.after_step(&machine, before, res.as_ref().err());
+        }
+        if let Err(info) = res {
+            if let Some(backtraces) = backtraces {
+                *backtraces = get_backtraces(&machine, &info);
+            }
+            return Ok(info);
         }
+
+        // Drops everything not reachable from `machine`, including the memory before the step.
+        mark_and_sweep(&machine);
+    }
+}
+
+fn get_backtraces<M: Memory>(machine: &Machine<M>, info: &TerminationInfo) -> Vec<Backtrace> {
+    // On a deadlock, no thread is to blame.
+    let active = match info {
+        TerminationInfo::Deadlock => None,
+        _ => Some(machine.active_thread_id()),
     };
+    let mut backtraces = Vec::new();
+    for thread in Int::ZERO..machine.thread_count() {
+        let frames = thread_frames(machine, thread);
+        if !frames.is_empty() {
+            let active = active == Some(thread);
+            backtraces.push(Backtrace { thread: thread_u32(thread), active, frames });
+        }
+    }
+    backtraces
+}
+
+/// The ID of a thread as tools report it.
+pub(crate) fn thread_u32(thread: ThreadId) -> u32 {
+    u32::try_from(thread.try_to_usize().unwrap()).unwrap()
+}
 
-    // Extract the TerminationInfo from the `NdResult<!>`.
-    res.get_internal()
+/// The frames of the given thread, innermost frame first.
+pub(crate) fn thread_frames<M: Memory>(machine: &Machine<M>, thread: ThreadId) -> Vec<Frame> {
+    let mut frames: Vec<Frame> = machine
+        .thread_stack(thread)
+        .iter()
+        .map(|frame| {
+            Frame { func: frame.func, block: frame.block, stmt: frame.stmt.try_to_usize().unwrap() }
+        })
+        .collect();
+    frames.reverse();
+    frames
 }
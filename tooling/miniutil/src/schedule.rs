@@ -0,0 +1,296 @@
+//! Reproducible execution of concurrent programs.
+//!
+//! By default, the Abstract Machine makes its non-deterministic choices at random, so concurrent
+//! programs can behave differently from run to run. With a `Schedule`, every choice is instead
+//! made by a seeded pseudo-random generator, or taken from a trace recorded by an earlier run.
+//! That covers the thread that executes each step as well as all choices the machine makes with
+//! `choose`: allocation addresses, which store a weak-memory load reads from, NaN results, and
+//! which blocked thread acquires a released lock.
+
+use std::collections::VecDeque;
+
+use crate::*;
+
+/// Decides the non-deterministic choices of a run.
+#[derive(Clone, Debug)]
+pub enum Schedule {
+    /// Make the choices pseudo-randomly, using the given seed.
+    Seeded(u64),
+    /// Make the choices that were made in the run that produced this trace.
+    Replay(ScheduleTrace),
+}
+
+/// A non-deterministic choice made during a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Choice {
+    /// The thread that executes the next step.
+    Thread(u32),
+    /// A choice the machine made with `choose` during a step, as the index of the chosen value
+    /// (see `MadeChoice::index`).
+    Machine(ChoiceKind, Int),
+}
+
+/// The choices made during a run, in order.
+///
+/// The thread of a step is only recorded if more than one thread was enabled, since otherwise
+/// there is nothing to choose from. The choices the machine makes are all recorded, so that a
+/// replay can prescribe them in the order in which they are made.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScheduleTrace {
+    pub choices: Vec<Choice>,
+}
+
+/// A replayed trace does not fit the run: it ends too early, or it makes a choice that is not
+/// possible at that point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayDiverged(pub String);
+
+impl std::fmt::Display for ReplayDiverged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "replay diverged: {}", self.0)
+    }
+}
+
+const TRACE_HEADER: &str = "# MiniRust schedule trace: one non-deterministic choice per line";
+
+fn kind_name(kind: ChoiceKind) -> &'static str {
+    match kind {
+        ChoiceKind::Address => "address",
+        ChoiceKind::ReadFrom => "read-from",
+        ChoiceKind::NanResult => "nan",
+        ChoiceKind::LockAcquirer => "lock",
+    }
+}
+
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Choice::Thread(id) => write!(f, "thread {id}"),
+            Choice::Machine(kind, value) => write!(f, "{} {}", kind_name(*kind), value),
+        }
+    }
+}
+
+impl std::fmt::Display for ScheduleTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{TRACE_HEADER}")?;
+        for choice in &self.choices {
+            writeln!(f, "{choice}")?;
+        }
+        Ok(())
+    }
+}
+
+impl ScheduleTrace {
+    /// Parse a trace in the format written by its `Display` impl.
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn parse(src: &str) -> Result<ScheduleTrace, String> {
+        let mut choices = Vec::new();
+        for (i, line) in src.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let choice = parse_choice(line)
+                .ok_or_else(|| format!("line {}: expected a choice, found `{line}`", i + 1))?;
+            choices.push(choice);
+        }
+        Ok(ScheduleTrace { choices })
+    }
+}
+
+fn parse_choice(line: &str) -> Option<Choice> {
+    let (kind, value) = line.split_once(' ')?;
+    let value = value.trim();
+    if kind == "thread" {
+        return Some(Choice::Thread(value.parse().ok()?));
+    }
+    let kind = [
+        ChoiceKind::Address,
+        ChoiceKind::ReadFrom,
+        ChoiceKind::NanResult,
+        ChoiceKind::LockAcquirer,
+    ]
+    .into_iter()
+    .find(|&k| kind_name(k) == kind)?;
+    let value: u128 = value.parse().ok()?;
+    Some(Choice::Machine(kind, Int::from(value)))
+}
+
+/// Makes the choices of a run according to a `Schedule`, and records them.
+///
+/// The machine makes its choices with `choose` during a step, so they cannot be made one at a
+/// time: before each step, the `Scheduler` prescribes the indices of the next choices, and after
+/// the step, it records the choices that were actually made.
+pub(crate) struct Scheduler {
+    schedule: Schedule,
+    /// The pseudo-random generator for `Schedule::Seeded`.
+    rng: SplitMix64,
+    /// For `Schedule::Seeded`, the indices that were prescribed but not used yet.
+    /// They are prescribed again before the next step, so that retrying a step is not needed.
+    pending: VecDeque<Int>,
+    trace: ScheduleTrace,
+    /// Set once a replayed trace did not fit the run.
+    diverged: Option<ReplayDiverged>,
+}
+
+impl Scheduler {
+    /// How many choices are prescribed for each step in `Schedule::Seeded`.
+    /// Choices beyond these start at index 0, see `Choices::prescribe`.
+    const PRESCRIBED: usize = 64;
+
+    pub(crate) fn new(schedule: Schedule) -> Self {
+        let rng = match schedule {
+            Schedule::Seeded(seed) => seed,
+            Schedule::Replay(_) => 0,
+        };
+        Scheduler {
+            schedule,
+            rng: SplitMix64::new(rng),
+            pending: VecDeque::new(),
+            trace: ScheduleTrace::default(),
+            diverged: None,
+        }
+    }
+
+    /// The choices to prescribe for setting up the machine for `prog`.
+    /// This allocates every global, function and vtable, which makes one choice each.
+    pub(crate) fn setup_choices(&mut self, prog: Program) -> List<Int> {
+        self.prescription(prog.globals.len() + prog.functions.len() + prog.vtables.len())
+    }
+
+    /// Prescribe the choices of the next step.
+    pub(crate) fn prescribe<M: Memory>(&mut self, machine: &mut Machine<M>) {
+        machine.prescribe_choices(self.prescription(Int::from(Self::PRESCRIBED)));
+    }
+
+    /// The indices of the next `count` choices. When replaying, this is all the choices the trace
+    /// makes before it picks a thread again, whatever `count` is.
+    fn prescription(&mut self, count: Int) -> List<Int> {
+        let count = count.try_to_usize().unwrap();
+        match self.schedule {
+            Schedule::Seeded(_) => {
+                while self.pending.len() < count {
+                    // The number of options can exceed `u64`, so we combine two random numbers.
+                    let random =
+                        (u128::from(self.rng.next_u64()) << 64) | u128::from(self.rng.next_u64());
+                    self.pending.push_back(Int::from(random));
+                }
+                self.pending.iter().copied().take(count).collect()
+            }
+            Schedule::Replay(ref trace) =>
+                trace.choices[self.trace.choices.len()..]
+                    .iter()
+                    .map_while(|choice| {
+                        match choice {
+                            Choice::Machine(_, index) => Some(*index),
+                            Choice::Thread(_) => None,
+                        }
+                    })
+                    .collect(),
+        }
+    }
+
+    /// Record the choices that the machine made during the most recent step, or while setting up.
+    /// If they do not fit the replayed trace, `check` reports the divergence.
+    pub(crate) fn record<M: Memory>(&mut self, machine: &Machine<M>) {
+        for made in machine.step_choices().iter() {
+            let choice = Choice::Machine(made.kind, made.index);
+            match self.schedule {
+                Schedule::Seeded(_) =>
+                    if made.prescribed {
+                        self.pending.pop_front();
+                    },
+                Schedule::Replay(_) =>
+                    match self.replayed() {
+                        Some(replayed) if replayed == choice => {}
+                        Some(replayed) => self.diverge(format!(
+                            "the trace has `{replayed}`, but the run makes the choice `{choice}`"
+                        )),
+                        None => {}
+                    },
+            }
+            self.trace.choices.push(choice);
+        }
+    }
+
+    /// Pick one of the given, non-empty list of enabled threads.
+    /// If the replayed trace diverges, this picks the first thread, and `check` reports the
+    /// divergence.
+    pub(crate) fn pick(&mut self, enabled: List<ThreadId>) -> ThreadId {
+        let enabled: Vec<ThreadId> = enabled.iter().collect();
+        if let &[id] = enabled.as_slice() {
+            return id;
+        }
+
+        let choice = match self.schedule {
+            Schedule::Seeded(_) =>
+                Some(enabled[(self.rng.next_u64() % enabled.len() as u64) as usize]),
+            Schedule::Replay(_) =>
+                match self.replayed() {
+                    Some(Choice::Thread(id)) if enabled.contains(&Int::from(id)) =>
+                        Some(Int::from(id)),
+                    Some(choice) => {
+                        self.diverge(format!(
+                            "the trace has `{choice}`, but the run picks one of the threads \
+                        {enabled:?}"
+                        ));
+                        None
+                    }
+                    None => None,
+                },
+        };
+        let choice = choice.unwrap_or(enabled[0]);
+        let id = u32::try_from(choice.try_to_usize().unwrap()).unwrap();
+        self.trace.choices.push(Choice::Thread(id));
+        choice
+    }
+
+    /// Whether the replayed trace still fits the run.
+    pub(crate) fn check(&self) -> Result<(), ReplayDiverged> {
+        match &self.diverged {
+            Some(diverged) => Err(diverged.clone()),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn into_trace(self) -> ScheduleTrace {
+        self.trace
+    }
+
+    /// The next choice of the replayed trace. Records a divergence if the trace has ended.
+    fn replayed(&mut self) -> Option<Choice> {
+        let Schedule::Replay(ref trace) = self.schedule else { unreachable!() };
+        let step = self.trace.choices.len();
+        let choice = trace.choices.get(step).copied();
+        if choice.is_none() {
+            self.diverge(format!("the trace ends after {step} choices"));
+        }
+        choice
+    }
+
+    /// Record the first divergence, which is the one worth reporting.
+    fn diverge(&mut self, msg: String) {
+        if self.diverged.is_none() {
+            self.diverged = Some(ReplayDiverged(msg));
+        }
+    }
+}
+
+/// SplitMix64. We implement this ourselves so that a seed means the same thing everywhere.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        SplitMix64(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,167 @@
+//! Step-by-step traces of executions.
+//!
+//! A trace has a line for each executed statement or terminator, saying which thread executed it
+//! and where it is in the program, followed by an indented line for each memory operation of that
+//! step. The values computed by a step show up as the bytes it loads and stores.
+//! For example:
+//!
+//! ```text
+//! step 4: thread 0, f0 bb1 stmt 0: _1 = 4_u32;
+//!   store alloc3+0: 04 00 00 00
+//! ```
+//!
+//! Addresses are shown relative to the allocation they point into, with allocations numbered in
+//! the order they were created, so that traces of executions with different addresses can be
+//! compared with `diff`. That includes pointers that are loaded and stored: their bytes are shown
+//! as `ptr(alloc3+8)`. Uninitialized bytes are shown as `__`, and bytes with provenance that do not
+//! form a whole pointer are shown as `**`, since their value is part of an address.
+
+use std::io::Write;
+
+use crate::fmt::StatementFormatter;
+use crate::*;
+
+/// An allocation that has been reported by a memory event.
+struct Allocation {
+    addr: usize,
+    size: usize,
+}
+
+pub(crate) struct Tracer {
+    out: Box<dyn Write>,
+    prog: Program,
+    formatter: StatementFormatter,
+    /// All allocations so far, in the order they were created.
+    /// The index of an allocation is its number.
+    allocations: Vec<Allocation>,
+    /// The number of the next step.
+    step: usize,
+}
+
+impl Tracer {
+    pub(crate) fn new(prog: Program, out: impl Write + 'static) -> Self {
+        let formatter = StatementFormatter::new(prog);
+        Tracer { out: Box::new(out), prog, formatter, allocations: Vec::new(), step: 0 }
+    }
+
+    /// Log the memory operations that set up the machine.
+    pub(crate) fn init<M: Memory>(&mut self, machine: &Machine<M>) {
+        self.line("initialization:".to_string());
+        self.events(machine);
+    }
+
+    /// Log the statement or terminator that the given thread is about to execute.
+    pub(crate) fn before_step<M: Memory>(&mut self, machine: &Machine<M>, thread: ThreadId) {
+        let frame = machine.thread_stack(thread).last().unwrap();
+        let block = self.prog.functions.index_at(frame.func).blocks.index_at(frame.block);
+        let stmt = frame.stmt;
+        let (position, text) = if stmt < block.statements.len() {
+            (format!("stmt {stmt}"), self.formatter.fmt_statement(block.statements.index_at(stmt)))
+        } else {
+            ("terminator".to_string(), self.formatter.fmt_terminator(block.terminator))
+        };
+        let (fn_id, bb_id) = (frame.func.0.get_internal(), frame.block.0.get_internal());
+        let step = self.step;
+        self.line(format!("step {step}: thread {thread}, f{fn_id} bb{bb_id} {position}: {text}"));
+        self.step += 1;
+    }
+
+    /// Log the memory operations of the step that was just executed.
+    pub(crate) fn after_step<M: Memory>(&mut self, machine: &Machine<M>) {
+        self.events(machine);
+    }
+
+    /// Log how the execution ended.
+    pub(crate) fn end(&mut self, info: &TerminationInfo) {
+        let end = match info {
+            TerminationInfo::MachineStop => "stop".to_string(),
+            TerminationInfo::Ub(ub) => format!("UB: {ub}"),
+            TerminationInfo::Abort(msg) => format!("Panic: {}", msg.get_internal()),
+            TerminationInfo::IllFormed(err) => format!("ill-formed: {}", err.msg.get_internal()),
+            TerminationInfo::Deadlock => "deadlock".to_string(),
+            TerminationInfo::MemoryLeak => "memory leak".to_string(),
+        };
+        self.line(format!("end: {end}"));
+    }
+
+    fn events<M: Memory>(&mut self, machine: &Machine<M>) {
+        for event in machine.step_events().iter() {
+            let line = match event {
+                MemoryEvent::Allocate { addr, size, align, kind } => {
+                    let id = self.allocations.len();
+                    let (size, align) = (size.bytes(), align.bytes());
+                    self.allocations.push(Allocation {
+                        addr: addr.try_to_usize().unwrap(),
+                        size: size.try_to_usize().unwrap(),
+                    });
+                    format!("allocate alloc{id}: size {size}, align {align}, {kind:?}")
+                }
+                MemoryEvent::Deallocate { addr, .. } =>
+                    format!("deallocate {}", self.fmt_addr(addr)),
+                MemoryEvent::Load { addr, bytes, atomicity } => {
+                    let atomic = fmt_atomicity(atomicity);
+                    format!("{atomic}load {}: {}", self.fmt_addr(addr), self.fmt_bytes::<M>(bytes))
+                }
+                MemoryEvent::Store { addr, bytes, atomicity } => {
+                    let atomic = fmt_atomicity(atomicity);
+                    format!("{atomic}store {}: {}", self.fmt_addr(addr), self.fmt_bytes::<M>(bytes))
+                }
+            };
+            self.line(format!("  {line}"));
+        }
+    }
+
+    /// Show an address relative to the most recent allocation containing it.
+    fn fmt_addr(&self, addr: Address) -> String {
+        let addr = addr.try_to_usize().unwrap();
+        let found = self.allocations.iter().enumerate().rev().find(|(_, alloc)| {
+            alloc.addr <= addr && (addr < alloc.addr + alloc.size || addr == alloc.addr)
+        });
+        match found {
+            Some((id, alloc)) => format!("alloc{id}+{}", addr - alloc.addr),
+            None => format!("{addr:#x}"),
+        }
+    }
+
+    /// Show bytes in hex, except for pointers, whose address is shown like `fmt_addr` does.
+    fn fmt_bytes<M: Memory>(&self, bytes: List<AbstractByte<M::Provenance>>) -> String {
+        let bytes: Vec<AbstractByte<M::Provenance>> = bytes.iter().collect();
+        let ptr_size = M::T::PTR_SIZE.bytes().try_to_usize().unwrap();
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            // A pointer is a run of initialized bytes that all have the same provenance.
+            let ptr = bytes.get(i..i + ptr_size).filter(|run| {
+                run[0].provenance().is_some()
+                    && run
+                        .iter()
+                        .all(|b| b.data().is_some() && b.provenance() == run[0].provenance())
+            });
+            if let Some(run) = ptr {
+                let data: List<u8> = run.iter().map(|b| b.data().unwrap()).collect();
+                let addr = M::T::ENDIANNESS.decode(Unsigned, data);
+                out.push(format!("ptr({})", self.fmt_addr(addr)));
+                i += ptr_size;
+                continue;
+            }
+            out.push(match bytes[i] {
+                AbstractByte::Uninit => "__".to_string(),
+                AbstractByte::Init(val, None) => format!("{val:02x}"),
+                AbstractByte::Init(_, Some(_)) => "**".to_string(),
+            });
+            i += 1;
+        }
+        out.join(" ")
+    }
+
+    fn line(&mut self, line: String) {
+        writeln!(self.out, "{line}").expect("could not write trace");
+    }
+}
+
+fn fmt_atomicity(atomicity: Atomicity) -> &'static str {
+    match atomicity {
+        Atomicity::Atomic => "atomic ",
+        Atomicity::None => "",
+    }
+}